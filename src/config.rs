@@ -1,4 +1,7 @@
 mod colors;
+mod command;
+mod date_format;
+mod file_worker_config;
 mod keycode;
 mod logger;
 mod styles;
@@ -6,21 +9,30 @@ mod text_modifier;
 mod text_style;
 mod todo_config;
 
+pub use self::command::{Command, CountArgs};
+pub use self::date_format::DateFormat;
+pub use self::file_worker_config::FileWorkerConfig;
 pub use self::keycode::KeyCodeDef;
 pub use self::logger::Logger;
 pub use self::styles::Styles;
 pub use self::styles::StylesValue;
+pub use self::text_modifier::TextModifier;
 pub use self::text_style::TextStyle;
 pub use self::text_style::TextStyleList;
 pub use self::todo_config::ToDoConfig;
 
-use self::colors::opt_color;
+use self::colors::{opt_color, parse_color};
 use crate::{
+    file_worker::{ArchiveMode, LineEnding, ParseErrorPolicy, SavePolicy},
     layout::widget::widget_type::WidgetType,
-    todo::task_list::TaskSort,
-    ui::{EventHandlerUI, UIEvent},
+    todo::{
+        hooks::{HookFormat, HookPaths},
+        task_list::TaskSort,
+    },
+    ui::{EventHandlerUI, InputPosition, UIEvent},
 };
-use clap::{arg, CommandFactory, Parser};
+use chrono::Weekday;
+use clap::{CommandFactory, Parser};
 
 use clap_complete::{generate, shells::Bash};
 use crossterm::event::KeyCode;
@@ -30,7 +42,7 @@ use std::{
     collections::HashMap,
     env::var,
     error::Error,
-    fs::File,
+    fs::{self, File},
     io::{self, Read, Write},
     num::ParseIntError,
     path::PathBuf,
@@ -43,10 +55,11 @@ use tui::style::Color;
 #[command(author, version, about, long_about = None)]
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub struct Config {
-    /// Path to configuration file.
+    /// Path to configuration file, can be used multiple times. Later files
+    /// override earlier ones.
     #[serde(skip)]
     #[arg(short, long, value_name = "FILE")]
-    config_path: Option<PathBuf>,
+    config_path: Vec<PathBuf>,
 
     /// Generate autocomplete script to given file path.
     #[serde(skip)]
@@ -66,8 +79,18 @@ pub struct Config {
     #[arg(long, value_name = "FILE", help_heading = "export")]
     export_default_config: Option<PathBuf>,
 
+    /// Check the configuration files and the layout and exit.
+    #[serde(skip)]
+    #[arg(long)]
+    check_layout: bool,
+
+    /// Command executed without the user interface.
+    #[serde(skip)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[serde(default, with = "opt_color")]
-    #[arg(long, value_name = "COLOR")]
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
     active_color: Option<Color>,
 
     /// Widget that will be active after start of the application.
@@ -78,18 +101,104 @@ pub struct Config {
     #[arg(short = 'T', long, value_name = "STRING")]
     window_title: Option<String>,
 
+    /// Show header bar with the current file name, task counts and mode.
+    #[arg(long, value_name = "FLAG")]
+    header: Option<bool>,
+
+    /// Position of the input field, on the top or the bottom of the window.
+    #[arg(long, value_name = "INPUT_POSITION")]
+    input_position: Option<InputPosition>,
+
+    /// Maximal number of rows the input field grows to when the text does not fit in one row.
+    #[arg(long, value_name = "NUMBER")]
+    input_max_height: Option<u16>,
+
+    /// Maximal width of the content, wider windows center it with margins.
+    #[arg(long, value_name = "NUMBER")]
+    max_width: Option<u16>,
+
     #[arg(short, long, value_name = "STRING")]
     todo_path: Option<String>,
 
     #[arg(short, long, value_name = "STRING")]
     archive_path: Option<String>,
 
+    /// Where done tasks are archived: Inline, SiblingDone (done.txt next
+    /// to the todo file) or a path to the archive file.
+    #[arg(long, value_name = "MODE")]
+    archive_mode: Option<ArchiveMode>,
+
+    /// Named todo files that can be switched at runtime.
+    #[clap(skip)]
+    todo_files: Option<Vec<(String, PathBuf)>>,
+
+    #[arg(long, value_name = "LINE_ENDING")]
+    line_ending: Option<LineEnding>,
+
+    /// Number of blank lines between tasks in the saved todo file.
+    #[arg(long, value_name = "NUMBER")]
+    task_spacing: Option<usize>,
+
+    /// What to do with lines that cannot be parsed as tasks, see `ParseErrorPolicy`.
+    #[arg(long, value_name = "PARSE_ERROR_POLICY")]
+    on_parse_error: Option<ParseErrorPolicy>,
+
+    /// URL template for hashtags that reference an issue (e.g. `#123`).
+    /// The `{n}` in the template is replaced by the issue number.
+    #[arg(long, value_name = "URL")]
+    issue_url_template: Option<String>,
+
+    /// Symbols of priorities used by the `$priority_symbol` placeholder.
+    #[clap(skip)]
+    priority_symbols: Option<HashMap<String, String>>,
+
+    /// Strftime pattern used to display dates, e.g. `%d.%m.%Y`.
+    #[arg(long, value_name = "FORMAT")]
+    date_format: Option<DateFormat>,
+
+    /// First day of the week used by the due this week filter.
+    #[arg(long, value_name = "DAY")]
+    week_start: Option<Weekday>,
+
+    /// Scripts executed on task events.
+    #[clap(skip)]
+    hook_paths: Option<HookPaths>,
+
+    /// Format of the task passed to hook scripts.
+    #[arg(long, value_name = "HOOK_FORMAT")]
+    hook_format: Option<HookFormat>,
+
     #[arg(long)] // TODO value type
     priority_colors: Option<TextStyleList>,
 
     #[arg(short, long, value_name = "FLAG")]
     wrap_preview: Option<bool>,
 
+    /// Wrap long tasks in task lists over more rows.
+    #[arg(long, value_name = "FLAG")]
+    wrap_list: Option<bool>,
+
+    /// Symbol displayed before the selected task.
+    #[arg(long, value_name = "STRING")]
+    selection_symbol: Option<String>,
+
+    /// Highlight the whole row of the selected task, not just its text.
+    #[arg(long, value_name = "FLAG")]
+    highlight_full_row: Option<bool>,
+
+    /// Show a bar colored by the priority on the left of each task.
+    #[arg(long, value_name = "FLAG")]
+    priority_gutter: Option<bool>,
+
+    /// Style categories of tasks that are selected by filters
+    /// with the category select style.
+    #[arg(long, value_name = "FLAG")]
+    highlight_filter_tokens: Option<bool>,
+
+    /// Include categories of done tasks in category lists.
+    #[arg(long, value_name = "FLAG")]
+    use_done: Option<bool>,
+
     #[arg(long, value_name = "TEXT_STYLE")]
     list_active_color: Option<TextStyle>,
 
@@ -102,33 +211,94 @@ pub struct Config {
     #[arg(short = 'd', long, value_parser = parse_duration, value_name = "DURATION")]
     autosave_duration: Option<Duration>,
 
+    /// Maximal random time added to each autosave interval.
+    #[arg(long, value_parser = parse_duration, value_name = "DURATION")]
+    autosave_jitter: Option<Duration>,
+
+    /// When changes are saved, see `SavePolicy`.
+    #[arg(long, value_name = "SAVE_POLICY")]
+    save_policy: Option<SavePolicy>,
+
+    /// Ask before quitting if there are unsaved changes.
+    #[arg(long, value_name = "FLAG")]
+    confirm_quit_unsaved: Option<bool>,
+
+    /// Stay in the insert mode after a new task is added, so more tasks
+    /// can be added one after another.
+    #[arg(long, value_name = "FLAG")]
+    stay_in_insert: Option<bool>,
+
     #[arg(long, value_name = "FILE", help_heading = "export")]
     save_state_path: Option<PathBuf>,
 
-    #[arg(long, value_name = "FILE")]
+    /// Log file path, can be also set by TODOTXT_TUI_LOGFILE.
+    #[arg(long, value_name = "FILE", env = "TODOTXT_TUI_LOGFILE")]
     log_file: Option<PathBuf>,
 
     #[arg(long, value_name = "FILE")]
     log_format: Option<String>,
 
-    #[arg(long, value_name = "LOG_LEVEL")]
+    /// Log level (off, error, warn, info, debug, trace),
+    /// can be also set by TODOTXT_TUI_LOGLEVEL.
+    #[arg(long, value_parser = parse_log_level, value_name = "LOG_LEVEL", env = "TODOTXT_TUI_LOGLEVEL")]
     log_level: Option<LevelFilter>,
 
     #[arg(short, long, value_name = "FLAG")]
     file_watcher: Option<bool>,
 
+    /// Time window in milliseconds in which file changes are merged
+    /// into a single reload.
+    #[arg(long, value_name = "MILLISECONDS")]
+    watcher_debounce_ms: Option<u64>,
+
+    /// Commit the todo files after every save if they are in a git repository.
+    #[arg(long, value_name = "FLAG")]
+    git_autocommit: Option<bool>,
+
     #[arg(short = 'L', long, value_parser = parse_duration, value_name = "DURATION")]
     list_refresh_rate: Option<Duration>,
 
     #[arg(short, long, value_name = "NUMBER")]
     list_shift: Option<usize>,
 
+    /// Show active filters as chips in the border of task lists.
+    #[arg(long, value_name = "FLAG")]
+    filter_chips: Option<bool>,
+
     #[arg(long, value_name = "TASK_SORT")]
     pending_sort: Option<TaskSort>,
 
     #[arg(long, value_name = "TASK_SORT")]
     done_sort: Option<TaskSort>,
 
+    /// Sorting used for tasks that are equal by the primary sorting.
+    #[arg(long, value_name = "TASK_SORT")]
+    sort_tiebreak: Option<TaskSort>,
+
+    /// Sort tasks without a project first by the `ProjectThenPriority` sorting.
+    #[arg(long, value_name = "FLAG")]
+    no_project_first: Option<bool>,
+
+    /// Keep completed tasks in the pending list until the next save.
+    #[arg(long, value_name = "FLAG")]
+    inline_done: Option<bool>,
+
+    /// Set the finish date of tasks when they are completed.
+    #[arg(long, value_name = "FLAG")]
+    set_final_date: Option<bool>,
+
+    /// Move the priority of completed tasks to the `pri:` tag and back when reopened.
+    #[arg(long, value_name = "FLAG")]
+    preserve_priority_on_done: Option<bool>,
+
+    /// Search matches only whole words.
+    #[arg(long, value_name = "FLAG")]
+    search_word_boundary: Option<bool>,
+
+    /// New tasks get the category that is the only selected filter of its kind.
+    #[arg(long, value_name = "FLAG")]
+    inherit_active_category: Option<bool>,
+
     #[arg(short, long, value_name = "STRING")]
     preview_format: Option<String>,
 
@@ -147,6 +317,9 @@ pub struct Config {
     #[clap(skip)]
     window_keybind: Option<EventHandlerUI>,
 
+    #[clap(skip)]
+    preview_keybind: Option<EventHandlerUI>,
+
     #[arg(long, value_name = "TEXT_STYLE")]
     category_style: Option<TextStyle>,
 
@@ -165,6 +338,25 @@ pub struct Config {
     #[arg(long, value_name = "TEXT_STYLE")]
     hashtags_style: Option<TextStyle>,
 
+    #[arg(long, value_name = "TEXT_STYLE")]
+    now_style: Option<TextStyle>,
+
+    /// Style of completed tasks in task lists.
+    #[arg(long, value_name = "TEXT_STYLE")]
+    done_style: Option<TextStyle>,
+
+    /// Style of search matches.
+    #[arg(long, value_name = "TEXT_STYLE")]
+    highlight_style: Option<TextStyle>,
+
+    /// Style of the search match in the selected task.
+    #[arg(long, value_name = "TEXT_STYLE")]
+    current_match_style: Option<TextStyle>,
+
+    /// Styles of task progress, each style is used from its minimal percentage.
+    #[clap(skip)]
+    progress_styles: Option<Vec<(u8, TextStyle)>>,
+
     #[clap(skip)]
     custom_category_style: Option<HashMap<String, TextStyle>>,
 }
@@ -189,11 +381,29 @@ impl Config {
         Ok(Self::load_from_buffer(File::open(path)?))
     }
 
+    /// Loads the configuration files given by the arguments, or the default
+    /// configuration file if no file is given. Later files override earlier ones.
     pub fn load_config(&self) -> io::Result<Self> {
-        match &self.config_path {
-            Some(path) => Config::load(path),
-            None => Self::load_default(),
+        if self.config_path.is_empty() {
+            return Self::load_default();
         }
+        let configs = self
+            .config_path
+            .iter()
+            .map(Config::load)
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::layered(configs))
+    }
+
+    /// Merges the configurations in order, later configurations override
+    /// earlier ones.
+    pub fn layered<I>(configs: I) -> Self
+    where
+        I: IntoIterator<Item = Config>,
+    {
+        configs
+            .into_iter()
+            .fold(Config::default(), |merged, config| config.merge(merged))
     }
 
     /// Returns the default configuration file path based on environment variables.
@@ -204,13 +414,60 @@ impl Config {
     ///
     /// A `Result` containing the default configuration file path (`Ok`) or an error (`Err`) if the path cannot be determined.
     pub fn load_default() -> io::Result<Self> {
+        Ok(Self::load_from_buffer(File::open(Self::default_path())?))
+    }
+
+    /// Returns the path of the default configuration file based on the
+    /// XDG_CONFIG_HOME and HOME environment variables.
+    fn default_path() -> PathBuf {
         const CONFIG_FOLDER: &str = "/.config/";
         const CONFIG_NAME: &str = "todo-tui.toml";
         let path = var("XDG_CONFIG_HOME")
             .or_else(|_| var("HOME").map(|home| format!("{home}{CONFIG_FOLDER}")))
             .unwrap_or(String::from("~") + CONFIG_FOLDER)
             + CONFIG_NAME;
-        Ok(Self::load_from_buffer(File::open(path)?))
+        PathBuf::from(path)
+    }
+
+    /// Checks that the configuration files loaded by `load_config` can be parsed.
+    /// Unlike loading, errors are returned instead of falling back to defaults.
+    /// A missing default configuration file is not an error.
+    pub fn check_files(&self) -> Result<(), Box<dyn Error>> {
+        let paths = if self.config_path.is_empty() {
+            let path = Self::default_path();
+            if !path.exists() {
+                return Ok(());
+            }
+            vec![path]
+        } else {
+            self.config_path.clone()
+        };
+        for path in paths {
+            let error = |e: &dyn Error| format!("{}: {}", path.display(), e);
+            let content = fs::read_to_string(&path).map_err(|e| error(&e))?;
+            toml::from_str::<Config>(&content).map_err(|e| error(&e))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the folder for the state of the application, e.g. the log file.
+    ///
+    /// The folder is `todotxt-tui` in XDG_STATE_HOME, or in `~/.local/state`
+    /// if XDG_STATE_HOME is not set. `None` if neither XDG_STATE_HOME nor HOME
+    /// is set.
+    pub fn state_folder() -> Option<PathBuf> {
+        Self::state_folder_from(var("XDG_STATE_HOME").ok(), var("HOME").ok())
+    }
+
+    /// Returns the state folder for the values of XDG_STATE_HOME and HOME.
+    fn state_folder_from(xdg_state_home: Option<String>, home: Option<String>) -> Option<PathBuf> {
+        const STATE_NAME: &str = "todotxt-tui";
+        let not_empty = |path: &String| !path.is_empty();
+        match (xdg_state_home.filter(not_empty), home.filter(not_empty)) {
+            (Some(path), _) => Some(PathBuf::from(path).join(STATE_NAME)),
+            (None, Some(home)) => Some(PathBuf::from(home).join(".local/state").join(STATE_NAME)),
+            (None, None) => None,
+        }
     }
 
     /// Loads a configuration from a provided reader.
@@ -242,42 +499,95 @@ impl Config {
 
     pub fn merge(self, other: Config) -> Self {
         Self {
-            config_path: self.config_path.or(other.config_path),
+            config_path: if self.config_path.is_empty() {
+                other.config_path
+            } else {
+                self.config_path
+            },
             generate_autocomplete: self.generate_autocomplete.or(other.generate_autocomplete),
             export_config: self.export_config.or(other.export_config),
             export_default_config: self.export_default_config.or(other.export_default_config),
+            check_layout: self.check_layout || other.check_layout,
+            command: self.command.or(other.command),
             active_color: self.active_color.or(other.active_color),
             init_widget: self.init_widget.or(other.init_widget),
             window_title: self.window_title.or(other.window_title),
+            header: self.header.or(other.header),
+            input_position: self.input_position.or(other.input_position),
+            input_max_height: self.input_max_height.or(other.input_max_height),
+            max_width: self.max_width.or(other.max_width),
             todo_path: self.todo_path.or(other.todo_path),
             archive_path: self.archive_path.or(other.archive_path),
+            archive_mode: self.archive_mode.or(other.archive_mode),
+            todo_files: self.todo_files.or(other.todo_files),
+            line_ending: self.line_ending.or(other.line_ending),
+            task_spacing: self.task_spacing.or(other.task_spacing),
+            on_parse_error: self.on_parse_error.or(other.on_parse_error),
+            issue_url_template: self.issue_url_template.or(other.issue_url_template),
+            priority_symbols: self.priority_symbols.or(other.priority_symbols),
+            date_format: self.date_format.or(other.date_format),
+            week_start: self.week_start.or(other.week_start),
+            hook_paths: self.hook_paths.or(other.hook_paths),
+            hook_format: self.hook_format.or(other.hook_format),
             priority_colors: self.priority_colors.or(other.priority_colors),
             wrap_preview: self.wrap_preview.or(other.wrap_preview),
+            wrap_list: self.wrap_list.or(other.wrap_list),
+            selection_symbol: self.selection_symbol.or(other.selection_symbol),
+            highlight_full_row: self.highlight_full_row.or(other.highlight_full_row),
+            priority_gutter: self.priority_gutter.or(other.priority_gutter),
+            highlight_filter_tokens: self
+                .highlight_filter_tokens
+                .or(other.highlight_filter_tokens),
+            use_done: self.use_done.or(other.use_done),
             list_active_color: self.list_active_color.or(other.list_active_color),
             pending_active_color: self.pending_active_color.or(other.pending_active_color),
             done_active_color: self.done_active_color.or(other.done_active_color),
             autosave_duration: self.autosave_duration.or(other.autosave_duration),
+            autosave_jitter: self.autosave_jitter.or(other.autosave_jitter),
+            save_policy: self.save_policy.or(other.save_policy),
+            confirm_quit_unsaved: self.confirm_quit_unsaved.or(other.confirm_quit_unsaved),
+            stay_in_insert: self.stay_in_insert.or(other.stay_in_insert),
             save_state_path: self.save_state_path.or(other.save_state_path),
             log_file: self.log_file.or(other.log_file),
             log_format: self.log_format.or(other.log_format),
             log_level: self.log_level.or(other.log_level),
             file_watcher: self.file_watcher.or(other.file_watcher),
+            watcher_debounce_ms: self.watcher_debounce_ms.or(other.watcher_debounce_ms),
+            git_autocommit: self.git_autocommit.or(other.git_autocommit),
             list_refresh_rate: self.list_refresh_rate.or(other.list_refresh_rate),
             list_shift: self.list_shift.or(other.list_shift),
+            filter_chips: self.filter_chips.or(other.filter_chips),
             pending_sort: self.pending_sort.or(other.pending_sort),
             done_sort: self.done_sort.or(other.done_sort),
+            sort_tiebreak: self.sort_tiebreak.or(other.sort_tiebreak),
+            no_project_first: self.no_project_first.or(other.no_project_first),
+            inline_done: self.inline_done.or(other.inline_done),
+            set_final_date: self.set_final_date.or(other.set_final_date),
+            preserve_priority_on_done: self
+                .preserve_priority_on_done
+                .or(other.preserve_priority_on_done),
+            search_word_boundary: self.search_word_boundary.or(other.search_word_boundary),
+            inherit_active_category: self
+                .inherit_active_category
+                .or(other.inherit_active_category),
             preview_format: self.preview_format.or(other.preview_format),
             layout: self.layout.or(other.layout),
             tasks_keybind: self.tasks_keybind.or(other.tasks_keybind),
             category_keybind: self.category_keybind.or(other.category_keybind),
             list_keybind: self.list_keybind.or(other.list_keybind),
             window_keybind: self.window_keybind.or(other.window_keybind),
+            preview_keybind: self.preview_keybind.or(other.preview_keybind),
             category_style: self.category_style.or(other.category_style),
             category_select_style: self.category_select_style.or(other.category_select_style),
             category_remove_style: self.category_remove_style.or(other.category_remove_style),
             projects_style: self.projects_style.or(other.projects_style),
             contexts_style: self.contexts_style.or(other.contexts_style),
             hashtags_style: self.hashtags_style.or(other.hashtags_style),
+            now_style: self.now_style.or(other.now_style),
+            done_style: self.done_style.or(other.done_style),
+            highlight_style: self.highlight_style.or(other.highlight_style),
+            current_match_style: self.current_match_style.or(other.current_match_style),
+            progress_styles: self.progress_styles.or(other.progress_styles),
             custom_category_style: self.custom_category_style.or(other.custom_category_style),
         }
     }
@@ -288,38 +598,81 @@ impl Config {
             generate_autocomplete: self.generate_autocomplete.clone(),
             export_config: self.export_config.clone(),
             export_default_config: self.export_default_config.clone(),
+            check_layout: self.check_layout,
+            command: self.command.clone(),
             active_color: Some(self.get_active_color()),
             init_widget: Some(self.get_init_widget()),
             window_title: Some(self.get_window_title()),
+            header: Some(self.get_header()),
+            input_position: Some(self.get_input_position()),
+            input_max_height: Some(self.get_input_max_height()),
+            max_width: self.get_max_width(),
             todo_path: Some(self.get_todo_path()),
             archive_path: self.get_archive_path(),
+            archive_mode: Some(self.get_archive_mode()),
+            todo_files: Some(self.get_todo_files()),
+            line_ending: Some(self.get_line_ending()),
+            task_spacing: Some(self.get_task_spacing()),
+            on_parse_error: Some(self.get_on_parse_error()),
+            issue_url_template: self.get_issue_url_template(),
+            priority_symbols: Some(self.get_priority_symbols()),
+            date_format: Some(self.get_date_format()),
+            week_start: Some(self.get_week_start()),
+            hook_paths: Some(self.get_hook_paths()),
+            hook_format: Some(self.get_hook_format()),
             priority_colors: Some(self.get_priority_colors()),
             wrap_preview: Some(self.get_wrap_preview()),
+            wrap_list: Some(self.get_wrap_list()),
+            selection_symbol: Some(self.get_selection_symbol()),
+            highlight_full_row: Some(self.get_highlight_full_row()),
+            priority_gutter: Some(self.get_priority_gutter()),
+            highlight_filter_tokens: Some(self.get_highlight_filter_tokens()),
+            use_done: Some(self.get_use_done()),
             list_active_color: Some(self.get_list_active_color()),
             pending_active_color: Some(self.get_pending_active_color()),
             done_active_color: Some(self.get_done_active_color()),
             autosave_duration: Some(self.get_autosave_duration()),
+            autosave_jitter: Some(self.get_autosave_jitter()),
+            save_policy: Some(self.get_save_policy()),
+            confirm_quit_unsaved: Some(self.get_confirm_quit_unsaved()),
+            stay_in_insert: Some(self.get_stay_in_insert()),
             save_state_path: self.get_save_state_path(),
             log_file: Some(self.get_log_file()),
             log_format: Some(self.get_log_format()),
             log_level: Some(self.get_log_level()),
             file_watcher: Some(self.get_file_watcher()),
+            watcher_debounce_ms: Some(self.get_watcher_debounce_ms()),
+            git_autocommit: Some(self.get_git_autocommit()),
             list_refresh_rate: Some(self.get_list_refresh_rate()),
             list_shift: Some(self.get_list_shift()),
+            filter_chips: Some(self.get_filter_chips()),
             pending_sort: Some(self.get_pending_sort()),
             done_sort: Some(self.get_done_sort()),
+            sort_tiebreak: Some(self.get_sort_tiebreak()),
+            no_project_first: Some(self.get_no_project_first()),
+            inline_done: Some(self.get_inline_done()),
+            set_final_date: Some(self.get_set_final_date()),
+            preserve_priority_on_done: Some(self.get_preserve_priority_on_done()),
+            search_word_boundary: Some(self.get_search_word_boundary()),
+            inherit_active_category: Some(self.get_inherit_active_category()),
             preview_format: Some(self.get_preview_format()),
             layout: Some(self.get_layout()),
             tasks_keybind: Some(self.get_tasks_keybind()),
             category_keybind: Some(self.get_category_keybind()),
             list_keybind: Some(self.get_list_keybind()),
             window_keybind: Some(self.get_window_keybind()),
+            preview_keybind: Some(self.get_preview_keybind()),
             category_style: Some(self.get_category_style()),
             category_select_style: Some(self.get_category_select_style()),
             category_remove_style: Some(self.get_category_remove_style()),
             projects_style: Some(self.get_projects_style()),
             contexts_style: Some(self.get_contexts_style()),
             hashtags_style: Some(self.get_hashtags_style()),
+            now_style: Some(self.get_now_style()),
+            done_style: Some(self.get_done_style()),
+            highlight_style: Some(self.get_highlight_style()),
+            current_match_style: Some(self.get_current_match_style()),
+            progress_styles: Some(self.get_progress_styles()),
             custom_category_style: Some(self.get_custom_category_style()),
         }
     }
@@ -352,6 +705,16 @@ impl Config {
         Ok(ret)
     }
 
+    /// Checks if the configuration and the layout should be only checked.
+    pub fn get_check_layout(&self) -> bool {
+        self.check_layout
+    }
+
+    /// Gets the command that should be executed instead of starting the user interface.
+    pub fn get_command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+
     pub fn get_active_color(&self) -> Color {
         self.active_color.unwrap_or(Color::Red)
     }
@@ -366,6 +729,22 @@ impl Config {
             .unwrap_or(String::from("ToDo tui"))
     }
 
+    pub fn get_header(&self) -> bool {
+        self.header.unwrap_or(false)
+    }
+
+    pub fn get_input_position(&self) -> InputPosition {
+        self.input_position.unwrap_or_default()
+    }
+
+    pub fn get_input_max_height(&self) -> u16 {
+        self.input_max_height.unwrap_or(1)
+    }
+
+    pub fn get_max_width(&self) -> Option<u16> {
+        self.max_width
+    }
+
     pub fn get_todo_path(&self) -> String {
         self.todo_path
             .clone()
@@ -376,6 +755,56 @@ impl Config {
         self.archive_path.clone()
     }
 
+    /// Gets the archive mode. If the mode is not set, the archive path is used
+    /// if it is set, otherwise done tasks are kept in the todo file.
+    pub fn get_archive_mode(&self) -> ArchiveMode {
+        match (&self.archive_mode, &self.archive_path) {
+            (Some(mode), _) => mode.clone(),
+            (None, Some(path)) => ArchiveMode::Explicit(path.clone()),
+            (None, None) => ArchiveMode::Inline,
+        }
+    }
+
+    pub fn get_todo_files(&self) -> Vec<(String, PathBuf)> {
+        self.todo_files.clone().unwrap_or_default()
+    }
+
+    pub fn get_line_ending(&self) -> LineEnding {
+        self.line_ending.unwrap_or_default()
+    }
+
+    pub fn get_task_spacing(&self) -> usize {
+        self.task_spacing.unwrap_or(0)
+    }
+
+    pub fn get_on_parse_error(&self) -> ParseErrorPolicy {
+        self.on_parse_error.unwrap_or_default()
+    }
+
+    pub fn get_issue_url_template(&self) -> Option<String> {
+        self.issue_url_template.clone()
+    }
+
+    pub fn get_priority_symbols(&self) -> HashMap<String, String> {
+        self.priority_symbols.clone().unwrap_or_default()
+    }
+
+    pub fn get_date_format(&self) -> DateFormat {
+        self.date_format.clone().unwrap_or_default()
+    }
+
+    pub fn get_week_start(&self) -> Weekday {
+        self.week_start.unwrap_or(Weekday::Mon)
+    }
+
+    pub fn get_hook_paths(&self) -> HookPaths {
+        self.hook_paths.clone().unwrap_or_default()
+    }
+
+    pub fn get_hook_format(&self) -> HookFormat {
+        self.hook_format.unwrap_or_default()
+    }
+
     fn get_priority_colors(&self) -> TextStyleList {
         self.priority_colors.clone().unwrap_or_default()
     }
@@ -384,6 +813,30 @@ impl Config {
         self.wrap_preview.unwrap_or(true)
     }
 
+    pub fn get_wrap_list(&self) -> bool {
+        self.wrap_list.unwrap_or(false)
+    }
+
+    pub fn get_selection_symbol(&self) -> String {
+        self.selection_symbol.clone().unwrap_or(String::from(">>"))
+    }
+
+    pub fn get_highlight_full_row(&self) -> bool {
+        self.highlight_full_row.unwrap_or(true)
+    }
+
+    pub fn get_priority_gutter(&self) -> bool {
+        self.priority_gutter.unwrap_or(false)
+    }
+
+    pub fn get_highlight_filter_tokens(&self) -> bool {
+        self.highlight_filter_tokens.unwrap_or(false)
+    }
+
+    pub fn get_use_done(&self) -> bool {
+        self.use_done.unwrap_or(false)
+    }
+
     pub fn get_list_active_color(&self) -> TextStyle {
         self.list_active_color
             .unwrap_or(TextStyle::default().bg(Color::LightRed))
@@ -401,12 +854,37 @@ impl Config {
         self.autosave_duration.unwrap_or(Duration::from_secs(900))
     }
 
+    pub fn get_autosave_jitter(&self) -> Duration {
+        self.autosave_jitter.unwrap_or(Duration::ZERO)
+    }
+
+    pub fn get_save_policy(&self) -> SavePolicy {
+        self.save_policy.unwrap_or_default()
+    }
+
+    pub fn get_confirm_quit_unsaved(&self) -> bool {
+        self.confirm_quit_unsaved.unwrap_or(true)
+    }
+
+    pub fn get_stay_in_insert(&self) -> bool {
+        self.stay_in_insert.unwrap_or(false)
+    }
+
+    /// Gets the path of the saved UI state, `state.toml` in the state folder
+    /// by default. `None` if the path is not set and there is no state folder.
     pub fn get_save_state_path(&self) -> Option<PathBuf> {
-        self.save_state_path.clone()
+        self.save_state_path
+            .clone()
+            .or_else(|| Some(Self::state_folder()?.join("state.toml")))
     }
 
+    /// Gets the path of the log file, `log.log` in the state folder by default
+    /// or in the working directory if there is no state folder.
     fn get_log_file(&self) -> PathBuf {
-        self.log_file.clone().unwrap_or(PathBuf::from("log.log"))
+        self.log_file
+            .clone()
+            .or_else(|| Some(Self::state_folder()?.join("log.log")))
+            .unwrap_or(PathBuf::from("log.log"))
     }
 
     fn get_log_format(&self) -> String {
@@ -423,6 +901,14 @@ impl Config {
         self.file_watcher.unwrap_or(true)
     }
 
+    pub fn get_watcher_debounce_ms(&self) -> u64 {
+        self.watcher_debounce_ms.unwrap_or(200)
+    }
+
+    pub fn get_git_autocommit(&self) -> bool {
+        self.git_autocommit.unwrap_or(false)
+    }
+
     pub fn get_list_refresh_rate(&self) -> Duration {
         self.list_refresh_rate.unwrap_or(Duration::from_secs(5))
     }
@@ -431,12 +917,44 @@ impl Config {
         self.list_shift.unwrap_or(4)
     }
 
+    pub fn get_filter_chips(&self) -> bool {
+        self.filter_chips.unwrap_or(false)
+    }
+
     pub fn get_pending_sort(&self) -> TaskSort {
         self.pending_sort.unwrap_or(TaskSort::None)
     }
 
     pub fn get_done_sort(&self) -> TaskSort {
-        self.done_sort.unwrap_or(TaskSort::None)
+        self.done_sort.unwrap_or(TaskSort::FinishDateReverse)
+    }
+
+    pub fn get_sort_tiebreak(&self) -> TaskSort {
+        self.sort_tiebreak.unwrap_or_default()
+    }
+
+    pub fn get_no_project_first(&self) -> bool {
+        self.no_project_first.unwrap_or(false)
+    }
+
+    pub fn get_inline_done(&self) -> bool {
+        self.inline_done.unwrap_or(false)
+    }
+
+    pub fn get_set_final_date(&self) -> bool {
+        self.set_final_date.unwrap_or(false)
+    }
+
+    pub fn get_preserve_priority_on_done(&self) -> bool {
+        self.preserve_priority_on_done.unwrap_or(false)
+    }
+
+    pub fn get_search_word_boundary(&self) -> bool {
+        self.search_word_boundary.unwrap_or(false)
+    }
+
+    pub fn get_inherit_active_category(&self) -> bool {
+        self.inherit_active_category.unwrap_or(false)
     }
 
     pub fn get_preview_format(&self) -> String {
@@ -475,8 +993,19 @@ Link: $link",
         self.tasks_keybind.clone().unwrap_or(EventHandlerUI::new(&[
             (KeyCode::Char('U'), UIEvent::SwapUpItem),
             (KeyCode::Char('D'), UIEvent::SwapDownItem),
+            (KeyCode::Char('Y'), UIEvent::MoveToTop),
+            (KeyCode::Char('B'), UIEvent::MoveToBottom),
             (KeyCode::Char('x'), UIEvent::RemoveItem),
             (KeyCode::Char('d'), UIEvent::MoveItem),
+            (KeyCode::Char('c'), UIEvent::ToggleNow),
+            (KeyCode::Char('b'), UIEvent::TogglePin),
+            (KeyCode::Char('s'), UIEvent::CycleSort),
+            (KeyCode::Char('r'), UIEvent::ToggleSortOrder),
+            (KeyCode::Char('X'), UIEvent::ToggleDone),
+            (KeyCode::Char('+'), UIEvent::PriorityUp),
+            (KeyCode::Char('-'), UIEvent::PriorityDown),
+            (KeyCode::Char('n'), UIEvent::NextSearch),
+            (KeyCode::Char('N'), UIEvent::PrevSearch),
             (KeyCode::Enter, UIEvent::Select),
         ]))
     }
@@ -487,6 +1016,8 @@ Link: $link",
             .unwrap_or(EventHandlerUI::new(&[
                 (KeyCode::Enter, UIEvent::Select),
                 (KeyCode::Backspace, UIEvent::Remove),
+                (KeyCode::Char('D'), UIEvent::ToggleUseDone),
+                (KeyCode::Char('i'), UIEvent::InvertFilters),
             ]))
     }
 
@@ -499,17 +1030,49 @@ Link: $link",
         ]))
     }
 
+    pub fn get_preview_keybind(&self) -> EventHandlerUI {
+        self.preview_keybind
+            .clone()
+            .unwrap_or(EventHandlerUI::new(&[
+                (KeyCode::Char('j'), UIEvent::ListDown),
+                (KeyCode::Char('k'), UIEvent::ListUp),
+                (KeyCode::Char('g'), UIEvent::ListFirst),
+                (KeyCode::Char('G'), UIEvent::ListLast),
+            ]))
+    }
+
     pub fn get_window_keybind(&self) -> EventHandlerUI {
         self.window_keybind.clone().unwrap_or(EventHandlerUI::new(&[
             (KeyCode::Char('q'), UIEvent::Quit),
             (KeyCode::Char('S'), UIEvent::Save),
             (KeyCode::Char('u'), UIEvent::Load),
+            (KeyCode::Char('F'), UIEvent::SwitchFile),
             (KeyCode::Char('H'), UIEvent::MoveLeft),
             (KeyCode::Char('L'), UIEvent::MoveRight),
             (KeyCode::Char('K'), UIEvent::MoveUp),
             (KeyCode::Char('J'), UIEvent::MoveDown),
             (KeyCode::Char('I'), UIEvent::InsertMode),
             (KeyCode::Char('E'), UIEvent::EditMode),
+            (KeyCode::Char('e'), UIEvent::EditSubjectMode),
+            (KeyCode::Char('A'), UIEvent::AppendMode),
+            (KeyCode::Char('/'), UIEvent::SearchMode),
+            (KeyCode::Char('w'), UIEvent::ToggleWrap),
+            (KeyCode::Char('t'), UIEvent::FilterDue),
+            (KeyCode::Char('T'), UIEvent::FilterNoDue),
+            (KeyCode::Char('y'), UIEvent::FilterDueToday),
+            (KeyCode::Char('o'), UIEvent::FilterOverdue),
+            (KeyCode::Char('W'), UIEvent::FilterDueThisWeek),
+            (KeyCode::Char('C'), UIEvent::ClearFilters),
+            (KeyCode::Char('P'), UIEvent::SaveFilterPreset),
+            (KeyCode::Char('p'), UIEvent::ApplyFilterPreset),
+            (KeyCode::Char('v'), UIEvent::ShowDetail),
+            (KeyCode::Char('O'), UIEvent::OpenLink),
+            (KeyCode::Char('M'), UIEvent::CompleteAllFiltered),
+            (KeyCode::Char('1'), UIEvent::FocusList),
+            (KeyCode::Char('2'), UIEvent::FocusDone),
+            (KeyCode::Char('3'), UIEvent::FocusProjects),
+            (KeyCode::Char('4'), UIEvent::FocusContexts),
+            (KeyCode::Char('5'), UIEvent::FocusHashtags),
         ]))
     }
 
@@ -539,6 +1102,40 @@ Link: $link",
         self.hashtags_style.unwrap_or_default()
     }
 
+    fn get_now_style(&self) -> TextStyle {
+        self.now_style
+            .unwrap_or_else(|| TextStyle::default().modifier(TextModifier::Bold))
+    }
+
+    fn get_done_style(&self) -> TextStyle {
+        self.done_style
+            .unwrap_or_else(|| TextStyle::default().modifier(TextModifier::CrossedOut))
+    }
+
+    fn get_highlight_style(&self) -> TextStyle {
+        self.highlight_style
+            .unwrap_or_else(|| TextStyle::default().fg(Color::Black).bg(Color::Yellow))
+    }
+
+    fn get_current_match_style(&self) -> TextStyle {
+        self.current_match_style.unwrap_or_else(|| {
+            TextStyle::default()
+                .fg(Color::Black)
+                .bg(Color::LightRed)
+                .modifier(TextModifier::Bold)
+        })
+    }
+
+    fn get_progress_styles(&self) -> Vec<(u8, TextStyle)> {
+        self.progress_styles.clone().unwrap_or_else(|| {
+            vec![
+                (0, TextStyle::default().fg(Color::Red)),
+                (50, TextStyle::default().fg(Color::Yellow)),
+                (100, TextStyle::default().fg(Color::Green)),
+            ]
+        })
+    }
+
     fn get_custom_category_style(&self) -> HashMap<String, TextStyle> {
         let default = || {
             let mut custom_category_style = HashMap::new();
@@ -556,11 +1153,67 @@ fn parse_duration(arg: &str) -> Result<Duration, ParseIntError> {
     Ok(Duration::from_secs(arg.parse()?))
 }
 
+fn parse_log_level(arg: &str) -> Result<LevelFilter, String> {
+    match arg.trim().to_lowercase().as_str() {
+        "warning" => Ok(LevelFilter::Warn),
+        level => level.parse().map_err(|_| {
+            format!("invalid log level '{arg}', use off, error, warn, info, debug or trace")
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Result;
 
+    #[test]
+    fn test_state_folder() {
+        let home = Some(String::from("/home/user"));
+        assert_eq!(
+            Config::state_folder_from(Some(String::from("/tmp/state")), home.clone()),
+            Some(PathBuf::from("/tmp/state/todotxt-tui"))
+        );
+        assert_eq!(
+            Config::state_folder_from(None, home.clone()),
+            Some(PathBuf::from("/home/user/.local/state/todotxt-tui"))
+        );
+        assert_eq!(
+            Config::state_folder_from(Some(String::new()), home),
+            Some(PathBuf::from("/home/user/.local/state/todotxt-tui"))
+        );
+        assert_eq!(Config::state_folder_from(None, None), None);
+        assert_eq!(
+            Config::state_folder_from(Some(String::new()), Some(String::new())),
+            None
+        );
+
+        let config = Config::load_from_buffer(r#"log_file = "my.log""#.as_bytes());
+        assert_eq!(config.get_log_file(), PathBuf::from("my.log"));
+        let config = Config::load_from_buffer(r#"save_state_path = "state.toml""#.as_bytes());
+        assert_eq!(
+            config.get_save_state_path(),
+            Some(PathBuf::from("state.toml"))
+        );
+        if let Some(folder) = Config::state_folder() {
+            assert_eq!(Config::default().get_log_file(), folder.join("log.log"));
+            assert_eq!(
+                Config::default().get_save_state_path(),
+                Some(folder.join("state.toml"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_log_level() {
+        assert_eq!(parse_log_level("trace"), Ok(LevelFilter::Trace));
+        assert_eq!(parse_log_level("DEBUG"), Ok(LevelFilter::Debug));
+        assert_eq!(parse_log_level(" Info "), Ok(LevelFilter::Info));
+        assert_eq!(parse_log_level("warning"), Ok(LevelFilter::Warn));
+        assert_eq!(parse_log_level("off"), Ok(LevelFilter::Off));
+        assert!(parse_log_level("verbose").is_err());
+    }
+
     #[test]
     fn test_deserialization() {
         let deserialized: Config = toml::from_str(
@@ -601,6 +1254,18 @@ mod tests {
         assert_eq!(c.window_title, Some(String::from("Title")));
         assert_eq!(c.todo_path, Some(String::from("path to todo file")));
         assert_eq!(c.archive_path, None);
+        assert_eq!(c.get_archive_mode(), ArchiveMode::Inline);
+
+        let c = Config::load_from_buffer("archive_path = \"archive.txt\"".as_bytes());
+        assert_eq!(
+            c.get_archive_mode(),
+            ArchiveMode::Explicit(String::from("archive.txt"))
+        );
+
+        let c = Config::load_from_buffer(
+            "archive_path = \"archive.txt\"\narchive_mode = \"SiblingDone\"".as_bytes(),
+        );
+        assert_eq!(c.get_archive_mode(), ArchiveMode::SiblingDone);
 
         Ok(())
     }
@@ -634,4 +1299,37 @@ mod tests {
         );
         assert_eq!(new_conf.window_title, Some("Window title".to_string()));
     }
+
+    #[test]
+    fn test_layered() {
+        let base = Config::load_from_buffer(
+            r#"
+            todo_path = "base/todo.txt"
+            window_title = "Base title"
+            use_done = true
+            "#
+            .as_bytes(),
+        );
+        let machine = Config::load_from_buffer(
+            r#"
+            window_title = "Machine title"
+            use_done = false
+            "#
+            .as_bytes(),
+        );
+
+        let config = Config::layered([base, machine]);
+        assert_eq!(config.todo_path, Some(String::from("base/todo.txt")));
+        assert_eq!(config.window_title, Some(String::from("Machine title")));
+        assert_eq!(config.use_done, Some(false));
+        assert_eq!(config.archive_path, None);
+
+        let args = Config {
+            window_title: Some(String::from("Argument title")),
+            ..Default::default()
+        };
+        let config = args.merge(config);
+        assert_eq!(config.window_title, Some(String::from("Argument title")));
+        assert_eq!(config.todo_path, Some(String::from("base/todo.txt")));
+    }
 }
@@ -1,4 +1,6 @@
+use crate::{error::ToDoRes, ToDoError};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use tui::style::Color;
 
 /// Serialization and deserialization support for the TUI color type.
@@ -28,9 +30,60 @@ pub enum ColorDef {
     Indexed(u8),
 }
 
+/// Parses a color from its name, hex value `#rrggbb`, `rgb(r, g, b)`
+/// or index in the 256-color palette (`123` or `color123`).
+///
+/// # Parameters
+///
+/// - `s`: The string to parse.
+///
+/// # Returns
+///
+/// The parsed color or `ToDoError::ParseColor` if the string is not a color.
+pub fn parse_color(s: &str) -> ToDoRes<Color> {
+    let err = || ToDoError::ParseColor(s.to_string());
+    let trimmed = s.trim();
+    if let Some(hex) = trimmed.strip_prefix('#') {
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(err());
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| err());
+        return Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    let lower = trimmed.to_lowercase();
+    if let Some(channels) = lower
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let channels = channels
+            .split(',')
+            .map(|channel| channel.trim().parse::<u8>().map_err(|_| err()))
+            .collect::<ToDoRes<Vec<_>>>()?;
+        return match channels[..] {
+            [r, g, b] => Ok(Color::Rgb(r, g, b)),
+            _ => Err(err()),
+        };
+    }
+    let index = lower.strip_prefix("color").unwrap_or(&lower);
+    if !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()) {
+        return index.parse::<u8>().map(Color::Indexed).map_err(|_| err());
+    }
+    Color::from_str(trimmed).map_err(|_| err())
+}
+
+/// Converts the color to a string that can be parsed by `parse_color`.
+/// Rgb colors are written as `#rrggbb` and indexed colors as `colorN`.
+pub fn color_to_string(color: &Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("#{r:02x}{g:02x}{b:02x}"),
+        Color::Indexed(index) => format!("color{index}"),
+        named => format!("{named:?}"),
+    }
+}
+
 pub mod opt_color {
-    use super::ColorDef;
-    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use super::{parse_color, ColorDef};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
     use tui::style::Color;
 
     pub fn serialize<S>(value: &Option<Color>, serializer: S) -> Result<S::Ok, S::Error>
@@ -48,9 +101,103 @@ pub mod opt_color {
         D: Deserializer<'de>,
     {
         #[derive(Deserialize)]
-        struct Helper(#[serde(with = "ColorDef")] Color);
+        #[serde(untagged)]
+        enum Helper {
+            Def(#[serde(with = "ColorDef")] Color),
+            Str(String),
+        }
+
+        match Option::deserialize(deserializer)? {
+            Some(Helper::Def(color)) => Ok(Some(color)),
+            Some(Helper::Str(s)) => parse_color(&s).map(Some).map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() -> ToDoRes<()> {
+        assert_eq!(parse_color("#ff8800")?, Color::Rgb(255, 136, 0));
+        assert_eq!(parse_color("#FF8800")?, Color::Rgb(255, 136, 0));
+        assert_eq!(parse_color("rgb(255, 136, 0)")?, Color::Rgb(255, 136, 0));
+        assert_eq!(parse_color("RGB(1,2,3)")?, Color::Rgb(1, 2, 3));
+        assert_eq!(parse_color("Red")?, Color::Red);
+        assert_eq!(parse_color("light-blue")?, Color::LightBlue);
+        assert_eq!(parse_color("color255")?, Color::Indexed(255));
+        assert_eq!(parse_color("Color0")?, Color::Indexed(0));
+        assert_eq!(parse_color("123")?, Color::Indexed(123));
+
+        for s in [
+            "#zzz",
+            "#zzzzzz",
+            "#ff880",
+            "#ff88001",
+            "#",
+            "rgb(1, 2)",
+            "rgb(1, 2, 256)",
+            "rgb(1,2,3",
+            "nocolor",
+            "300",
+            "color256",
+            "color",
+            "color-1",
+            "99999999999",
+        ] {
+            assert_eq!(parse_color(s), Err(ToDoError::ParseColor(s.to_string())));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> ToDoRes<()> {
+        for color in [
+            Color::Rgb(255, 136, 0),
+            Color::Rgb(0, 0, 0),
+            Color::Red,
+            Color::LightMagenta,
+            Color::DarkGray,
+            Color::Reset,
+            Color::Indexed(0),
+            Color::Indexed(255),
+        ] {
+            assert_eq!(parse_color(&color_to_string(&color))?, color);
+        }
+        assert_eq!(color_to_string(&Color::Rgb(255, 136, 0)), "#ff8800");
+        assert_eq!(color_to_string(&Color::Indexed(42)), "color42");
+        Ok(())
+    }
 
-        let helper = Option::deserialize(deserializer)?;
-        Ok(helper.map(|Helper(external)| external))
+    #[test]
+    fn deserialize() {
+        #[derive(Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default, with = "opt_color")]
+            color: Option<Color>,
+        }
+        let parse = |s: &str| toml::from_str::<Wrapper>(s).map(|w| w.color);
+        assert_eq!(parse("color = \"Red\"").unwrap(), Some(Color::Red));
+        assert_eq!(parse("color = \"red\"").unwrap(), Some(Color::Red));
+        assert_eq!(
+            parse("color = \"#ff8800\"").unwrap(),
+            Some(Color::Rgb(255, 136, 0))
+        );
+        assert_eq!(
+            parse("color.Rgb = [1, 2, 3]").unwrap(),
+            Some(Color::Rgb(1, 2, 3))
+        );
+        assert_eq!(
+            parse("color = \"color42\"").unwrap(),
+            Some(Color::Indexed(42))
+        );
+        assert_eq!(parse("color.Indexed = 5").unwrap(), Some(Color::Indexed(5)));
+        assert_eq!(parse("").unwrap(), None);
+        assert!(parse("color = \"#zzz\"")
+            .unwrap_err()
+            .to_string()
+            .contains("Color '#zzz' is invalid"));
     }
 }
@@ -0,0 +1,35 @@
+use clap::{Args, Subcommand};
+
+/// Commands executed without starting the user interface.
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Print numbers of tasks, e.g. for status bars.
+    Count(CountArgs),
+    /// Add a new task and save the todo file right away, `save_policy`
+    /// applies only to the user interface.
+    Add {
+        /// The task, relative dates are expanded as in the user interface.
+        task: String,
+    },
+}
+
+/// Numbers of tasks printed by the count command. All numbers are printed
+/// if none is selected.
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CountArgs {
+    /// Number of pending tasks.
+    #[arg(long)]
+    pub pending: bool,
+
+    /// Number of done tasks.
+    #[arg(long)]
+    pub done: bool,
+
+    /// Number of pending tasks after their due date.
+    #[arg(long)]
+    pub overdue: bool,
+
+    /// Number of pending tasks due today.
+    #[arg(long)]
+    pub due_today: bool,
+}
@@ -0,0 +1,103 @@
+use chrono::{
+    format::{Item, StrftimeItems},
+    NaiveDate,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use crate::ToDoError;
+
+/// Strftime pattern used to display dates. The pattern is validated when created.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(try_from = "String", into = "String")]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct DateFormat(String);
+
+impl DateFormat {
+    /// Formats the date by the pattern.
+    pub fn format(&self, date: &NaiveDate) -> impl Display + '_ {
+        date.format(&self.0)
+    }
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        Self(String::from("%Y-%m-%d"))
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = ToDoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if StrftimeItems::new(s).any(|item| item == Item::Error) {
+            Err(ToDoError::ParseDateFormat(s.to_string()))
+        } else {
+            Ok(Self(s.to_string()))
+        }
+    }
+}
+
+impl TryFrom<String> for DateFormat {
+    type Error = ToDoError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(&value)
+    }
+}
+
+impl From<DateFormat> for String {
+    fn from(value: DateFormat) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() -> Result<(), ToDoError> {
+        let date = NaiveDate::from_ymd_opt(2023, 11, 2).unwrap();
+        assert_eq!(
+            DateFormat::default().format(&date).to_string(),
+            "2023-11-02"
+        );
+        assert_eq!(
+            DateFormat::from_str("%d.%m.%Y")?.format(&date).to_string(),
+            "02.11.2023"
+        );
+        assert_eq!(
+            DateFormat::from_str("%b %e, %y")?.format(&date).to_string(),
+            "Nov  2, 23"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            DateFormat::from_str("%Y-%m-%"),
+            Err(ToDoError::ParseDateFormat(String::from("%Y-%m-%")))
+        );
+        assert!(DateFormat::from_str("%Q").is_err());
+
+        #[derive(Deserialize, Debug)]
+        struct Wrapper {
+            #[allow(dead_code)]
+            date_format: DateFormat,
+        }
+        let err = toml::from_str::<Wrapper>("date_format = \"%d.%m.%\"").unwrap_err();
+        assert!(err.to_string().contains("Date format '%d.%m.%' is invalid"));
+    }
+}
@@ -0,0 +1,44 @@
+use super::Config;
+use crate::{
+    file_worker::{ArchiveMode, LineEnding, ParseErrorPolicy},
+    todo::hooks::HookPaths,
+};
+use std::{path::PathBuf, time::Duration};
+
+pub struct FileWorkerConfig {
+    pub todo_path: String,
+    pub archive_path: Option<String>,
+    pub archive_mode: ArchiveMode,
+    pub todo_files: Vec<(String, PathBuf)>,
+    pub line_ending: LineEnding,
+    pub task_spacing: usize,
+    pub on_parse_error: ParseErrorPolicy,
+    pub autosave_duration: Duration,
+    pub autosave_jitter: Duration,
+    pub file_watcher: bool,
+    pub watcher_debounce: Duration,
+    pub git_autocommit: bool,
+    pub hook_paths: HookPaths,
+}
+
+impl FileWorkerConfig {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            todo_path: config.get_todo_path(),
+            archive_path: config
+                .get_archive_mode()
+                .archive_path(&config.get_todo_path()),
+            archive_mode: config.get_archive_mode(),
+            todo_files: config.get_todo_files(),
+            line_ending: config.get_line_ending(),
+            task_spacing: config.get_task_spacing(),
+            on_parse_error: config.get_on_parse_error(),
+            autosave_duration: config.get_autosave_duration(),
+            autosave_jitter: config.get_autosave_jitter(),
+            file_watcher: config.get_file_watcher(),
+            watcher_debounce: Duration::from_millis(config.get_watcher_debounce_ms()),
+            git_autocommit: config.get_git_autocommit(),
+            hook_paths: config.get_hook_paths().file_hooks(),
+        }
+    }
+}
@@ -5,7 +5,7 @@ use log4rs::{
     config::{Appender, Config as LogConfig, Root},
     encode::pattern::PatternEncoder,
 };
-use std::{error::Error, path::PathBuf};
+use std::{error::Error, fs::create_dir_all, path::PathBuf};
 
 pub struct Logger {
     file: PathBuf,
@@ -23,6 +23,9 @@ impl Logger {
     }
 
     pub fn init(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(folder) = self.file.parent().filter(|f| !f.as_os_str().is_empty()) {
+            create_dir_all(folder)?;
+        }
         let logfile = FileAppender::builder()
             .encoder(Box::new(PatternEncoder::new(&self.format)))
             .build(&self.file)?;
@@ -4,7 +4,7 @@ use super::{text_style::TextStyleList, Config, TextStyle};
 use todo_txt::Task;
 use tui::style::Style;
 
-use crate::error::ToDoRes;
+use crate::{error::ToDoRes, todo::task_list::get_progress};
 
 #[derive(Default)]
 pub struct Styles {
@@ -16,6 +16,12 @@ pub struct Styles {
     pub category_select_style: TextStyle,
     pub category_remove_style: TextStyle,
     pub custom_category_style: HashMap<String, TextStyle>,
+    pub now_style: TextStyle,
+    pub done_style: TextStyle,
+    pub highlight: TextStyle,
+    pub current_match_style: TextStyle,
+    /// Minimal percentages of progress and their styles sorted by the percentage.
+    pub progress_styles: Vec<(u8, TextStyle)>,
 }
 
 #[derive(Debug)]
@@ -24,6 +30,7 @@ pub enum StylesValue {
     Const(Style),
     CustomCategory,
     Priority,
+    Progress,
 }
 
 impl StylesValue {
@@ -52,6 +59,7 @@ impl StylesValue {
             Priority => styles
                 .priority_style
                 .get_style(task.priority.clone().into()),
+            Progress => styles.get_progress_style(task).get_style(),
         }
     }
 }
@@ -68,7 +76,13 @@ impl Styles {
             contexts_style: config.get_contexts_style().combine(&category_style),
             hashtags_style: config.get_hashtags_style().combine(&category_style),
             custom_category_style: HashMap::new(),
+            now_style: config.get_now_style(),
+            done_style: config.get_done_style(),
+            highlight: config.get_highlight_style(),
+            current_match_style: config.get_current_match_style(),
+            progress_styles: config.get_progress_styles(),
         };
+        styles.progress_styles.sort_by_key(|(min, _)| *min);
         styles.custom_category_style = config
             .get_custom_category_style()
             .into_iter()
@@ -93,6 +107,7 @@ impl Styles {
         Ok(match name {
             "priority" => Priority,
             "custom_category" => CustomCategory,
+            "progress" => Progress,
             "projects" => Const(self.projects_style.get_style()),
             "contexts" => Const(self.contexts_style.get_style()),
             "hashtags" => Const(self.hashtags_style.get_style()),
@@ -124,6 +139,20 @@ impl Styles {
         })
     }
 
+    /// Gets the style of the task progress, that is the style with the highest
+    /// minimal percentage not greater than the progress.
+    pub fn get_progress_style(&self, task: &Task) -> TextStyle {
+        get_progress(task)
+            .and_then(|progress| {
+                self.progress_styles
+                    .iter()
+                    .rev()
+                    .find(|(min, _)| *min <= progress)
+            })
+            .map(|(_, style)| *style)
+            .unwrap_or_default()
+    }
+
     pub fn get_category_style(&self, category: &str) -> TextStyle {
         match self.custom_category_style.get(category) {
             Some(style) => *style,
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use tui::style::Modifier;
@@ -14,6 +14,10 @@ pub enum TextModifier {
     Bold,
     Italic,
     Underlined,
+    CrossedOut,
+    Dim,
+    Reversed,
+    SlowBlink,
 }
 
 // TODO coverage
@@ -24,7 +28,11 @@ impl FromStr for TextModifier {
         match s.to_lowercase().as_str() {
             "bold" => Ok(Self::Bold),
             "italic" => Ok(Self::Italic),
-            "underline" => Ok(Self::Underlined),
+            "underline" | "underlined" => Ok(Self::Underlined),
+            "crossedout" | "strikethrough" => Ok(Self::CrossedOut),
+            "dim" => Ok(Self::Dim),
+            "reversed" => Ok(Self::Reversed),
+            "blink" | "slowblink" => Ok(Self::SlowBlink),
             _ => Err(ToDoError::ParseTextModifier(s.to_string())),
         }
     }
@@ -37,10 +45,30 @@ impl From<TextModifier> for Modifier {
             Bold => Modifier::BOLD,
             Italic => Modifier::ITALIC,
             Underlined => Modifier::UNDERLINED,
+            CrossedOut => Modifier::CROSSED_OUT,
+            Dim => Modifier::DIM,
+            Reversed => Modifier::REVERSED,
+            SlowBlink => Modifier::SLOW_BLINK,
         }
     }
 }
 
+impl fmt::Display for TextModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TextModifier::*;
+        let name = match self {
+            Bold => "Bold",
+            Italic => "Italic",
+            Underlined => "Underlined",
+            CrossedOut => "CrossedOut",
+            Dim => "Dim",
+            Reversed => "Reversed",
+            SlowBlink => "SlowBlink",
+        };
+        write!(f, "{name}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,5 +83,61 @@ mod tests {
 
         let underline = TextModifier::Underlined;
         assert_eq!(Modifier::from(underline), Modifier::UNDERLINED);
+
+        let crossed_out = TextModifier::CrossedOut;
+        assert_eq!(Modifier::from(crossed_out), Modifier::CROSSED_OUT);
+
+        let dim = TextModifier::Dim;
+        assert_eq!(Modifier::from(dim), Modifier::DIM);
+
+        let reversed = TextModifier::Reversed;
+        assert_eq!(Modifier::from(reversed), Modifier::REVERSED);
+
+        let slow_blink = TextModifier::SlowBlink;
+        assert_eq!(Modifier::from(slow_blink), Modifier::SLOW_BLINK);
+    }
+
+    #[test]
+    fn from_str() -> Result<(), ToDoError> {
+        use TextModifier::*;
+        for (s, modifier) in [
+            ("bold", Bold),
+            ("Italic", Italic),
+            ("underline", Underlined),
+            ("crossedout", CrossedOut),
+            ("strikethrough", CrossedOut),
+            ("dim", Dim),
+            ("reversed", Reversed),
+            ("blink", SlowBlink),
+            ("SlowBlink", SlowBlink),
+        ] {
+            assert_eq!(TextModifier::from_str(s)?, modifier);
+        }
+        assert_eq!(
+            TextModifier::from_str("blinking"),
+            Err(ToDoError::ParseTextModifier(String::from("blinking")))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> Result<(), ToDoError> {
+        use TextModifier::*;
+        #[derive(Serialize, Deserialize)]
+        struct Wrapper {
+            modifier: TextModifier,
+        }
+        for modifier in [
+            Bold, Italic, Underlined, CrossedOut, Dim, Reversed, SlowBlink,
+        ] {
+            let displayed = modifier.to_string();
+            assert_eq!(TextModifier::from_str(&displayed)?, modifier);
+            // Displayed name is the same as the serialized one.
+            let serialized = toml::to_string(&Wrapper { modifier }).unwrap();
+            assert_eq!(serialized.trim(), format!("modifier = \"{displayed}\""));
+            let deserialized: Wrapper = toml::from_str(&serialized).unwrap();
+            assert_eq!(deserialized.modifier, modifier);
+        }
+        Ok(())
     }
 }
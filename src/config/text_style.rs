@@ -1,8 +1,8 @@
-use super::colors::opt_color;
+use super::colors::{color_to_string, opt_color, parse_color};
 use super::text_modifier::TextModifier;
 use crate::ToDoError;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, fmt, str::FromStr};
 use tui::style::{Color, Style};
 
 /// Represents the styling for text elements.
@@ -62,7 +62,6 @@ impl TextStyle {
     /// # Returns
     ///
     /// A new `TextStyle` with the specified text modifier.
-    #[allow(dead_code)]
     pub fn modifier(mut self, modifier: TextModifier) -> Self {
         self.modifier = Some(modifier);
         self
@@ -137,11 +136,13 @@ impl FromStr for TextStyle {
         let mut ret = TextStyle::default();
         for word in s.split_whitespace() {
             if let Some(stripped) = word.strip_prefix('^') {
-                match Color::from_str(stripped) {
+                match parse_color(stripped) {
                     Ok(c) => ret = ret.bg(c),
                     Err(_) => return Err(ToDoError::ParseTextStyle(word.to_string())),
                 }
-            } else if let Ok(color) = Color::from_str(word) {
+            } else if word.starts_with('#') {
+                ret = ret.fg(parse_color(word)?);
+            } else if let Ok(color) = parse_color(word) {
                 ret = ret.fg(color);
             } else if let Ok(modifier) = TextModifier::from_str(word) {
                 ret = ret.modifier(modifier);
@@ -157,6 +158,19 @@ impl FromStr for TextStyle {
     }
 }
 
+/// Writes the style in the format parsed by `TextStyle::from_str`.
+impl fmt::Display for TextStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let words = [
+            self.fg.map(|c| color_to_string(&c)),
+            self.bg.map(|c| format!("^{}", color_to_string(&c))),
+            self.modifier.map(|m| m.to_string()),
+        ];
+        let words: Vec<_> = words.into_iter().flatten().collect();
+        write!(f, "{}", words.join(" "))
+    }
+}
+
 /// Represents a list of text styles for priorities.
 ///
 /// This struct maintains a list of text styles for different priority levels.
@@ -303,6 +317,22 @@ mod tests {
             TextStyle::from_str("underline").unwrap(),
             TextStyle::default().modifier(TextModifier::Underlined)
         );
+        assert_eq!(
+            TextStyle::from_str("strikethrough").unwrap(),
+            TextStyle::default().modifier(TextModifier::CrossedOut)
+        );
+        assert_eq!(
+            TextStyle::from_str("dim").unwrap(),
+            TextStyle::default().modifier(TextModifier::Dim)
+        );
+        assert_eq!(
+            TextStyle::from_str("reversed").unwrap(),
+            TextStyle::default().modifier(TextModifier::Reversed)
+        );
+        assert_eq!(
+            TextStyle::from_str("blink").unwrap(),
+            TextStyle::default().modifier(TextModifier::SlowBlink)
+        );
         assert_eq!(
             TextStyle::from_str("red bold ^blue italic").unwrap(),
             TextStyle::default()
@@ -314,6 +344,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn from_str_hex() -> ToDoRes<()> {
+        assert_eq!(
+            TextStyle::from_str("#ff8800")?,
+            TextStyle::default().fg(Color::Rgb(255, 136, 0))
+        );
+        assert_eq!(
+            TextStyle::from_str("rgb(1,2,3) ^#000000 bold")?,
+            TextStyle::default()
+                .fg(Color::Rgb(1, 2, 3))
+                .bg(Color::Rgb(0, 0, 0))
+                .modifier(TextModifier::Bold)
+        );
+        assert_eq!(
+            TextStyle::from_str("color123 ^45")?,
+            TextStyle::default()
+                .fg(Color::Indexed(123))
+                .bg(Color::Indexed(45))
+        );
+        assert!(TextStyle::from_str("300").is_err());
+        assert_eq!(
+            TextStyle::from_str("#zzz"),
+            Err(ToDoError::ParseColor(String::from("#zzz")))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> ToDoRes<()> {
+        let style = TextStyle::default()
+            .fg(Color::Rgb(255, 136, 0))
+            .bg(Color::LightBlue)
+            .modifier(TextModifier::CrossedOut);
+        assert_eq!(style.to_string(), "#ff8800 ^LightBlue CrossedOut");
+        for style in [
+            style,
+            TextStyle::default(),
+            TextStyle::default().bg(Color::Rgb(0, 10, 255)),
+            TextStyle::default().fg(Color::Red),
+            TextStyle::default()
+                .fg(Color::Indexed(255))
+                .bg(Color::Indexed(16)),
+        ] {
+            assert_eq!(TextStyle::from_str(&style.to_string())?, style);
+        }
+        Ok(())
+    }
+
     #[test]
     fn from_str_err() {
         assert_eq!(
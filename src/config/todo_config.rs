@@ -1,18 +1,47 @@
-use super::Config;
-use crate::todo::task_list::TaskSort;
+use super::{Config, DateFormat};
+use crate::todo::{hooks::Hooks, task_list::TaskSort};
+use chrono::Weekday;
+use std::collections::HashMap;
 
 pub struct ToDoConfig {
     pub use_done: bool,
     pub pending_sort: TaskSort,
     pub done_sort: TaskSort,
+    pub sort_tiebreak: TaskSort,
+    pub no_project_first: bool,
+    pub inline_done: bool,
+    pub set_final_date: bool,
+    pub preserve_priority_on_done: bool,
+    pub search_word_boundary: bool,
+    pub inherit_active_category: bool,
+    pub issue_url_template: Option<String>,
+    pub priority_symbols: HashMap<String, String>,
+    pub date_format: DateFormat,
+    pub week_start: Weekday,
+    pub hooks: Hooks,
 }
 
 impl ToDoConfig {
     pub fn new(config: &Config) -> Self {
         Self {
-            use_done: false, // TODO add to config
+            use_done: config.get_use_done(),
             pending_sort: config.get_pending_sort(),
             done_sort: config.get_done_sort(),
+            sort_tiebreak: config.get_sort_tiebreak(),
+            no_project_first: config.get_no_project_first(),
+            inline_done: config.get_inline_done(),
+            set_final_date: config.get_set_final_date(),
+            preserve_priority_on_done: config.get_preserve_priority_on_done(),
+            search_word_boundary: config.get_search_word_boundary(),
+            inherit_active_category: config.get_inherit_active_category(),
+            issue_url_template: config.get_issue_url_template(),
+            priority_symbols: config.get_priority_symbols(),
+            date_format: config.get_date_format(),
+            week_start: config.get_week_start(),
+            hooks: Hooks::new(
+                config.get_hook_paths().task_hooks(),
+                config.get_hook_format(),
+            ),
         }
     }
 }
@@ -8,22 +8,34 @@ pub type ToDoRes<T> = Result<T, ToDoError>;
 pub enum ToDoError {
     #[error("Selected widgent is not in layout")]
     WidgetDoesNotExist,
+    #[error("Selected widget cannot be focused")]
+    WidgetCannotFocus,
     #[error("Value cannot be parsed: {0}")]
     ParseValue(#[from] std::num::ParseIntError),
     #[error("Value can constraint only unsigned integer and % not {0}")]
     ParseUnknownValue(String),
     #[error("Unknown widget type: {0}")]
     ParseWidgetType(String),
+    #[error("Unknown category: {0}")]
+    ParseCategory(String),
+    #[error("Filter '{0}' must be in the form 'ToggleFilter(Category, name)'.")]
+    ParseToggleFilter(String),
     #[error("There must be almost one container.")]
     ParseNotStart,
     #[error("All containers must be closed.")]
     ParseNotEnd,
+    #[error("Layout must contain at least one widget.")]
+    ParseNoWidget,
     #[error("Unknown text before start of the container '{0}'")]
     ParseUnknowBeforeContainer(String),
     #[error("Direction '{0}' is invalid.")]
     ParseInvalidDirection(String),
     #[error("Style '{0}' is invalid")]
     ParseTextStyle(String),
+    #[error("Color '{0}' is invalid, use a color name, #rrggbb, rgb(r, g, b) or index 0-255.")]
+    ParseColor(String),
+    #[error("Date format '{0}' is invalid.")]
+    ParseDateFormat(String),
     #[error("Modifier '{0}' is invalid.")]
     ParseTextModifier(String),
     #[error("Block '{0}' have escape on the end.")]
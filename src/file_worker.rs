@@ -1,15 +1,30 @@
-use crate::{config::Config, todo::ToDo};
+use crate::{
+    config::{Config, FileWorkerConfig},
+    todo::{
+        hooks::{HookEvent, HookFormat, Hooks},
+        ToDo,
+    },
+};
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
 use notify::{
     event::{AccessKind, AccessMode, EventKind},
     Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::{hash_map::RandomState, BTreeSet};
+use std::ffi::OsStr;
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Read, Result as ioResult, Write};
-use std::path::Path;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Read, Result as ioResult, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
 use std::str::FromStr;
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{RecvTimeoutError, Sender};
 use std::sync::{mpsc, Arc, Mutex};
-use std::{thread, time::Duration};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use todo_txt::Task;
 
 /// Commands that can be sent to the `FileWorker` for various file-related operations.
@@ -17,14 +32,172 @@ pub enum FileWorkerCommands {
     ForceSave,
     Save,
     Load,
+    /// Saves the actual todo list and loads the given todo file.
+    SwitchTo(PathBuf),
+    /// The todo file was changed by another program. It is loaded if there
+    /// are no unsaved changes, otherwise the conflict is reported to the user
+    /// and saving is postponed until it is resolved.
+    FileChanged,
+    /// Adds tasks from the file that are not in the todo list and saves it.
+    Merge,
     Exit,
 }
 
+/// Line separator used when saving the todo list.
+#[derive(Clone, Copy, Serialize, Deserialize, Default, ValueEnum)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+    /// Line ending of the platform the application runs on.
+    Native,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Native if cfg!(windows) => "\r\n",
+            LineEnding::Native => "\n",
+        }
+    }
+}
+
+/// When changes made in the user interface are saved.
+#[derive(Clone, Copy, Serialize, Deserialize, Default, ValueEnum, PartialEq, Eq, Debug)]
+pub enum SavePolicy {
+    /// Changes are saved only periodically by `autosave_duration`
+    /// or by the `Save` event.
+    #[default]
+    Timer,
+    /// Changes of tasks are saved right after they are made, for example
+    /// when a new task is entered or a task is removed. Periodic saving
+    /// still works as with `Timer`.
+    AutoSaveImmediate,
+}
+
+/// What is done with lines of the file that cannot be parsed as tasks.
+/// A line cannot be parsed if its due or threshold date is not a date
+/// in the `YYYY-MM-DD` format. Skipped and kept lines are reported
+/// as a load error, so the user knows why the tasks are missing.
+#[derive(Clone, Copy, Serialize, Deserialize, Default, ValueEnum, PartialEq, Eq, Debug)]
+pub enum ParseErrorPolicy {
+    /// Lines are dropped with a warning and are not saved back.
+    Skip,
+    /// Lines are kept unchanged and saved back on their position.
+    #[default]
+    KeepRaw,
+    /// Loading of the file fails and the file is not saved
+    /// until it is loaded successfully.
+    Abort,
+}
+
+/// Where done tasks are archived.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug))]
+pub enum ArchiveMode {
+    /// Done tasks are kept in the todo file.
+    #[default]
+    Inline,
+    /// Done tasks are archived to `done.txt` in the directory of the todo file.
+    SiblingDone,
+    /// Done tasks are archived to the given file.
+    Explicit(String),
+}
+
+impl ArchiveMode {
+    const SIBLING_DONE: &'static str = "done.txt";
+
+    /// Gets the path of the archive file for the todo file.
+    ///
+    /// # Returns
+    ///
+    /// `None` if done tasks are kept in the todo file.
+    pub fn archive_path(&self, todo_path: &str) -> Option<String> {
+        match self {
+            ArchiveMode::Inline => None,
+            ArchiveMode::SiblingDone => Some(
+                Path::new(todo_path)
+                    .with_file_name(Self::SIBLING_DONE)
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            ArchiveMode::Explicit(path) => Some(path.clone()),
+        }
+    }
+}
+
+impl FromStr for ArchiveMode {
+    type Err = Error;
+
+    /// Parses `Inline` or `SiblingDone`, any other value is a path to the archive file.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "inline" => ArchiveMode::Inline,
+            "siblingdone" => ArchiveMode::SiblingDone,
+            _ => ArchiveMode::Explicit(s.to_string()),
+        })
+    }
+}
+
+/// Merges bursts of file change events into a single notification.
+///
+/// Every new event postpones the notification, so it is emitted only after
+/// no other event arrived for the whole debounce window.
+struct Debounce {
+    window: Duration,
+    deadline: Option<Instant>,
+}
+
+impl Debounce {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            deadline: None,
+        }
+    }
+
+    /// Registers a new event that occurred at `now`.
+    fn event(&mut self, now: Instant) {
+        self.deadline = Some(now + self.window);
+    }
+
+    /// Time remaining until the pending notification, `None` if there is nothing pending.
+    fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    /// Returns `true` and clears the pending event if its window already elapsed.
+    fn ready(&mut self, now: Instant) -> bool {
+        match self.deadline {
+            Some(deadline) if deadline <= now => {
+                self.deadline = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 /// Manages file operations for the todo list and archive.
 pub struct FileWorker {
-    todo_path: String,
-    archive_path: Option<String>,
+    config: FileWorkerConfig,
     todo: Arc<Mutex<ToDo>>,
+    /// Todo and archive paths from the configuration. The archive is used
+    /// only together with the todo file it is configured for.
+    default_paths: (String, Option<String>),
+    hooks: Hooks,
+    /// Version of the todo list when it was last saved or loaded.
+    version: usize,
+    /// Number of file change events caused by own saves that are ignored.
+    skip_count: usize,
+    /// The file was changed while there were unsaved changes.
+    conflict: bool,
+    /// The last load failed on a line that cannot be parsed.
+    load_failed: bool,
 }
 
 impl FileWorker {
@@ -32,114 +205,503 @@ impl FileWorker {
     ///
     /// # Arguments
     ///
-    /// * `todo_path` - The path to the todo list file.
-    /// * `archive_path` - The optional path to the archive file.
+    /// * `config` - Configuration of the file worker.
     /// * `todo` - A shared reference to the `ToDo` data structure.
     ///
     /// # Returns
     ///
     /// A `FileWorker` instance.
-    pub fn new(
-        todo_path: String,
-        archive_path: Option<String>,
-        todo: Arc<Mutex<ToDo>>,
-    ) -> FileWorker {
+    pub fn new(config: FileWorkerConfig, todo: Arc<Mutex<ToDo>>) -> FileWorker {
         log::info!(
             "Init file worker: file: {}, archive: {:?}",
-            todo_path,
-            archive_path
+            config.todo_path,
+            config.archive_path
         );
         FileWorker {
-            todo_path,
-            archive_path,
+            default_paths: (config.todo_path.clone(), config.archive_path.clone()),
+            hooks: Hooks::new(config.hook_paths.clone(), HookFormat::Text),
+            config,
             todo,
+            version: 0,
+            skip_count: 0,
+            conflict: false,
+            load_failed: false,
         }
     }
 
+    /// Saves the actual todo list and switches to another todo file.
+    ///
+    /// If the new file cannot be loaded, the worker stays with the actual file.
+    /// The new file is remembered in the todo list once it is loaded.
+    /// The actual todo list is not saved if its file failed to load, so the file
+    /// is not overwritten by the old tasks. Files are not switched while there
+    /// is an unresolved conflict with changes of the file by another program.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the todo file to switch to.
+    ///
+    /// # Returns
+    ///
+    /// An `ioResult` indicating success or an error if file operations fail.
+    pub fn switch_to(&mut self, path: PathBuf) -> ioResult<()> {
+        if self.conflict {
+            return Err(Error::other(
+                "The todo file changed, the conflict must be resolved before switching files",
+            ));
+        }
+        if self.load_failed {
+            log::warn!("File Worker: The file is not saved until it is loaded successfully.");
+        } else {
+            self.save()?;
+        }
+        let path = path.to_string_lossy().into_owned();
+        log::info!("Switch todo file to {path}");
+        let archive_path = match self.config.archive_mode {
+            _ if path == self.default_paths.0 => self.default_paths.1.clone(),
+            ArchiveMode::SiblingDone => self.config.archive_mode.archive_path(&path),
+            _ => None,
+        };
+        let todo_path = mem::replace(&mut self.config.todo_path, path);
+        let archive_path = mem::replace(&mut self.config.archive_path, archive_path);
+        if let Err(e) = self.load() {
+            self.config.todo_path = todo_path;
+            self.config.archive_path = archive_path;
+            return Err(e);
+        }
+        self.load_failed = false;
+        self.todo
+            .lock()
+            .unwrap()
+            .set_todo_file(PathBuf::from(&self.config.todo_path));
+        Ok(())
+    }
+
+    /// Runs the file hook with the todo file path as the argument.
+    fn run_hook(&self, event: HookEvent) {
+        self.hooks.run(event, &self.config.todo_path);
+    }
+
     /// Loads todo list data from the file(s).
     ///
     /// This method loads data from the main todo list file and optionally from an archive file.
+    /// Load hooks are executed before and after a successful load.
     ///
     /// # Returns
     ///
     /// An `ioResult` indicating success or an error if file operations fail.
     pub fn load(&self) -> ioResult<()> {
-        let mut todo = ToDo::new(&Config::default()); // TODO this can be improved
-        Self::load_tasks(File::open(&self.todo_path)?, &mut todo)?;
-        log::info!("Load tasks from file {}", self.todo_path);
-        if let Some(path) = &self.archive_path {
+        self.run_hook(HookEvent::PreLoad);
+        self.load_files()?;
+        self.run_hook(HookEvent::PostLoad);
+        Ok(())
+    }
+
+    fn load_files(&self) -> ioResult<()> {
+        // Only the tasks are moved to the shared ToDo, so its state (filters,
+        // sorting, active task) is kept.
+        let todo = self.read_files()?;
+        self.todo.lock().unwrap().move_data(todo);
+        Ok(())
+    }
+
+    /// Reads tasks from the todo file and the archive file.
+    fn read_files(&self) -> ioResult<ToDo> {
+        let mut todo = ToDo::new(&Config::default());
+        todo.raw_lines =
+            Self::load_tasks(File::open(&self.config.todo_path)?, &mut todo, &self.config)?;
+        log::info!("Load tasks from file {}", self.config.todo_path);
+        if let Some(path) = &self.config.archive_path {
             log::info!("Load tasks from achive file {}", path);
-            Self::load_tasks(File::open(path)?, &mut todo)?;
+            match File::open(path) {
+                Ok(file) => {
+                    todo.archive_raw_lines = Self::load_tasks(file, &mut todo, &self.config)?
+                }
+                // Sibling done.txt is created on the first save.
+                Err(e)
+                    if e.kind() == ErrorKind::NotFound
+                        && self.config.archive_mode == ArchiveMode::SiblingDone =>
+                {
+                    log::info!("Archive file {} does not exist yet", path);
+                }
+                Err(e) => return Err(e),
+            }
         }
         log::debug!("Loaded pending {}x tasks", todo.pending.len());
         log::debug!("Loaded done {}x tasks", todo.done.len());
-        self.todo.lock().unwrap().move_data(todo);
+        Ok(todo)
+    }
+
+    /// Merges the todo list with the files. Tasks from the files that are not
+    /// in the todo list are added to it and the result is saved. Tasks removed
+    /// only in one of them are kept.
+    ///
+    /// # Returns
+    ///
+    /// An `ioResult` indicating success or an error if file operations fail.
+    pub fn merge(&self) -> ioResult<()> {
+        let mut merged = self.read_files()?;
+        {
+            let todo = self.todo.lock().unwrap();
+            let in_files: BTreeSet<String> = merged.task_lines().collect();
+            for task in todo.pending.iter().chain(&todo.done) {
+                if !in_files.contains(&task.to_string()) {
+                    merged.add_task(task.clone());
+                }
+            }
+        }
+        log::info!("Merge tasks from file {}", self.config.todo_path);
+        self.todo.lock().unwrap().move_data(merged);
+        self.save()
+    }
+
+    /// Remembers the todo list as saved, so later changes can be detected.
+    fn remember_saved(&mut self) {
+        let mut todo = self.todo.lock().unwrap();
+        self.version = todo.get_version();
+        todo.mark_saved();
+    }
+
+    /// Handles a command sent to the running worker.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command to handle, `Exit` is handled by the caller.
+    ///
+    /// # Returns
+    ///
+    /// An `ioResult` indicating success or an error if file operations fail.
+    pub(crate) fn handle_command(&mut self, command: FileWorkerCommands) -> ioResult<()> {
+        use FileWorkerCommands::*;
+        match command {
+            Save if self.conflict => {
+                log::info!("File Worker: Save is postponed until the file conflict is resolved.");
+                return Ok(());
+            }
+            Save | ForceSave | Merge if self.load_failed => {
+                log::warn!("File Worker: The file is not saved until it is loaded successfully.");
+                return Ok(());
+            }
+            Save => {
+                if self.version == self.todo.lock().unwrap().get_version() {
+                    log::debug!("File Worker: Todo list is actual.");
+                    return Ok(());
+                }
+                self.skip_count += 2;
+                self.save()?;
+            }
+            ForceSave => {
+                self.skip_count += 2;
+                self.save()?;
+            }
+            FileChanged if self.skip_count > 0 => {
+                self.skip_count -= 1;
+                log::debug!("Load file 'skip_count': {}", self.skip_count);
+                return Ok(());
+            }
+            FileChanged if self.todo.lock().unwrap().has_unsaved_changes() => {
+                log::info!("Todo file changed while there are unsaved changes.");
+                self.conflict = true;
+                self.todo.lock().unwrap().set_file_conflict();
+                return Ok(());
+            }
+            Load | FileChanged => {
+                if let Err(e) = self.load() {
+                    self.load_failed = e.kind() == ErrorKind::InvalidData;
+                    if self.load_failed {
+                        self.todo.lock().unwrap().set_load_error(e.to_string());
+                    }
+                    return Err(e);
+                }
+                self.load_failed = false;
+                log::info!("Todo list updated from file.");
+            }
+            Merge => {
+                self.skip_count += 2;
+                self.merge()?;
+            }
+            SwitchTo(path) => self.switch_to(path)?,
+            Exit => return Ok(()),
+        }
+        self.conflict = false;
+        self.remember_saved();
         Ok(())
     }
 
     /// Loads tasks from a given reader and adds them to the provided `ToDo` instance.
     ///
+    /// Lines that are not tasks (comments, blank lines and lines that cannot
+    /// be parsed) are not dropped but returned together with their position,
+    /// so they can be written back on save. If task spacing is set, blank
+    /// lines are only formatting and are ignored.
+    ///
+    /// Leading and trailing whitespace of tasks is stripped, spacing inside
+    /// the task text is kept and saved back unchanged. Only the due and
+    /// threshold dates are written at the end of the task on save.
+    ///
+    /// As the todo.txt format specifies, a task is done only if the trimmed
+    /// line starts with a lowercase `x` followed by a space. An `x` anywhere
+    /// else in the line does not mark the task done.
+    ///
+    /// Lines with tasks that cannot be parsed are handled by `on_parse_error`
+    /// of the configuration, `Abort` returns an error of the `InvalidData` kind,
+    /// `Skip` and `KeepRaw` add the lines to the load error of the todo list.
+    ///
+    /// Lines are read into a single reused buffer, only the raw lines are
+    /// allocated. Tasks are loaded to a `ToDo` that is not shared yet, so
+    /// loading of large files does not block the shared todo list.
+    ///
     /// # Arguments
     ///
     /// * `reader` - A readable source (e.g., a file) to load tasks from.
     /// * `todo` - A mutable reference to the `ToDo` instance where tasks will be added.
+    /// * `config` - Configuration of the file format.
     ///
     /// # Returns
     ///
-    /// An `ioResult` indicating success or an error if file operations fail.
-    fn load_tasks<R: Read>(reader: R, todo: &mut ToDo) -> ioResult<()> {
-        for line in BufReader::new(reader).lines() {
-            let line = line?;
-            let line = line.trim();
-            if line.is_empty() {
+    /// An `ioResult` with the raw lines or an error if file operations fail.
+    pub(crate) fn load_tasks<R: Read>(
+        reader: R,
+        todo: &mut ToDo,
+        config: &FileWorkerConfig,
+    ) -> ioResult<Vec<(usize, String)>> {
+        let mut raw_lines = Vec::new();
+        let mut reader = BufReader::new(reader);
+        let mut buffer = String::new();
+        for position in 0.. {
+            buffer.clear();
+            if reader.read_line(&mut buffer)? == 0 {
+                break;
+            }
+            let line = buffer
+                .strip_suffix('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line))
+                .unwrap_or(&buffer);
+            let trimmed = line.trim();
+            if trimmed.is_empty() && config.task_spacing > 0 {
                 continue;
             }
-            match Task::from_str(line) {
+            if trimmed.is_empty() || Self::is_comment(trimmed) {
+                raw_lines.push((position, line.to_string()));
+                continue;
+            }
+            match Self::parse_task(trimmed) {
                 Ok(task) => todo.add_task(task),
-                Err(e) => log::warn!("Task cannot be load due {e}: {line}"),
+                Err(e) => match config.on_parse_error {
+                    ParseErrorPolicy::Skip => {
+                        log::warn!("Task is skipped due {e}: {line}");
+                        todo.add_load_error(format!(
+                            "Line {} cannot be parsed: {e}, it is skipped",
+                            position + 1
+                        ));
+                    }
+                    ParseErrorPolicy::KeepRaw => {
+                        log::warn!("Task cannot be load due {e}: {line}");
+                        todo.add_load_error(format!(
+                            "Line {} cannot be parsed: {e}, it is kept unchanged in the file",
+                            position + 1
+                        ));
+                        raw_lines.push((position, line.to_string()));
+                    }
+                    ParseErrorPolicy::Abort => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("Line {} cannot be parsed: {e}", position + 1),
+                        ));
+                    }
+                },
             }
         }
-        Ok(())
+        Ok(raw_lines)
+    }
+
+    /// Parses a task from the line. The task is invalid if its due or threshold
+    /// date is not a date, the parser would drop such dates silently.
+    fn parse_task(line: &str) -> Result<Task, String> {
+        let task = Task::from_str(line).map_err(|e| e.to_string())?;
+        for word in line.split_whitespace() {
+            for key in ["due:", "t:"] {
+                if let Some(value) = word.strip_prefix(key) {
+                    if NaiveDate::parse_from_str(value, "%Y-%m-%d").is_err() {
+                        return Err(format!("invalid date {word}"));
+                    }
+                }
+            }
+        }
+        Ok(task)
+    }
+
+    /// Checks if the line is a comment. Comment starts with `#` followed
+    /// by whitespace, so tasks starting with a hashtag are not comments.
+    fn is_comment(line: &str) -> bool {
+        line.strip_prefix('#')
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
     }
 
     /// Saves todo list data to the file(s).
     ///
     /// This method saves data to the main todo list file and optionally to an archive file.
+    /// Tasks completed in place are moved to done tasks before saving.
+    /// Save hooks are executed before and after a successful save.
     ///
     /// # Returns
     ///
     /// An `ioResult` indicating success or an error if file operations fail.
-    fn save(&self) -> ioResult<()> {
-        let mut f = File::create(&self.todo_path)?;
-        let todo = self.todo.lock().unwrap();
+    pub fn save(&self) -> ioResult<()> {
+        self.run_hook(HookEvent::PreSave);
+        self.save_files()?;
+        self.run_hook(HookEvent::PostSave);
+        if self.config.git_autocommit {
+            if let Err(e) = self.git_commit() {
+                log::error!("Git autocommit failed: {e}");
+                self.todo
+                    .lock()
+                    .unwrap()
+                    .set_save_error(format!("Git autocommit failed: {e}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Commits the todo files if they are inside a git repository.
+    /// If they are not, nothing is done. Only the todo files are committed,
+    /// other changes staged in the repository are left as they are.
+    fn git_commit(&self) -> ioResult<()> {
+        // Git runs in the directory of the todo file, so the paths must not
+        // be relative to the working directory of the application.
+        let todo_path = Path::new(&self.config.todo_path).canonicalize()?;
+        let dir = todo_path.parent().unwrap_or(Path::new("/"));
+        let mut paths = vec![todo_path.clone()];
+        if let Some(archive_path) = &self.config.archive_path {
+            paths.push(Path::new(archive_path).canonicalize()?);
+        }
+        let git = |args: &[&OsStr], with_paths: bool| {
+            let mut command = Command::new("git");
+            command.arg("-C").arg(dir).args(args);
+            if with_paths {
+                command.arg("--").args(&paths);
+            }
+            command.output()
+        };
+
+        let output = git(
+            &["rev-parse".as_ref(), "--is-inside-work-tree".as_ref()],
+            false,
+        )?;
+        if !output.status.success() {
+            log::debug!("{} is not in a git repository", dir.display());
+            return Ok(());
+        }
+
+        let check = |output: Output| {
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(Error::other(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ))
+            }
+        };
+        check(git(&["add".as_ref()], true)?)?;
+
+        // Nothing to commit if the todo files did not change since the last commit.
+        let output = git(
+            &["diff".as_ref(), "--cached".as_ref(), "--quiet".as_ref()],
+            true,
+        )?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let message = format!(
+            "todotxt-tui autosave {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        check(git(
+            &["commit".as_ref(), "-m".as_ref(), message.as_ref()],
+            true,
+        )?)
+    }
+
+    fn save_files(&self) -> ioResult<()> {
+        let mut f = File::create(&self.config.todo_path)?;
+        let mut todo = self.todo.lock().unwrap();
+        todo.relocate_done();
         log::info!(
             "Saving todo task to {}{}",
-            self.todo_path,
-            self.archive_path
+            self.config.todo_path,
+            self.config
+                .archive_path
                 .as_ref()
                 .map_or(String::from(""), |p| String::from(" and") + &p.clone()),
         );
-        Self::save_tasks(&mut f, &todo.pending)?;
-        match &self.archive_path {
-            Some(s) => Self::save_tasks(&mut File::create(s)?, &todo.done),
-            None => Self::save_tasks(&mut f, &todo.done),
+        match &self.config.archive_path {
+            Some(s) => {
+                Self::save_tasks(&mut f, &todo.pending, &todo.raw_lines, &self.config)?;
+                Self::save_tasks(
+                    &mut File::create(s)?,
+                    &todo.done,
+                    &todo.archive_raw_lines,
+                    &self.config,
+                )
+            }
+            None => Self::save_tasks(
+                &mut f,
+                todo.pending.iter().chain(&todo.done),
+                &todo.raw_lines,
+                &self.config,
+            ),
         }
     }
 
     /// Saves a list of tasks to the provided writer.
     ///
+    /// Raw lines are placed back to their original positions, lines that
+    /// are out of the range of saved tasks are appended at the end. Tasks
+    /// are separated by the configured number of blank lines.
+    ///
     /// # Arguments
     ///
     /// * `writer` - A writable destination (e.g., a file) where tasks will be saved.
-    /// * `tasks` - A reference to a slice of tasks to be saved.
+    /// * `tasks` - Tasks to be saved.
+    /// * `raw_lines` - Lines that are not tasks with their positions, sorted by position.
+    /// * `config` - Configuration of the file format.
     ///
     /// # Returns
     ///
     /// An `ioResult` indicating success or an error if file operations fail.
-    fn save_tasks<W: Write>(writer: &mut W, tasks: &[Task]) -> ioResult<()> {
+    fn save_tasks<'a, W, I>(
+        writer: &mut W,
+        tasks: I,
+        raw_lines: &[(usize, String)],
+        config: &FileWorkerConfig,
+    ) -> ioResult<()>
+    where
+        W: Write,
+        I: IntoIterator<Item = &'a Task>,
+    {
         let mut writer = BufWriter::new(writer);
-        for task in tasks.iter() {
-            writer.write_all((task.to_string() + "\n").as_bytes())?;
+        let mut write_line = |line: &str| -> ioResult<()> {
+            writer.write_all(line.as_bytes())?;
+            writer.write_all(config.line_ending.as_str().as_bytes())
+        };
+        let mut raw_lines = raw_lines.iter().peekable();
+        let mut position = 0;
+        for (i, task) in tasks.into_iter().enumerate() {
+            if i > 0 {
+                for _ in 0..config.task_spacing {
+                    write_line("")?;
+                    position += 1;
+                }
+            }
+            while let Some((_, line)) = raw_lines.next_if(|(pos, _)| *pos <= position) {
+                write_line(line)?;
+                position += 1;
+            }
+            write_line(&task.to_string())?;
+            position += 1;
+        }
+        for (_, line) in raw_lines {
+            write_line(line)?;
         }
         Ok(())
     }
@@ -149,70 +711,45 @@ impl FileWorker {
     /// This method starts the `FileWorker` thread and handles file-related operations and
     /// synchronization with other parts of the application.
     ///
-    /// # Arguments
-    ///
-    /// * `autosave_duration` - The duration between automatic saves of todo data.
-    /// * `handle_changes` - A flag indicating whether to handle file change events.
-    ///
     /// # Returns
     ///
-    /// A `Sender` that can be used to send commands to the `FileWorker` thread.
-    pub fn run(
-        self,
-        autosave_duration: Duration,
-        handle_changes: bool,
-    ) -> Sender<FileWorkerCommands> {
+    /// A `Sender` that can be used to send commands to the `FileWorker` thread
+    /// and the handle of the thread. The thread ends after the `Exit` command
+    /// when all commands sent before are handled.
+    pub fn run(mut self) -> (Sender<FileWorkerCommands>, JoinHandle<()>) {
         use FileWorkerCommands::*;
         let (tx, rx) = mpsc::channel::<FileWorkerCommands>();
-        if !autosave_duration.is_zero() {
-            Self::spawn_autosave(tx.clone(), autosave_duration);
-        }
-
-        if handle_changes {
-            Self::spawn_watcher(tx.clone(), self.todo_path.clone());
-            if let Some(path) = &self.archive_path {
-                Self::spawn_watcher(tx.clone(), path.clone());
-            }
+        if !self.config.autosave_duration.is_zero() {
+            Self::spawn_autosave(
+                tx.clone(),
+                self.config.autosave_duration,
+                self.config.autosave_jitter,
+            );
         }
 
-        thread::spawn(move || {
-            let mut version = self.todo.lock().unwrap().get_version();
-            let mut skip_count: usize = 0;
+        // Remember the loaded tasks before the caller can change them.
+        self.remember_saved();
+        let tx_watcher = tx.clone();
+        let handle = thread::spawn(move || {
+            // Watchers stop when dropped, so they live as long as the worker.
+            let mut watchers = self.spawn_watchers(&tx_watcher);
             for received in rx {
-                if let Err(e) = match received {
-                    Save => {
-                        let act_version = self.todo.lock().unwrap().get_version();
-                        if version == act_version {
-                            log::debug!("File Worker: Todo list is actual.");
-                            Ok(())
-                        } else {
-                            skip_count += 2;
-                            version = act_version;
-                            self.save()
-                        }
-                    }
-                    ForceSave => {
-                        skip_count += 2;
-                        self.save()
-                    }
-                    Load => {
-                        if skip_count > 0 {
-                            skip_count -= 1;
-                            log::debug!("Load file 'skip_count': {}", skip_count);
-                            continue;
-                        }
-                        let result = self.load();
-                        version = self.todo.lock().unwrap().get_version();
-                        log::info!("Todo list updated from file.");
-                        result
-                    }
-                    Exit => break,
-                } {
+                let switch = matches!(received, SwitchTo(_));
+                if switch {
+                    watchers.clear();
+                }
+                if let Exit = received {
+                    break;
+                }
+                if let Err(e) = self.handle_command(received) {
                     log::error!("File Worker: {}", e.kind());
                 }
+                if switch {
+                    watchers = self.spawn_watchers(&tx_watcher);
+                }
             }
         });
-        tx
+        (tx, handle)
     }
 
     /// Spawns an autosave thread that periodically saves the todo list data.
@@ -221,54 +758,128 @@ impl FileWorker {
     ///
     /// * `tx` - A sender for sending `FileWorkerCommands` to the `FileWorker` thread.
     /// * `duration` - The duration between automatic saves of todo data.
-    fn spawn_autosave(tx: Sender<FileWorkerCommands>, duration: Duration) {
+    /// * `jitter` - The maximal random time added to each interval.
+    fn spawn_autosave(tx: Sender<FileWorkerCommands>, duration: Duration, jitter: Duration) {
         let tx_worker = tx.clone();
         log::trace!("Start autosaver");
         thread::spawn(move || loop {
-            thread::sleep(duration);
-            log::trace!("Autosave with duration {}", duration.as_secs_f64());
+            let random = RandomState::new().build_hasher().finish();
+            let interval = Self::next_autosave_interval(duration, jitter, random);
+            thread::sleep(interval);
+            log::trace!("Autosave with duration {}", interval.as_secs_f64());
             if tx_worker.send(FileWorkerCommands::Save).is_err() {
                 log::trace!("Autosave end");
             }
         });
     }
 
+    /// Computes the time to the next autosave.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The duration between automatic saves of todo data.
+    /// * `jitter` - The maximal random time added to the duration.
+    /// * `random` - The random number choosing the added time.
+    ///
+    /// # Returns
+    ///
+    /// The interval within `duration` and `duration + jitter`.
+    fn next_autosave_interval(duration: Duration, jitter: Duration, random: u64) -> Duration {
+        if jitter.is_zero() {
+            return duration;
+        }
+        let max = u64::try_from(jitter.as_nanos()).unwrap_or(u64::MAX);
+        duration + Duration::from_nanos(random % max.saturating_add(1))
+    }
+
+    /// Spawns file watchers for the actual todo and archive files
+    /// if the file watcher is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - A sender for sending `FileWorkerCommands` to the `FileWorker` thread.
+    ///
+    /// # Returns
+    ///
+    /// Watchers that must be kept alive as long as the files should be watched.
+    fn spawn_watchers(&self, tx: &Sender<FileWorkerCommands>) -> Vec<RecommendedWatcher> {
+        if !self.config.file_watcher {
+            return Vec::new();
+        }
+        std::iter::once(&self.config.todo_path)
+            .chain(&self.config.archive_path)
+            .filter_map(|path| {
+                Self::spawn_watcher(tx.clone(), path.clone(), self.config.watcher_debounce)
+                    .map_err(|e| log::error!("Cannot watch file {path}: {e}"))
+                    .ok()
+            })
+            .collect()
+    }
+
     /// Spawns a file watcher thread to monitor changes to a specific file.
     ///
+    /// Change events that arrive within `debounce` from each other are merged,
+    /// so only one `FileChanged` command is sent after the file settles.
+    ///
     /// # Arguments
     ///
     /// * `tx` - A sender for sending `FileWorkerCommands` to the `FileWorker` thread.
     /// * `path` - The path to the file to be watched for changes.
-    fn spawn_watcher(tx: Sender<FileWorkerCommands>, path: String) {
+    /// * `debounce` - The time window in which changes are merged.
+    ///
+    /// # Returns
+    ///
+    /// The watcher that must be kept alive as long as the file should be watched.
+    fn spawn_watcher(
+        tx: Sender<FileWorkerCommands>,
+        path: String,
+        debounce: Duration,
+    ) -> notify::Result<RecommendedWatcher> {
         log::trace!("Start file watcher");
+        let (tx_handle, rx_handle) = mpsc::channel();
+        let mut watcher: RecommendedWatcher = Watcher::new(tx_handle, NotifyConfig::default())?;
+        watcher.watch(Path::new(&path), RecursiveMode::NonRecursive)?;
         thread::spawn(move || {
-            let (tx_handle, rx_handle) = std::sync::mpsc::channel();
-            let mut watcher: RecommendedWatcher =
-                Watcher::new(tx_handle, NotifyConfig::default()).unwrap();
-            watcher
-                .watch(Path::new(&path), RecursiveMode::NonRecursive)
-                .unwrap();
-            for res in rx_handle {
+            let mut debounce = Debounce::new(debounce);
+            loop {
+                let res = match debounce.timeout(Instant::now()) {
+                    Some(timeout) => match rx_handle.recv_timeout(timeout) {
+                        Ok(res) => Some(res),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    },
+                    None => match rx_handle.recv() {
+                        Ok(res) => Some(res),
+                        Err(_) => break,
+                    },
+                };
                 match res {
-                    Ok(event) => match event.kind {
+                    Some(Ok(event)) => match event.kind {
                         EventKind::Access(AccessKind::Close(AccessMode::Write)) => {
                             log::trace!("File {} changed", path);
-                            if tx.send(FileWorkerCommands::Load).is_err() {
-                                break;
-                            };
+                            debounce.event(Instant::now());
                         }
                         _ => log::debug!("Change: {event:?}"),
                     },
-                    Err(error) => log::error!("Error: {error:?}"),
+                    Some(Err(error)) => log::error!("Error: {error:?}"),
+                    None => {}
+                }
+                if debounce.ready(Instant::now())
+                    && tx.send(FileWorkerCommands::FileChanged).is_err()
+                {
+                    break;
                 }
             }
+            log::trace!("File watcher for {} end", path);
         });
+        Ok(watcher)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{test_utils::TempDir, todo::ToDoData};
 
     const TESTING_STRING: &str = r#"
         x (A) 2023-05-21 2023-04-30 measure space for 1 +project1 @context1 #hashtag1 due:2023-06-30
@@ -279,10 +890,64 @@ mod tests {
                                     measure space for 6 +project3 @context2 #hashtag2 due:2023-06-30
         "#;
 
+    #[test]
+    fn test_next_autosave_interval() {
+        let duration = Duration::from_secs(900);
+        let jitter = Duration::from_secs(30);
+        for random in [
+            0,
+            1,
+            29_999_999_999,
+            30_000_000_000,
+            12_345_678_901_234,
+            u64::MAX,
+        ] {
+            let interval = FileWorker::next_autosave_interval(duration, jitter, random);
+            assert!(interval >= duration);
+            assert!(interval <= duration + jitter);
+        }
+        assert_eq!(
+            FileWorker::next_autosave_interval(duration, jitter, 30_000_000_000),
+            duration + jitter
+        );
+        assert_eq!(
+            FileWorker::next_autosave_interval(duration, Duration::ZERO, u64::MAX),
+            duration
+        );
+    }
+
+    #[test]
+    fn test_load_large_file() -> ioResult<()> {
+        const COUNT: usize = 20_000;
+        let mut file = String::new();
+        for i in 0..COUNT {
+            match i % 10 {
+                0 => file.push_str(&format!("x 2023-05-21 done task {i} +project\r\n")),
+                1 => file.push_str("# comment\n"),
+                _ => file.push_str(&format!("(B) pending task {i} @context due:2023-06-30\n")),
+            }
+        }
+        let mut todo = ToDo::default();
+        let config = FileWorkerConfig::new(&Config::default());
+        let start = std::time::Instant::now();
+        let raw_lines = FileWorker::load_tasks(file.as_bytes(), &mut todo, &config)?;
+        log::info!("Loaded {COUNT} lines in {:?}", start.elapsed());
+
+        assert_eq!(todo.done.len(), COUNT / 10);
+        assert_eq!(todo.pending.len(), COUNT / 10 * 8);
+        assert_eq!(raw_lines.len(), COUNT / 10);
+        assert_eq!(raw_lines[0], (1, String::from("# comment")));
+        assert_eq!(todo.done[0].subject, "done task 0 +project");
+        assert_eq!(todo.pending[0].subject, "pending task 2 @context");
+
+        Ok(())
+    }
+
     #[test]
     fn test_load_tasks() -> ioResult<()> {
+        let config = FileWorkerConfig::new(&Config::default());
         let mut todo = ToDo::default();
-        FileWorker::load_tasks(TESTING_STRING.as_bytes(), &mut todo)?;
+        FileWorker::load_tasks(TESTING_STRING.as_bytes(), &mut todo, &config)?;
         assert_eq!(todo.pending.len(), 4);
         assert_eq!(todo.done.len(), 2);
         assert_eq!(
@@ -315,10 +980,71 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_load_done_marker() -> ioResult<()> {
+        let config = FileWorkerConfig::new(&Config::default());
+        let mut todo = ToDo::default();
+        let tasks = [
+            "task x marks the spot",
+            "xylophone practice",
+            "X uppercase is not done",
+            "(A) x after priority",
+            "  x done with indentation",
+            "x 2023-06-01 done with date",
+        ]
+        .join("\n");
+        FileWorker::load_tasks(tasks.as_bytes(), &mut todo, &config)?;
+        let subjects = |tasks: &[Task]| tasks.iter().map(|t| t.subject.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            subjects(&todo.pending),
+            [
+                "task x marks the spot",
+                "xylophone practice",
+                "X uppercase is not done",
+                "x after priority",
+            ]
+        );
+        assert_eq!(
+            subjects(&todo.done),
+            ["done with indentation", "done with date"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keep_internal_spacing() -> ioResult<()> {
+        let config = FileWorkerConfig::new(&Config::default());
+        let mut todo = ToDo::default();
+        let tasks = [
+            "(A) 2023-01-01 align  the  columns +project",
+            "x 2023-02-01 2023-01-01 done  with   spaces @context",
+            "call  mom +family due:2023-06-30",
+        ];
+        let input = tasks.map(|task| format!("    {task}  ")).join("\n");
+        FileWorker::load_tasks(input.as_bytes(), &mut todo, &config)?;
+        let mut buf: Vec<u8> = Vec::new();
+        FileWorker::save_tasks(
+            &mut buf,
+            todo.pending.iter().chain(&todo.done),
+            &[],
+            &config,
+        )?;
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            [tasks[0], tasks[2], tasks[1]]
+                .map(|task| format!("{task}\n"))
+                .concat()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_write_tasks() -> ioResult<()> {
+        let config = FileWorkerConfig::new(&Config::default());
         let mut todo = ToDo::default();
-        FileWorker::load_tasks(TESTING_STRING.as_bytes(), &mut todo)?;
+        FileWorker::load_tasks(TESTING_STRING.as_bytes(), &mut todo, &config)?;
         let get_expected = |line: fn(&String) -> bool| {
             TESTING_STRING
                 .trim()
@@ -331,7 +1057,7 @@ mod tests {
         };
         let pretty_assert = |tasks, expected: &str, msg: &str| -> ioResult<()> {
             let mut buf: Vec<u8> = Vec::new();
-            FileWorker::save_tasks(&mut buf, tasks)?;
+            FileWorker::save_tasks(&mut buf, tasks, &[], &config)?;
             assert_eq!(
                 expected.as_bytes(),
                 buf,
@@ -357,4 +1083,603 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn debounce_merges_bursts() {
+        let start = Instant::now();
+        let window = Duration::from_millis(100);
+        let mut debounce = Debounce::new(window);
+        assert_eq!(debounce.timeout(start), None);
+        assert!(!debounce.ready(start));
+
+        debounce.event(start);
+        debounce.event(start + Duration::from_millis(50));
+        assert!(!debounce.ready(start + Duration::from_millis(100)));
+        assert_eq!(
+            debounce.timeout(start + Duration::from_millis(100)),
+            Some(Duration::from_millis(50))
+        );
+
+        assert!(debounce.ready(start + Duration::from_millis(150)));
+        assert!(!debounce.ready(start + Duration::from_millis(200)));
+        assert_eq!(debounce.timeout(start + Duration::from_millis(200)), None);
+    }
+
+    #[test]
+    fn debounce_zero_window() {
+        let now = Instant::now();
+        let mut debounce = Debounce::new(Duration::ZERO);
+        debounce.event(now);
+        assert_eq!(debounce.timeout(now), Some(Duration::ZERO));
+        assert!(debounce.ready(now));
+    }
+
+    #[test]
+    fn test_keep_raw_lines() -> ioResult<()> {
+        const FILE: &str = "# header\n(A) first task\n\n#hashtag second task\n# footer\n";
+        let config = FileWorkerConfig::new(&Config::default());
+        let mut todo = ToDo::default();
+        let raw_lines = FileWorker::load_tasks(FILE.as_bytes(), &mut todo, &config)?;
+        assert_eq!(todo.pending.len(), 2);
+        assert_eq!(
+            raw_lines,
+            vec![
+                (0, String::from("# header")),
+                (2, String::from("")),
+                (4, String::from("# footer")),
+            ]
+        );
+
+        let mut buf: Vec<u8> = Vec::new();
+        FileWorker::save_tasks(&mut buf, &todo.pending, &raw_lines, &config)?;
+        assert_eq!(String::from_utf8(buf).unwrap(), FILE);
+
+        todo.pending.remove(0);
+        let mut buf: Vec<u8> = Vec::new();
+        FileWorker::save_tasks(&mut buf, &todo.pending, &raw_lines, &config)?;
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "# header\n#hashtag second task\n\n# footer\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_ending() -> ioResult<()> {
+        let mut todo = ToDo::default();
+        let mut config = FileWorkerConfig::new(&Config::default());
+        FileWorker::load_tasks(
+            "first task\r\nsecond task\r\n".as_bytes(),
+            &mut todo,
+            &config,
+        )?;
+        assert_eq!(todo.pending[0].subject, "first task");
+
+        let mut buf: Vec<u8> = Vec::new();
+        config.line_ending = LineEnding::CrLf;
+        FileWorker::save_tasks(&mut buf, &todo.pending, &[], &config)?;
+        assert_eq!(buf, b"first task\r\nsecond task\r\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_switch_file() -> ioResult<()> {
+        let dir = TempDir::new("switch");
+        let first = dir.join("first.txt");
+        let second = dir.join("second.txt");
+        std::fs::write(&first, "first task\n")?;
+        std::fs::write(&second, "second task\nanother second task\n")?;
+
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = first.to_string_lossy().into_owned();
+        config.archive_path = None;
+        config.todo_files = vec![
+            (String::from("first"), first.clone()),
+            (String::from("second"), second.clone()),
+        ];
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        let mut worker = FileWorker::new(config, todo.clone());
+        worker.load()?;
+        assert_eq!(todo.lock().unwrap().pending.len(), 1);
+
+        worker.switch_to(second.clone())?;
+        {
+            let todo = todo.lock().unwrap();
+            assert_eq!(todo.pending.len(), 2);
+            assert_eq!(todo.pending[0].subject, "second task");
+        }
+
+        assert!(worker.switch_to(dir.join("missing.txt")).is_err());
+        assert_eq!(todo.lock().unwrap().pending[0].subject, "second task");
+
+        worker.switch_to(first)?;
+        assert_eq!(todo.lock().unwrap().pending[0].subject, "first task");
+
+        Ok(())
+    }
+
+    const MALFORMED: &str = "first task\nbroken task due:someday\nlast task t:2023-06-30\n";
+
+    #[test]
+    fn test_comments_are_not_tasks() -> ioResult<()> {
+        const FILE: &str =
+            "# header\n(A) first task\n#   indented comment\nx done task\n# footer\n";
+        let dir = TempDir::new("comments");
+        let path = dir.join("todo.txt");
+        std::fs::write(&path, FILE)?;
+
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        let worker = FileWorker::new(config, todo.clone());
+        worker.load()?;
+        {
+            let mut todo = todo.lock().unwrap();
+            assert_eq!(todo.take_load_error(), None);
+            assert_eq!(todo.len(ToDoData::Pending), 1);
+            assert_eq!(todo.len(ToDoData::Done), 1);
+            let subjects = |data| -> Vec<String> {
+                todo.get_filtered_and_sorted(data)
+                    .vec
+                    .iter()
+                    .map(|(_, task)| task.subject.clone())
+                    .collect()
+            };
+            assert_eq!(subjects(ToDoData::Pending), ["first task"]);
+            assert_eq!(subjects(ToDoData::Done), ["done task"]);
+        }
+
+        worker.save()?;
+        assert_eq!(std::fs::read_to_string(&path)?, FILE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error_skip() -> ioResult<()> {
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.on_parse_error = ParseErrorPolicy::Skip;
+        let mut todo = ToDo::default();
+        let raw_lines = FileWorker::load_tasks(MALFORMED.as_bytes(), &mut todo, &config)?;
+        assert_eq!(todo.pending.len(), 2);
+        assert!(raw_lines.is_empty());
+        assert_eq!(
+            todo.take_load_error(),
+            Some(String::from(
+                "Line 2 cannot be parsed: invalid date due:someday, it is skipped"
+            ))
+        );
+
+        let mut buf: Vec<u8> = Vec::new();
+        FileWorker::save_tasks(&mut buf, &todo.pending, &raw_lines, &config)?;
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "first task\nlast task t:2023-06-30\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error_keep_raw() -> ioResult<()> {
+        let mut config = FileWorkerConfig::new(&Config::default());
+        assert_eq!(config.on_parse_error, ParseErrorPolicy::KeepRaw);
+        let mut todo = ToDo::default();
+        let raw_lines = FileWorker::load_tasks(MALFORMED.as_bytes(), &mut todo, &config)?;
+        assert_eq!(todo.pending.len(), 2);
+        assert_eq!(
+            raw_lines,
+            vec![(1, String::from("broken task due:someday"))]
+        );
+        assert_eq!(
+            todo.take_load_error(),
+            Some(String::from(
+                "Line 2 cannot be parsed: invalid date due:someday, it is kept unchanged in the file"
+            ))
+        );
+
+        let mut buf: Vec<u8> = Vec::new();
+        FileWorker::save_tasks(&mut buf, &todo.pending, &raw_lines, &config)?;
+        assert_eq!(String::from_utf8(buf).unwrap(), MALFORMED);
+
+        // Kept lines are reported to the shared todo list after the load.
+        let dir = TempDir::new("keep-raw");
+        let path = dir.join("todo.txt");
+        std::fs::write(&path, MALFORMED)?;
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        FileWorker::new(config, todo.clone()).load()?;
+        assert_eq!(todo.lock().unwrap().pending.len(), 2);
+        assert!(todo.lock().unwrap().take_load_error().is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error_abort() -> ioResult<()> {
+        use FileWorkerCommands::*;
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.on_parse_error = ParseErrorPolicy::Abort;
+        let error = FileWorker::load_tasks(MALFORMED.as_bytes(), &mut ToDo::default(), &config)
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().starts_with("Line 2 cannot be parsed"));
+
+        let dir = TempDir::new("abort");
+        let path = dir.join("todo.txt");
+        std::fs::write(&path, "first task\n")?;
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        let mut worker = FileWorker::new(config, todo.clone());
+        worker.load()?;
+        worker.remember_saved();
+
+        // The file is not loaded and it is not overwritten by the old tasks.
+        std::fs::write(&path, MALFORMED)?;
+        assert!(worker.handle_command(Load).is_err());
+        assert!(todo.lock().unwrap().take_load_error().is_some());
+        assert_eq!(todo.lock().unwrap().pending.len(), 1);
+        todo.lock().unwrap().new_task("new task").unwrap();
+        worker.handle_command(ForceSave)?;
+        assert_eq!(std::fs::read_to_string(&path)?, MALFORMED);
+
+        // Saving works again after the file is fixed and loaded.
+        std::fs::write(&path, "first task\n")?;
+        worker.handle_command(Load)?;
+        todo.lock().unwrap().new_task("new task").unwrap();
+        worker.handle_command(ForceSave)?;
+        assert!(std::fs::read_to_string(&path)?.contains("new task"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_switch_after_parse_error_abort() -> ioResult<()> {
+        use FileWorkerCommands::*;
+        let dir = TempDir::new("abort-switch");
+        let path = dir.join("todo.txt");
+        let other = dir.join("other.txt");
+        std::fs::write(&path, "first task\n")?;
+        std::fs::write(&other, "other task\n")?;
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.on_parse_error = ParseErrorPolicy::Abort;
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        let mut worker = FileWorker::new(config, todo.clone());
+        worker.handle_command(Load)?;
+
+        // The malformed file is not overwritten by the old tasks on switch.
+        std::fs::write(&path, MALFORMED)?;
+        assert!(worker.handle_command(Load).is_err());
+        todo.lock().unwrap().new_task("new task").unwrap();
+        worker.handle_command(SwitchTo(other.clone()))?;
+        assert_eq!(std::fs::read_to_string(&path)?, MALFORMED);
+        assert_eq!(todo.lock().unwrap().pending[0].subject, "other task");
+
+        // The switched file can be saved.
+        todo.lock().unwrap().new_task("saved task").unwrap();
+        worker.handle_command(Save)?;
+        assert!(std::fs::read_to_string(&other)?.contains("saved task"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_conflict() -> ioResult<()> {
+        use FileWorkerCommands::*;
+        let dir = TempDir::new("conflict");
+        let path = dir.join("todo.txt");
+        std::fs::write(&path, "first task\n")?;
+
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        let mut worker = FileWorker::new(config, todo.clone());
+        worker.load()?;
+        worker.remember_saved();
+
+        // Without unsaved changes the file is loaded.
+        std::fs::write(&path, "first task\nexternal task\n")?;
+        worker.handle_command(FileChanged)?;
+        assert_eq!(todo.lock().unwrap().pending.len(), 2);
+        assert!(!todo.lock().unwrap().take_file_conflict());
+
+        // Changes in memory and in the file at the same time.
+        todo.lock().unwrap().new_task("memory task").unwrap();
+        std::fs::write(&path, "first task\nexternal task\nanother external task\n")?;
+        worker.handle_command(FileChanged)?;
+        assert!(todo.lock().unwrap().take_file_conflict());
+        assert_eq!(todo.lock().unwrap().pending[2].subject, "memory task");
+        let other = dir.join("other.txt");
+        std::fs::write(&other, "other task\n")?;
+        assert!(worker.handle_command(SwitchTo(other)).is_err());
+        assert_eq!(todo.lock().unwrap().pending[2].subject, "memory task");
+        worker.handle_command(Save)?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "first task\nexternal task\nanother external task\n",
+        );
+
+        worker.handle_command(Merge)?;
+        let subjects = |todo: &ToDo| -> Vec<String> {
+            todo.pending.iter().map(|t| t.subject.clone()).collect()
+        };
+        assert_eq!(
+            subjects(&todo.lock().unwrap()),
+            [
+                "first task",
+                "external task",
+                "another external task",
+                "memory task"
+            ]
+        );
+        assert_eq!(std::fs::read_to_string(&path)?.lines().count(), 4);
+
+        // Own save does not cause a conflict, reload discards memory changes.
+        worker.handle_command(FileChanged)?;
+        worker.handle_command(FileChanged)?;
+        todo.lock().unwrap().new_task("discarded task").unwrap();
+        std::fs::write(&path, "first task\n")?;
+        worker.handle_command(FileChanged)?;
+        assert!(todo.lock().unwrap().take_file_conflict());
+        worker.handle_command(Load)?;
+        assert_eq!(subjects(&todo.lock().unwrap()), ["first task"]);
+        worker.handle_command(Save)?;
+        worker.handle_command(FileChanged)?;
+        assert!(!todo.lock().unwrap().take_file_conflict());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_mode() -> ioResult<()> {
+        assert_eq!(
+            ArchiveMode::Inline.archive_path("/home/user/todo.txt"),
+            None
+        );
+        assert_eq!(
+            ArchiveMode::SiblingDone.archive_path("/home/user/todo.txt"),
+            Some(String::from("/home/user/done.txt"))
+        );
+        assert_eq!(
+            ArchiveMode::SiblingDone.archive_path("todo.txt"),
+            Some(String::from("done.txt"))
+        );
+        assert_eq!(
+            ArchiveMode::Explicit(String::from("/archive.txt")).archive_path("/todo.txt"),
+            Some(String::from("/archive.txt"))
+        );
+        assert_eq!(
+            ArchiveMode::from_str("SiblingDone")?,
+            ArchiveMode::SiblingDone
+        );
+        assert_eq!(
+            ArchiveMode::from_str("/archive.txt")?,
+            ArchiveMode::Explicit(String::from("/archive.txt"))
+        );
+
+        let dir = TempDir::new("sibling");
+        let todo_path = dir.join("todo.txt");
+        std::fs::write(&todo_path, "first task\nsecond task\n")?;
+        let config = Config::load_from_buffer(
+            format!(
+                "todo_path = \"{}\"\narchive_mode = \"SiblingDone\"",
+                todo_path.display()
+            )
+            .as_bytes(),
+        );
+        let worker_config = FileWorkerConfig::new(&config);
+        let done_path = dir.join("done.txt");
+        assert_eq!(
+            worker_config.archive_path,
+            Some(done_path.to_string_lossy().into_owned())
+        );
+
+        // Missing done.txt is created on save.
+        let todo = Arc::new(Mutex::new(ToDo::new(&config)));
+        let worker = FileWorker::new(worker_config, todo.clone());
+        worker.load()?;
+        todo.lock().unwrap().move_task(ToDoData::Pending, 0);
+        worker.save()?;
+        assert_eq!(std::fs::read_to_string(&todo_path)?, "second task\n");
+        assert_eq!(std::fs::read_to_string(&done_path)?, "x first task\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_relocates_inline_done() -> ioResult<()> {
+        let dir = TempDir::new("inline");
+        let path = dir.join("todo.txt");
+        let config = Config::load_from_buffer("inline_done = true".as_bytes());
+        let mut todo = ToDo::new(&config);
+        FileWorker::load_tasks(
+            "first task\nsecond task\n".as_bytes(),
+            &mut todo,
+            &FileWorkerConfig::new(&config),
+        )?;
+        todo.move_task(ToDoData::Pending, 0);
+        assert_eq!(todo.pending.len(), 2);
+        assert!(todo.pending[0].finished);
+
+        let todo = Arc::new(Mutex::new(todo));
+        let mut worker_config = FileWorkerConfig::new(&config);
+        worker_config.todo_path = path.to_string_lossy().into_owned();
+        worker_config.archive_path = None;
+        FileWorker::new(worker_config, todo.clone()).save()?;
+        {
+            let todo = todo.lock().unwrap();
+            assert_eq!(todo.pending.len(), 1);
+            assert_eq!(todo.done.len(), 1);
+            assert_eq!(todo.done[0].subject, "first task");
+        }
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "second task\nx first task\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_relocates_reopened_done() -> ioResult<()> {
+        let dir = TempDir::new("reopened");
+        let todo_path = dir.join("todo.txt");
+        let done_path = dir.join("done.txt");
+        std::fs::write(&todo_path, "pending task\n")?;
+        std::fs::write(&done_path, "x first done task\nx second done task\n")?;
+
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = todo_path.to_string_lossy().into_owned();
+        config.archive_path = Some(done_path.to_string_lossy().into_owned());
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        let worker = FileWorker::new(config, todo.clone());
+        worker.load()?;
+        todo.lock().unwrap().toggle_finished(ToDoData::Done, 0);
+        worker.save()?;
+        assert_eq!(
+            std::fs::read_to_string(&todo_path)?,
+            "pending task\nfirst done task\n"
+        );
+        assert_eq!(std::fs::read_to_string(&done_path)?, "x second done task\n");
+
+        // The reopened task is loaded back as a pending task.
+        worker.load()?;
+        let todo = todo.lock().unwrap();
+        assert_eq!(todo.pending.len(), 2);
+        assert_eq!(todo.done.len(), 1);
+        assert_eq!(todo.pending[1].subject, "first done task");
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_save_hooks() -> ioResult<()> {
+        let dir = TempDir::new("save-hooks");
+        let path = dir.join("todo.txt");
+        let sentinel = dir.join("sentinel");
+        // Copy of the saved file proves the hook was executed after the save.
+        let hook = dir.script(
+            "post_save.sh",
+            &format!("#!/bin/sh\ncp \"$1\" {}\n", sentinel.display()),
+        )?;
+
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        config.hook_paths.post_save = Some(hook);
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        todo.lock().unwrap().new_task("saved task").unwrap();
+        FileWorker::new(config, todo).save()?;
+
+        let saved = std::fs::read_to_string(&path)?;
+        assert!(saved.contains("saved task"));
+        assert_eq!(std::fs::read_to_string(&sentinel)?, saved);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_autocommit() -> ioResult<()> {
+        let dir = TempDir::new("git");
+        let git = |args: &[&str]| -> ioResult<String> {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(args)
+                .output()?;
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        git(&["init", "-q"])?;
+        git(&["config", "user.name", "test"])?;
+        git(&["config", "user.email", "test@example.com"])?;
+        // Other staged changes of the repository are not committed.
+        std::fs::write(dir.join("other.txt"), "other")?;
+        git(&["add", "other.txt"])?;
+
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = dir.join("todo.txt").to_string_lossy().into_owned();
+        config.archive_path = None;
+        config.git_autocommit = true;
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        todo.lock().unwrap().new_task("committed task").unwrap();
+        let worker = FileWorker::new(config, todo.clone());
+        worker.save()?;
+        // Save without changes does not create an empty commit.
+        worker.save()?;
+
+        let log = git(&["log", "--format=%s"])?;
+        assert_eq!(log.lines().count(), 1);
+        assert!(log.starts_with("todotxt-tui autosave "));
+        assert_eq!(git(&["status", "--porcelain"])?, "A  other.txt\n");
+        assert!(todo.lock().unwrap().take_save_error().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_autocommit_relative_path() -> ioResult<()> {
+        let dir = TempDir::new("git-relative");
+        let git = |args: &[&str]| -> ioResult<String> {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(args)
+                .output()?;
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        git(&["init", "-q"])?;
+        git(&["config", "user.name", "test"])?;
+        git(&["config", "user.email", "test@example.com"])?;
+        std::fs::create_dir(dir.join("sub"))?;
+
+        // Path of the directory relative to the working directory of the test.
+        let cwd = std::env::current_dir()?.canonicalize()?;
+        let relative: PathBuf = cwd
+            .components()
+            .skip(1)
+            .map(|_| Path::new(".."))
+            .collect::<PathBuf>()
+            .join(dir.path().canonicalize()?.strip_prefix("/").unwrap())
+            .join("sub");
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = relative.join("todo.txt").to_string_lossy().into_owned();
+        config.archive_path = Some(relative.join("done.txt").to_string_lossy().into_owned());
+        config.git_autocommit = true;
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        todo.lock().unwrap().new_task("committed task").unwrap();
+        FileWorker::new(config, todo.clone()).save()?;
+
+        assert!(todo.lock().unwrap().take_save_error().is_none());
+        let files = git(&["show", "--name-only", "--format="])?;
+        assert_eq!(files, "sub/done.txt\nsub/todo.txt\n");
+        assert_eq!(git(&["status", "--porcelain"])?, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_spacing() -> ioResult<()> {
+        const FILE: &str = "# header\nfirst task\n\n\nsecond task\n\n\nthird task\n";
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.task_spacing = 2;
+        let mut todo = ToDo::default();
+        let raw_lines = FileWorker::load_tasks(FILE.as_bytes(), &mut todo, &config)?;
+        assert_eq!(todo.pending.len(), 3);
+        assert_eq!(raw_lines, vec![(0, String::from("# header"))]);
+
+        let mut buf: Vec<u8> = Vec::new();
+        FileWorker::save_tasks(&mut buf, &todo.pending, &raw_lines, &config)?;
+        assert_eq!(String::from_utf8(buf).unwrap(), FILE);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,199 @@
+use crate::{
+    config::{Command, Config, CountArgs, FileWorkerConfig},
+    file_worker::FileWorker,
+    todo::{counts::TaskCounts, get_actual_date, ToDo},
+};
+use std::{
+    error::Error,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+/// Runs the command without starting the user interface.
+/// Commands changing tasks always save them, regardless of the save policy.
+///
+/// # Arguments
+///
+/// * `config` - Configuration with paths to the todo files.
+/// * `command` - The command to run.
+/// * `out` - Output the result is written to.
+pub fn run_command(
+    config: &Config,
+    command: &Command,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let todo = Arc::new(Mutex::new(ToDo::new(config)));
+    let file_worker = FileWorker::new(FileWorkerConfig::new(config), todo.clone());
+    file_worker.load()?;
+    match command {
+        Command::Count(args) => write_counts(
+            &TaskCounts::new(&todo.lock().unwrap(), get_actual_date()),
+            args,
+            out,
+        ),
+        Command::Add { task } => {
+            todo.lock().unwrap().new_task(task)?;
+            file_worker.save()?;
+            Ok(())
+        }
+    }
+}
+
+/// Writes the selected counts, a single count is written as a plain number.
+fn write_counts(
+    counts: &TaskCounts,
+    args: &CountArgs,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let all = [
+        ("pending", args.pending, counts.pending),
+        ("done", args.done, counts.done),
+        ("overdue", args.overdue, counts.overdue),
+        ("due today", args.due_today, counts.due_today),
+    ];
+    let selected: Vec<_> = all.iter().filter(|(_, selected, _)| *selected).collect();
+    match selected.as_slice() {
+        [(_, _, count)] => writeln!(out, "{count}")?,
+        [] => {
+            for (name, _, count) in &all {
+                writeln!(out, "{name}: {count}")?;
+            }
+        }
+        selected => {
+            for (name, _, count) in selected {
+                writeln!(out, "{name}: {count}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+    use std::env;
+
+    fn fixture_config() -> Result<Config, Box<dyn Error>> {
+        Ok(Config::load_from_buffer(
+            format!(
+                "todo_path = \"{}count_todo.txt\"",
+                env::var("TODO_TUI_TEST_DIR")?
+            )
+            .as_bytes(),
+        ))
+    }
+
+    fn run(args: CountArgs) -> Result<String, Box<dyn Error>> {
+        let mut out = Vec::new();
+        run_command(&fixture_config()?, &Command::Count(args), &mut out)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn count() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            run(CountArgs::default())?,
+            "pending: 3\ndone: 2\noverdue: 1\ndue today: 0\n"
+        );
+        assert_eq!(
+            run(CountArgs {
+                pending: true,
+                ..Default::default()
+            })?,
+            "3\n"
+        );
+        assert_eq!(
+            run(CountArgs {
+                done: true,
+                overdue: true,
+                ..Default::default()
+            })?,
+            "done: 2\noverdue: 1\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn add() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("add");
+        let path = dir.join("todo.txt");
+        std::fs::copy(
+            format!("{}count_todo.txt", env::var("TODO_TUI_TEST_DIR")?),
+            &path,
+        )?;
+        let config =
+            Config::load_from_buffer(format!("todo_path = \"{}\"", path.display()).as_bytes());
+        run_command(
+            &config,
+            &Command::Add {
+                task: String::from("buy milk +groceries due:today"),
+            },
+            &mut Vec::new(),
+        )?;
+
+        let today = get_actual_date().format("%Y-%m-%d");
+        let content = std::fs::read_to_string(&path)?;
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[3],
+            format!("{today} buy milk +groceries due:{today}"),
+            "new pending task is saved after other pending tasks"
+        );
+        assert_eq!(lines[4], "x 2023-02-01 2023-01-01 done task");
+
+        let mut out = Vec::new();
+        run_command(&config, &Command::Count(CountArgs::default()), &mut out)?;
+        assert!(String::from_utf8(out)?.starts_with("pending: 4\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn add_runs_post_hook() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("add-hook");
+        let path = dir.join("todo.txt");
+        let sentinel = dir.join("sentinel");
+        std::fs::write(&path, "")?;
+        let hook = dir.script(
+            "post_new.sh",
+            &format!(
+                "#!/bin/sh\nsleep 0.1\necho \"$1\" > {}\n",
+                sentinel.display()
+            ),
+        )?;
+        let config = Config::load_from_buffer(
+            format!(
+                "todo_path = \"{}\"\n[hook_paths]\npost_new = \"{}\"\n",
+                path.display(),
+                hook.display()
+            )
+            .as_bytes(),
+        );
+        run_command(
+            &config,
+            &Command::Add {
+                task: String::from("hooked task"),
+            },
+            &mut Vec::new(),
+        )?;
+
+        // The command returns only after the queued post hook is executed.
+        assert!(std::fs::read_to_string(&sentinel)?.ends_with("hooked task\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_missing_file() {
+        let config = Config::load_from_buffer("todo_path = \"/nonexistent/todo.txt\"".as_bytes());
+        let result = run_command(
+            &config,
+            &Command::Count(CountArgs::default()),
+            &mut Vec::new(),
+        );
+        assert!(result.is_err());
+    }
+}
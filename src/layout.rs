@@ -3,12 +3,17 @@ mod render_trait;
 pub mod widget;
 
 use crate::{
-    config::Config, layout::widget::State, todo::ToDo, ui::HandleEvent, ToDoError, ToDoRes,
+    config::Config,
+    layout::widget::State,
+    todo::{task_list::TaskSort, ToDo},
+    ui::{HandleEvent, UIEvent},
+    ToDoError, ToDoRes,
 };
+use clap::ValueEnum;
 use container::Container;
 use crossterm::event::KeyEvent;
 use std::{fmt::Debug, sync::Arc, sync::Mutex};
-use widget::{widget_type::WidgetType, Widget};
+use widget::{widget_type::WidgetType, ListPosition, Widget};
 
 pub use render_trait::Render;
 
@@ -24,6 +29,7 @@ const ITEM_SEPARATOR: char = ',';
 const ARG_SEPARATOR: char = ':';
 const START_CONTAINER: char = '[';
 const END_CONTAINER: char = ']';
+const SORT_OPTION: &str = "sort:";
 
 const LEFT: Site = Site {
     direction: Direction::Horizontal,
@@ -114,6 +120,29 @@ impl Layout {
         }
     }
 
+    /// Parse a task sort from a string. Case and dashes are ignored,
+    /// so both `AlphanumericReverse` and `alphanumeric-reverse` are valid.
+    ///
+    /// # Parameters
+    ///
+    /// - `value`: A string slice with the name of the sort.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ToDoRes` containing the `TaskSort` or an error if the sort is unknown.
+    fn sort_from_string(value: &str) -> ToDoRes<TaskSort> {
+        let normalize = |s: &str| s.replace('-', "").to_lowercase();
+        let value = normalize(value);
+        TaskSort::value_variants()
+            .iter()
+            .find(|sort| {
+                sort.to_possible_value()
+                    .is_some_and(|v| normalize(v.get_name()) == value)
+            })
+            .copied()
+            .ok_or(ToDoError::ParseUnknownValue(value))
+    }
+
     fn process_item(
         item: &str,
         container: &mut Container,
@@ -122,8 +151,15 @@ impl Layout {
     ) -> ToDoRes<Option<Constraint>> {
         log::trace!("Process item: {item}");
         let s = item.to_lowercase();
+        let (options, s): (Vec<&str>, Vec<&str>) = s
+            .split_whitespace()
+            .partition(|token| token.starts_with(SORT_OPTION));
+        let s = s.concat();
         let x: Vec<&str> = s.splitn(2, ARG_SEPARATOR).map(|s| s.trim()).collect();
         let x = (x[0], if x.len() > 1 { Some(x[1]) } else { None });
+        if !options.is_empty() && matches!(x.0, "direction" | "size") {
+            return Err(ToDoError::ParseUnknownValue(options.join(" ")));
+        }
         match x.0 {
             "direction" => {
                 match x.1 {
@@ -137,16 +173,28 @@ impl Layout {
             }
             "size" => Ok(Some(Self::value_from_string(x.1)?)),
             _ => {
-                container.add_widget(Widget::new(
-                    WidgetType::from_str(x.0)?,
-                    data.clone(),
-                    config,
-                )?);
+                let widget_type = <WidgetType as FromStr>::from_str(x.0)?;
+                let mut widget = Widget::new(widget_type, data.clone(), config)?;
+                for option in options {
+                    let sort = Self::sort_from_string(&option[SORT_OPTION.len()..])?;
+                    match &mut widget {
+                        Widget::List(list) => list.set_sort(sort),
+                        _ => return Err(ToDoError::ParseUnknownValue(option.to_string())),
+                    }
+                }
+                container.add_widget(widget);
                 Ok(Some(Self::value_from_string(x.1)?))
             }
         }
     }
 
+    /// Checks that the layout of the configuration can be created,
+    /// without loading any tasks.
+    pub fn check(config: &Config) -> ToDoRes<()> {
+        let data = Arc::new(Mutex::new(ToDo::new(config)));
+        Self::from_str(&config.get_layout(), data, config).map(|_| ())
+    }
+
     /// Create a new `Layout` from a template string.
     ///
     /// This function parses a template string and creates a new `Layout` instance based on the
@@ -184,9 +232,10 @@ impl Layout {
         for ch in template.chars() {
             match ch {
                 START_CONTAINER => {
-                    if !string.is_empty() {
+                    if !string.trim().is_empty() {
                         return Err(ToDoError::ParseUnknowBeforeContainer(string));
                     }
+                    string.clear();
                     if layout.act().item_count() >= constraints_stack.last().unwrap().len() {
                         constraints_stack
                             .last_mut()
@@ -203,6 +252,14 @@ impl Layout {
                     constraints_stack.push(Vec::new());
                 }
                 END_CONTAINER => {
+                    // The last item of the container may not be followed by ITEM_SEPARATOR
+                    if !string.trim().is_empty() {
+                        if let Some(constrain) =
+                            Self::process_item(&string, layout.act_mut(), data.clone(), config)?
+                        {
+                            constraints_stack.last_mut().unwrap().push(constrain);
+                        }
+                    }
                     log::trace!(
                         "Act: {}, Constraints: {:?}",
                         layout.act,
@@ -215,6 +272,13 @@ impl Layout {
                         Some(parent) => parent,
                         // We are at root. Return created layout.
                         None => {
+                            if layout
+                                .containers
+                                .iter()
+                                .all(|container| container.widgets().next().is_none())
+                            {
+                                return Err(ToDoError::ParseNoWidget);
+                            }
                             Container::actualize_layout(&mut layout);
                             layout.act_mut().actual_mut().unwrap().focus();
                             return Ok(layout);
@@ -224,18 +288,18 @@ impl Layout {
                 }
                 ITEM_SEPARATOR => {
                     // Skip leading ITEM_SEPARATOR
-                    if !string.is_empty() {
+                    if !string.trim().is_empty() {
                         if let Some(constrain) =
                             Self::process_item(&string, layout.act_mut(), data.clone(), config)?
                         {
                             // TODO UNWRAP
                             constraints_stack.last_mut().unwrap().push(constrain);
                         }
-                        string.clear();
                     }
+                    string.clear();
                 }
-                ' ' => {}
-                '\n' => {}
+                // Whitespace separates options of the item
+                ch if ch.is_whitespace() => string.push(' '),
                 _ => string.push(ch),
             };
         }
@@ -367,6 +431,176 @@ impl Layout {
         }
     }
 
+    /// Gets the event the key triggers in the currently focused widget.
+    pub fn get_event(&self, event: &KeyEvent) -> UIEvent {
+        match self.act().actual() {
+            Some(widget) => widget.get_event(&event.code),
+            None => panic!("Actual is not widget"),
+        }
+    }
+
+    /// Passes the event to the currently focused widget.
+    ///
+    /// # Parameters
+    ///
+    /// - `event`: The event to be handled.
+    pub fn handle_event(&mut self, event: UIEvent) -> bool {
+        match self.act_mut().actual_mut() {
+            Some(widget) => widget.handle_event(event),
+            None => panic!("Actual is not widget"),
+        }
+    }
+
+    /// Passes the event to all widgets in the layout, even to not focused ones.
+    ///
+    /// # Parameters
+    ///
+    /// - `event`: The event to be handled.
+    ///
+    /// # Returns
+    ///
+    /// `true` if any widget handled the event.
+    pub fn broadcast_event(&mut self, event: UIEvent) -> bool {
+        let mut handled = false;
+        for widget in self
+            .containers
+            .iter_mut()
+            .flat_map(|container| container.widgets_mut())
+        {
+            handled |= widget.handle_event(event.clone());
+        }
+        handled
+    }
+
+    /// Finds the widget on the position.
+    ///
+    /// # Parameters
+    ///
+    /// - `column`: The column of the position.
+    /// - `row`: The row of the position.
+    ///
+    /// # Returns
+    ///
+    /// The type of the widget on the position, `None` if there is no widget.
+    pub fn find_widget(&self, column: u16, row: u16) -> Option<WidgetType> {
+        self.containers
+            .iter()
+            .flat_map(|container| container.widgets())
+            .find(|widget| {
+                let chunk = widget.get_base().chunk;
+                (chunk.left()..chunk.right()).contains(&column)
+                    && (chunk.top()..chunk.bottom()).contains(&row)
+            })
+            .map(|widget| widget.widget_type())
+    }
+
+    /// Scrolls the list on the position. The list is focused first, so
+    /// the selection moves in it.
+    ///
+    /// # Parameters
+    ///
+    /// - `column`: The column of the position.
+    /// - `row`: The row of the position.
+    /// - `down`: Scroll direction.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the list was scrolled.
+    pub fn scroll(&mut self, column: u16, row: u16, down: bool) -> bool {
+        let Some(widget_type) = self.find_widget(column, row) else {
+            return false;
+        };
+        if let Err(e) = self.select_widget(widget_type) {
+            log::debug!("Cannot scroll widget {}: {}", widget_type, e);
+            return false;
+        }
+        // Refreshes the length of the list if it was already focused.
+        self.focus();
+        self.handle_event(if down {
+            UIEvent::ListDown
+        } else {
+            UIEvent::ListUp
+        })
+    }
+
+    /// Focuses the widget on the position and selects the item under it.
+    ///
+    /// # Parameters
+    ///
+    /// - `column`: The column of the position.
+    /// - `row`: The row of the position.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an item was selected.
+    pub fn click(&mut self, column: u16, row: u16) -> bool {
+        let Some(widget_type) = self.find_widget(column, row) else {
+            return false;
+        };
+        if let Err(e) = self.select_widget(widget_type) {
+            log::debug!("Cannot click on widget {}: {}", widget_type, e);
+            return false;
+        }
+        self.containers
+            .iter_mut()
+            .flat_map(|container| container.widgets_mut())
+            .find(|widget| widget.widget_type() == widget_type)
+            .is_some_and(|widget| widget.click(row))
+    }
+
+    /// Moves the focus to the widget of the given type.
+    ///
+    /// # Parameters
+    ///
+    /// - `widget_type`: The type of the widget to focus.
+    pub fn select_widget(&mut self, widget_type: WidgetType) -> ToDoRes<()> {
+        if self.get_active_widget() == widget_type {
+            return Ok(());
+        }
+        let old = Holder::new(self);
+        Container::select_widget(self, widget_type)?;
+        if self.focus() {
+            old.unfocus(self);
+            Ok(())
+        } else {
+            old.set_old_back(self);
+            Err(ToDoError::WidgetCannotFocus)
+        }
+    }
+
+    /// Gets the scroll positions of all list widgets in the layout.
+    pub fn get_positions(&self) -> Vec<(WidgetType, ListPosition)> {
+        self.containers
+            .iter()
+            .flat_map(|container| container.widgets())
+            .filter_map(|widget| Some((widget.widget_type(), widget.get_position()?)))
+            .collect()
+    }
+
+    /// Restores the scroll positions of list widgets. Positions of widgets
+    /// that are not in the layout are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `positions`: The positions of widgets to restore.
+    pub fn set_positions(&mut self, positions: &[(WidgetType, ListPosition)]) {
+        for widget in self
+            .containers
+            .iter_mut()
+            .flat_map(|container| container.widgets_mut())
+        {
+            let widget_type = widget.widget_type();
+            if let Some((_, position)) = positions.iter().find(|(t, _)| *t == widget_type) {
+                widget.set_position(*position);
+            }
+        }
+    }
+
+    /// Gets the scroll position of the active widget, `None` if it is not a list.
+    pub fn get_active_position(&self) -> Option<ListPosition> {
+        self.act().actual()?.get_position()
+    }
+
     pub fn get_active_widget(&self) -> WidgetType {
         match self.act().get_active_type() {
             Some(widget_type) => widget_type,
@@ -402,6 +636,7 @@ impl Render for Layout {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::todo::ToDoData;
 
     fn mock_layout() -> Layout {
         let mock_layout = r#"
@@ -518,4 +753,145 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_progress_from_string() -> ToDoRes<()> {
+        let str_layout = r#"
+            [
+              Direction: Vertical,
+              Progress: 10%,
+              List,
+            ]
+        "#;
+        let mut layout = Layout::from_str(
+            str_layout,
+            Arc::new(Mutex::new(ToDo::default())),
+            &Config::default(),
+        )?;
+        layout.update_chunk(Rect::new(0, 0, 100, 50));
+        assert_eq!(layout.find_widget(1, 0), Some(WidgetType::Progress));
+        assert_eq!(layout.find_widget(1, 4), Some(WidgetType::Progress));
+        assert_eq!(layout.find_widget(1, 5), Some(WidgetType::List));
+        layout.select_widget(WidgetType::List)?;
+        assert_eq!(
+            layout.select_widget(WidgetType::Progress),
+            Err(ToDoError::WidgetCannotFocus)
+        );
+        assert_eq!(layout.get_active_widget(), WidgetType::List);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_categories_from_string() -> ToDoRes<()> {
+        let str_layout = r#"
+            [
+              Direction: Horizontal,
+              List: 70%,
+              Categories,
+            ]
+        "#;
+        let mut layout = Layout::from_str(
+            str_layout,
+            Arc::new(Mutex::new(ToDo::default())),
+            &Config::default(),
+        )?;
+        layout.update_chunk(Rect::new(0, 0, 100, 50));
+        assert_eq!(layout.find_widget(80, 10), Some(WidgetType::Categories));
+        layout.select_widget(WidgetType::Categories)?;
+        assert_eq!(layout.get_active_widget(), WidgetType::Categories);
+        assert_eq!(
+            <WidgetType as FromStr>::from_str("categories")?,
+            WidgetType::Categories
+        );
+        assert_eq!(WidgetType::Categories.to_string(), "Categories");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_from_string() -> ToDoRes<()> {
+        assert_eq!(Layout::sort_from_string("priority")?, TaskSort::Priority);
+        assert_eq!(
+            Layout::sort_from_string("alphanumericreverse")?,
+            TaskSort::AlphanumericReverse
+        );
+        assert_eq!(
+            Layout::sort_from_string("alphanumeric-reverse")?,
+            TaskSort::AlphanumericReverse
+        );
+        assert!(Layout::sort_from_string("unknown").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_widget_sort() -> ToDoRes<()> {
+        let str_layout = r#"
+            [
+              List: 50% sort:Priority,
+              Done: 50%   sort:AlphanumericReverse,
+            ]
+        "#;
+        let data = Arc::new(Mutex::new(ToDo::default()));
+        let pending_sort = data.lock().unwrap().get_sort(ToDoData::Pending);
+        let done_sort = data.lock().unwrap().get_sort(ToDoData::Done);
+        let layout = Layout::from_str(str_layout, data.clone(), &Config::default())?;
+        let sorts: Vec<_> = layout.containers[0]
+            .widgets()
+            .map(|widget| match widget {
+                Widget::List(list) => (list.data_type, list.get_sort()),
+                _ => panic!("Widget is not a list"),
+            })
+            .collect();
+        assert_eq!(
+            sorts,
+            vec![
+                (ToDoData::Pending, Some(TaskSort::Priority)),
+                (ToDoData::Done, Some(TaskSort::AlphanumericReverse)),
+            ]
+        );
+        // The global sorting is not changed by the widget options.
+        let data = data.lock().unwrap();
+        assert_eq!(data.get_sort(ToDoData::Pending), pending_sort);
+        assert_eq!(data.get_sort(ToDoData::Done), done_sort);
+
+        let invalid = Layout::from_str(
+            "[ Projects: 50% sort:Priority, ]",
+            Arc::new(Mutex::new(ToDo::default())),
+            &Config::default(),
+        );
+        assert!(invalid.is_err());
+        let invalid = Layout::from_str(
+            "[ Size: 50% sort:Priority, ]",
+            Arc::new(Mutex::new(ToDo::default())),
+            &Config::default(),
+        );
+        assert!(invalid.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() -> ToDoRes<()> {
+        Layout::check(&Config::default())?;
+
+        // The last item does not need the item separator.
+        let layout = Layout::from_str(
+            "[ List: 50%, Done: 50% ]",
+            Arc::new(Mutex::new(ToDo::default())),
+            &Config::default(),
+        )?;
+        assert_eq!(layout.containers[0].widgets().count(), 2);
+
+        let config = Config::load_from_buffer(r#"layout = "[ Lsit: 50% ]""#.as_bytes());
+        assert_eq!(
+            Layout::check(&config).err(),
+            Some(ToDoError::ParseWidgetType(String::from("lsit")))
+        );
+        let config = Config::load_from_buffer(r#"layout = "[ Direction: Vertical, ]""#.as_bytes());
+        assert_eq!(Layout::check(&config).err(), Some(ToDoError::ParseNoWidget));
+
+        Ok(())
+    }
 }
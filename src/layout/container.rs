@@ -7,6 +7,7 @@ use tui::{
 };
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 enum It {
     Cont(usize),
     Item(Widget),
@@ -86,6 +87,22 @@ impl Container {
         }
     }
 
+    /// Returns an iterator over the widgets directly in the container.
+    pub fn widgets(&self) -> impl Iterator<Item = &Widget> {
+        self.items.iter().filter_map(|item| match item {
+            It::Item(w) => Some(w),
+            It::Cont(_) => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the widgets directly in the container.
+    pub fn widgets_mut(&mut self) -> impl Iterator<Item = &mut Widget> {
+        self.items.iter_mut().filter_map(|item| match item {
+            It::Item(w) => Some(w),
+            It::Cont(_) => None,
+        })
+    }
+
     /// Returns a reference to the currently active item within the container.
     ///
     /// # Returns
@@ -179,7 +196,6 @@ impl Container {
     ///
     /// A result containing either an updated reference to the container with the selected widget
     /// type as the active item, or an error if the widget type is not found within the container.
-    #[allow(dead_code)]
     pub fn select_widget(layout: &mut Layout, widget_type: WidgetType) -> ToDoRes<()> {
         let mut index_item = 0;
         let (index_container, _) = layout
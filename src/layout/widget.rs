@@ -1,6 +1,7 @@
 mod state_categories;
 mod state_list;
 mod state_preview;
+mod state_progress;
 mod widget_base;
 mod widget_list;
 pub mod widget_trait;
@@ -17,11 +18,13 @@ use crossterm::event::KeyCode;
 use state_categories::StateCategories;
 use state_list::StateList;
 use state_preview::StatePreview;
+use state_progress::StateProgress;
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
 use tui::widgets::Block;
 use tui::{backend::Backend, Frame};
 use widget_base::WidgetBase;
+pub use widget_list::ListPosition;
 pub use widget_trait::State;
 use widget_type::WidgetType;
 
@@ -34,6 +37,7 @@ pub enum Widget {
     List(StateList),
     Category(StateCategories),
     Preview(StatePreview),
+    Progress(StateProgress),
 }
 
 impl Widget {
@@ -64,20 +68,33 @@ impl Widget {
             )),
             Project => Self::Category(StateCategories::new(
                 WidgetList::new(&widget_type, data, config),
-                ToDoCategory::Projects,
+                Some(ToDoCategory::Projects),
+                config,
             )),
             Context => Self::Category(StateCategories::new(
                 WidgetList::new(&widget_type, data, config),
-                ToDoCategory::Contexts,
+                Some(ToDoCategory::Contexts),
+                config,
             )),
             Hashtag => Self::Category(StateCategories::new(
                 WidgetList::new(&widget_type, data, config),
-                ToDoCategory::Hashtags,
+                Some(ToDoCategory::Hashtags),
+                config,
+            )),
+            Categories => Self::Category(StateCategories::new(
+                WidgetList::new(&widget_type, data, config),
+                None,
+                config,
             )),
             Preview => Self::Preview(StatePreview::new(
                 WidgetBase::new(&widget_type, data, config),
                 config,
             )?),
+            Progress => Self::Progress(StateProgress::new(WidgetBase::new(
+                &widget_type,
+                data,
+                config,
+            ))),
         })
     }
 
@@ -92,8 +109,11 @@ impl Widget {
         use WidgetType::*;
         match self {
             Widget::List(list) => list.data_type.into(),
-            Widget::Category(categories) => categories.category.into(),
+            Widget::Category(categories) => {
+                categories.category.map_or(Categories, WidgetType::from)
+            }
             Widget::Preview(_) => Preview,
+            Widget::Progress(_) => Progress,
         }
     }
 }
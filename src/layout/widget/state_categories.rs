@@ -1,20 +1,27 @@
-use super::{widget_base::WidgetBase, widget_list::WidgetList, widget_trait::State};
+use super::{
+    widget_base::WidgetBase,
+    widget_list::{ListPosition, WidgetList},
+    widget_trait::State,
+};
 use crate::{
-    todo::{FilterState, ToDoCategory},
+    config::Config,
+    todo::{CategoryList, FilterState, ToDo, ToDoCategory},
     ui::{HandleEvent, UIEvent},
 };
 use crossterm::event::KeyCode;
 use tui::{
     backend::Backend,
     style::{Color, Style},
-    widgets::List,
+    widgets::{List, ListItem},
     Frame,
 };
 
 /// Represents the state for a widget that displays categories.
 pub struct StateCategories {
     base: WidgetList,
-    pub category: ToDoCategory,
+    use_done: bool,
+    /// Displayed category, `None` displays all categories in one list.
+    pub category: Option<ToDoCategory>,
 }
 
 impl StateCategories {
@@ -23,13 +30,68 @@ impl StateCategories {
     /// # Parameters
     ///
     /// - `base`: The base properties shared among different widget types.
-    /// - `category`: The category of tasks to display.
+    /// - `category`: The category of tasks to display, `None` for all categories.
+    /// - `config`: The application configuration.
     ///
     /// # Returns
     ///
     /// A new `StateCategories` instance.
-    pub fn new(base: WidgetList, category: ToDoCategory) -> Self {
-        Self { base, category }
+    pub fn new(base: WidgetList, category: Option<ToDoCategory>, config: &Config) -> Self {
+        Self {
+            base,
+            use_done: config.get_use_done(),
+            category,
+        }
+    }
+
+    /// Gets types of categories displayed by this widget.
+    fn shown_categories(&self) -> &[ToDoCategory] {
+        self.category
+            .as_ref()
+            .map_or(ToDoCategory::get_all(), std::slice::from_ref)
+    }
+
+    /// Gets categories displayed by this widget. If all categories are
+    /// displayed, names are shown with the character starting them in a task.
+    ///
+    /// # Parameters
+    ///
+    /// - `todo`: The ToDo data to get categories from.
+    ///
+    /// # Returns
+    ///
+    /// The lists of categories with their type.
+    fn categories<'a>(&self, todo: &'a ToDo) -> Vec<(ToDoCategory, CategoryList<'a>)> {
+        self.shown_categories()
+            .iter()
+            .map(|category| {
+                let list = todo.get_categories_with_done(*category, self.use_done);
+                match self.category {
+                    Some(_) => (*category, list),
+                    None => (*category, list.prefix(category.prefix())),
+                }
+            })
+            .collect()
+    }
+
+    /// Gets the category at the specified index of the widget.
+    ///
+    /// # Parameters
+    ///
+    /// - `index`: The index of the item in the widget.
+    ///
+    /// # Returns
+    ///
+    /// The type and the name of the category or `None` if the index is out of bounds.
+    fn get_item(&self, mut index: usize) -> Option<(ToDoCategory, String)> {
+        let todo = self.base.data();
+        for (category, list) in self.categories(&todo) {
+            if index < list.len() {
+                return Some((category, list.get_name(index).clone()));
+            }
+            index -= list.len();
+        }
+        None
     }
 
     /// Returns the number of items in the category associated with this widget.
@@ -38,44 +100,46 @@ impl StateCategories {
     ///
     /// The number of items in the category.
     pub fn len(&self) -> usize {
-        self.base.data().get_categories(self.category).len()
+        self.categories(&self.base.data())
+            .iter()
+            .map(|(_, list)| list.len())
+            .sum()
+    }
+
+    /// Toggles the filter of the category at the active index.
+    fn toggle_filter(&mut self, filter_state: FilterState) {
+        if let Some((category, name)) = self.get_item(self.base.act()) {
+            self.base
+                .data()
+                .toggle_filter(category, &name, filter_state);
+        }
+        self.base.len = self.len();
     }
 }
 
 impl State for StateCategories {
     fn handle_event_state(&mut self, event: UIEvent) -> bool {
-        if self.base.handle_event(event) {
+        if self.base.handle_event(event.clone()) {
             return true;
         }
         match event {
-            // TODO improve doubled code
-            UIEvent::Select => {
-                let name;
+            UIEvent::Select => self.toggle_filter(FilterState::Select),
+            UIEvent::Remove => self.toggle_filter(FilterState::Remove),
+            UIEvent::InvertFilters => {
                 {
-                    let todo = self.base.data();
-                    name = todo
-                        .get_categories(self.category)
-                        .get_name(self.base.act())
-                        .clone();
+                    let mut todo = self.base.data();
+                    for category in self.shown_categories() {
+                        todo.invert_filters(*category);
+                    }
                 }
-                self.base
-                    .data()
-                    .toggle_filter(self.category, &name, FilterState::Select);
                 self.base.len = self.len();
             }
-            UIEvent::Remove => {
-                let name;
-                {
-                    let todo = self.base.data();
-                    name = todo
-                        .get_categories(self.category)
-                        .get_name(self.base.act())
-                        .clone();
-                }
-                self.base
-                    .data()
-                    .toggle_filter(self.category, &name, FilterState::Remove);
+            UIEvent::ToggleUseDone => {
+                self.use_done = !self.use_done;
                 self.base.len = self.len();
+                if self.base.act() >= self.base.len && self.base.len > 0 {
+                    self.base.last();
+                }
             }
             _ => return false,
         }
@@ -84,8 +148,12 @@ impl State for StateCategories {
 
     fn render<B: Backend>(&self, f: &mut Frame<B>) {
         let todo = self.base.data();
-        let data = todo.get_categories(self.category);
-        let list = List::new(data).block(self.get_block());
+        let items: Vec<ListItem> = self
+            .categories(&todo)
+            .into_iter()
+            .flat_map(|(_, list)| Vec::<ListItem>::from(list.search(todo.get_search())))
+            .collect();
+        let list = List::new(items).block(self.get_block());
         if !self.base.focus {
             f.render_widget(list, self.base.chunk)
         } else {
@@ -111,7 +179,115 @@ impl State for StateCategories {
         self.base.set_size(self.base.chunk.height - 2); // Two chars are borders.
     }
 
+    fn get_position(&self) -> Option<ListPosition> {
+        Some(self.base.position())
+    }
+
+    fn set_position(&mut self, position: ListPosition) {
+        self.base.len = self.len();
+        self.base.set_position(position);
+    }
+
+    fn click(&mut self, row: u16) -> bool {
+        self.base.len = self.len();
+        self.base.click(row)
+    }
+
     fn get_internal_event(&self, key: &KeyCode) -> UIEvent {
         self.base.get_event(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::widget::widget_type::WidgetType;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn toggle_use_done() {
+        let mut todo = ToDo::default();
+        todo.new_task("pending task +pending").unwrap();
+        todo.new_task("x done task +done").unwrap();
+        let todo = Arc::new(Mutex::new(todo));
+        let config = Config::default();
+        let mut widget = StateCategories::new(
+            WidgetList::new(&WidgetType::Project, todo, &config),
+            Some(ToDoCategory::Projects),
+            &config,
+        );
+        assert_eq!(widget.len(), 1);
+
+        assert!(widget.handle_event_state(UIEvent::ToggleUseDone));
+        assert_eq!(widget.len(), 2);
+        assert_eq!(widget.base.len, 2);
+        assert_eq!(
+            widget.get_item(0),
+            Some((ToDoCategory::Projects, String::from("done")))
+        );
+
+        assert!(widget.handle_event_state(UIEvent::ToggleUseDone));
+        assert_eq!(widget.len(), 1);
+    }
+
+    #[test]
+    fn combined_categories() {
+        let mut todo = ToDo::default();
+        todo.new_task("task +proj @proj #tag").unwrap();
+        todo.new_task("another task +other").unwrap();
+        let todo = Arc::new(Mutex::new(todo));
+        let config = Config::default();
+        let mut widget = StateCategories::new(
+            WidgetList::new(&WidgetType::Categories, todo.clone(), &config),
+            None,
+            &config,
+        );
+        assert_eq!(widget.len(), 4);
+        widget.focus_event();
+        let items: Vec<_> = (0..4).filter_map(|i| widget.get_item(i)).collect();
+        assert_eq!(
+            items,
+            [
+                (ToDoCategory::Projects, String::from("other")),
+                (ToDoCategory::Projects, String::from("proj")),
+                (ToDoCategory::Contexts, String::from("proj")),
+                (ToDoCategory::Hashtags, String::from("tag")),
+            ]
+        );
+        let names: Vec<String> = {
+            let todo = todo.lock().unwrap();
+            widget
+                .categories(&todo)
+                .into_iter()
+                .flat_map(|(_, list)| Vec::<ListItem>::from(list))
+                .map(|item| format!("{item:?}"))
+                .collect()
+        };
+        assert!(names[1].contains("\"+proj\""));
+        assert!(names[2].contains("\"@proj\""));
+
+        // Selecting `+proj` filters the project, not the context of the same name.
+        widget.base.set_size(10);
+        widget.base.down();
+        assert!(widget.handle_event_state(UIEvent::Select));
+        {
+            let todo = todo.lock().unwrap();
+            let state = todo.get_state();
+            assert_eq!(
+                state.get_category(ToDoCategory::Projects).get("proj"),
+                Some(&FilterState::Select)
+            );
+            assert!(state.get_category(ToDoCategory::Contexts).is_empty());
+        }
+
+        assert!(widget.handle_event_state(UIEvent::InvertFilters));
+        assert_eq!(
+            todo.lock()
+                .unwrap()
+                .get_state()
+                .get_category(ToDoCategory::Projects)
+                .get("proj"),
+            Some(&FilterState::Remove)
+        );
+    }
+}
@@ -1,16 +1,38 @@
-use super::{widget_base::WidgetBase, widget_list::WidgetList, widget_trait::State};
+use super::{
+    widget_base::WidgetBase,
+    widget_list::{ListPosition, WidgetList},
+    widget_trait::State,
+};
 use crate::{
     config::Config,
-    todo::{ToDo, ToDoData},
+    todo::{
+        task_list::{wrapped_height, TaskList, TaskSort, PRIORITY_GUTTER_WIDTH},
+        ToDo, ToDoData,
+    },
     ui::{HandleEvent, UIEvent},
 };
 use crossterm::event::KeyCode;
-use tui::{backend::Backend, style::Style, widgets::List, Frame};
+use tui::{
+    backend::Backend,
+    layout::Alignment,
+    style::Style,
+    text::Line,
+    widgets::{block::Title, List},
+    Frame,
+};
 
 /// Represents the state for a list widget that displays tasks.
 pub struct StateList {
     base: WidgetList,
     style: Style,
+    filter_chips: bool,
+    wrap_list: bool,
+    selection_symbol: String,
+    highlight_full_row: bool,
+    priority_gutter: bool,
+    highlight_filter_tokens: bool,
+    selected: Option<usize>,
+    sort: Option<TaskSort>,
     pub data_type: ToDoData,
 }
 
@@ -36,6 +58,14 @@ impl StateList {
                     ToDoData::Pending => config.get_pending_active_color(),
                 })
                 .get_style(),
+            filter_chips: config.get_filter_chips(),
+            wrap_list: config.get_wrap_list(),
+            selection_symbol: config.get_selection_symbol(),
+            highlight_full_row: config.get_highlight_full_row(),
+            priority_gutter: config.get_priority_gutter(),
+            highlight_filter_tokens: config.get_highlight_filter_tokens(),
+            selected: None,
+            sort: None,
             data_type,
         }
     }
@@ -49,6 +79,111 @@ impl StateList {
         self.base.data().len(self.data_type)
     }
 
+    /// Sets the sorting of this list, overriding the sorting of its ToDo data.
+    pub fn set_sort(&mut self, sort: TaskSort) {
+        self.sort = Some(sort);
+    }
+
+    /// Gets the sorting of this list, `None` if it uses the sorting of its ToDo data.
+    pub fn get_sort(&self) -> Option<TaskSort> {
+        self.sort
+    }
+
+    /// Gets the filtered tasks in the order they are displayed in this list.
+    fn filtered<'a>(&self, data: &'a ToDo) -> TaskList<'a> {
+        match self.sort {
+            Some(sort) => data.get_filtered_and_sorted_with(self.data_type, sort),
+            None => data.get_filtered_and_sorted(self.data_type),
+        }
+    }
+
+    /// Converts the index in this list to the index in the filtered
+    /// ToDo data sorted by its own sorting, which ToDo methods expect.
+    fn todo_index(&self, index: usize) -> usize {
+        if self.sort.is_none() {
+            return index;
+        }
+        let data = self.base.data();
+        self.filtered(&data)
+            .get_actual_index(index)
+            .and_then(|actual| {
+                data.get_filtered_and_sorted(self.data_type)
+                    .vec
+                    .iter()
+                    .position(|(index, _)| *index == actual)
+            })
+            .unwrap_or(index)
+    }
+
+    /// Converts the index in the filtered ToDo data sorted by its own sorting
+    /// to the index in this list.
+    fn list_index(&self, index: usize) -> usize {
+        if self.sort.is_none() {
+            return index;
+        }
+        let data = self.base.data();
+        data.get_actual_index(self.data_type, index)
+            .and_then(|actual| {
+                self.filtered(&data)
+                    .vec
+                    .iter()
+                    .position(|(index, _)| *index == actual)
+            })
+            .unwrap_or(index)
+    }
+
+    /// Gets the width tasks are wrapped to, `None` if the tasks are not wrapped.
+    fn wrap_width(&self) -> Option<usize> {
+        // Two chars are borders, the selection symbol and the priority gutter
+        // are displayed before the tasks.
+        let gutter = if self.priority_gutter {
+            PRIORITY_GUTTER_WIDTH
+        } else {
+            0
+        };
+        self.wrap_list.then(|| {
+            (self.base.chunk.width as usize)
+                .saturating_sub(2 + gutter + self.selection_symbol.chars().count())
+        })
+    }
+
+    /// Updates the number of rows taken by each task if the tasks are wrapped.
+    fn update_heights(&mut self) {
+        let Some(width) = self.wrap_width() else {
+            return;
+        };
+        let heights = self
+            .filtered(&self.base.data())
+            .vec
+            .iter()
+            .map(|(_, task)| wrapped_height(task, width))
+            .collect();
+        self.base.set_heights(heights);
+    }
+
+    /// Remembers the selected task by its index in ToDo data without filtering.
+    fn remember_selected(&mut self) {
+        self.selected = self
+            .filtered(&self.base.data())
+            .get_actual_index(self.base.index());
+    }
+
+    /// Selects the remembered task again if it is still in the filtered list,
+    /// so changes of filters do not move the selection to another task.
+    fn restore_selected(&mut self) {
+        let Some(selected) = self.selected.take() else {
+            return;
+        };
+        let index = self
+            .filtered(&self.base.data())
+            .vec
+            .iter()
+            .position(|(index, _)| *index == selected);
+        if let Some(index) = index {
+            self.base.go_to(index);
+        }
+    }
+
     /// Swaps tasks in the list at the selected and previous indices.
     ///
     /// # Parameters
@@ -57,6 +192,7 @@ impl StateList {
     /// - `second`: The index of the second task to swap.
     fn swap_tasks(&mut self, first: usize, second: usize) {
         log::trace!("Swap tasks with indexes: {}, {}", first, second);
+        let (first, second) = (self.todo_index(first), self.todo_index(second));
         self.base.data().swap_tasks(self.data_type, first, second);
     }
 
@@ -68,7 +204,8 @@ impl StateList {
     fn move_task(&mut self, r#move: fn(&mut ToDo, ToDoData, usize)) {
         let index = self.base.index();
         log::info!("Remove task with index {index}.");
-        r#move(&mut self.base.data(), self.data_type, index);
+        let todo_index = self.todo_index(index);
+        r#move(&mut self.base.data(), self.data_type, todo_index);
         let len = self.len();
         if len <= index && len > 0 {
             self.base.up();
@@ -79,7 +216,8 @@ impl StateList {
 
 impl State for StateList {
     fn handle_event_state(&mut self, event: UIEvent) -> bool {
-        if self.base.handle_event(event) {
+        self.update_heights();
+        if self.base.handle_event(event.clone()) {
             return true;
         }
         match event {
@@ -93,13 +231,69 @@ impl State for StateList {
                     self.swap_tasks(first, second)
                 }
             }
+            UIEvent::MoveToTop | UIEvent::MoveToBottom if self.base.len == 0 => {}
+            UIEvent::MoveToTop => {
+                let (index, first) = (self.todo_index(self.base.index()), self.todo_index(0));
+                self.base.data().move_to(self.data_type, index, first);
+                self.base.first();
+            }
+            UIEvent::MoveToBottom => {
+                let index = self.todo_index(self.base.index());
+                let last = self.todo_index(self.base.len - 1);
+                self.base.data().move_to(self.data_type, index, last);
+                self.base.last();
+            }
             UIEvent::RemoveItem => self.move_task(ToDo::remove_task),
             UIEvent::MoveItem => self.move_task(ToDo::move_task),
+            UIEvent::NextSearch | UIEvent::PrevSearch => {
+                let found = self.base.data().find_task(
+                    self.data_type,
+                    self.todo_index(self.base.index()),
+                    event == UIEvent::NextSearch,
+                );
+                if let Some(index) = found {
+                    self.base.go_to(self.list_index(index));
+                }
+            }
+            UIEvent::PriorityUp | UIEvent::PriorityDown => {
+                let index = self.todo_index(self.base.index());
+                self.base.data().change_priority(
+                    self.data_type,
+                    index,
+                    event == UIEvent::PriorityUp,
+                );
+            }
+            UIEvent::ToggleDone => {
+                let index = self.todo_index(self.base.index());
+                self.base.data().toggle_finished(self.data_type, index);
+            }
+            UIEvent::ToggleNow => {
+                let index = self.todo_index(self.base.index());
+                self.base.data().toggle_now(self.data_type, index);
+            }
+            UIEvent::TogglePin => {
+                let index = self.todo_index(self.base.index());
+                self.base.data().toggle_pin(self.data_type, index);
+            }
+            UIEvent::CycleSort | UIEvent::ToggleSortOrder => {
+                self.remember_selected();
+                let change = |sort: TaskSort| {
+                    if event == UIEvent::CycleSort {
+                        sort.next()
+                    } else {
+                        sort.reversed()
+                    }
+                };
+                match &mut self.sort {
+                    Some(sort) => *sort = change(*sort),
+                    None => self.base.data().change_sort(self.data_type, change),
+                }
+                self.restore_selected();
+            }
             UIEvent::Select => {
                 log::trace!("Set item on index {} active.", self.base.index());
-                self.base
-                    .data()
-                    .set_active(self.data_type, self.base.index());
+                let index = self.todo_index(self.base.index());
+                self.base.data().set_active(self.data_type, index);
             }
             _ => return false,
         }
@@ -108,14 +302,39 @@ impl State for StateList {
 
     fn render<B: Backend>(&self, f: &mut Frame<B>) {
         let data = self.base.data();
-        let filtered = data.get_filtered_and_sorted(self.data_type);
+        let filtered = self.filtered(&data);
         let (first, last) = self.base.range();
-        let filtered = filtered.slice(first, last);
-        let list = List::new(filtered).block(self.get_block());
+        let current = if self.base.focus {
+            Some(self.base.act())
+        } else {
+            None
+        };
+        let filtered = filtered
+            .slice(first, last)
+            .search(data.get_search(), current)
+            .wrap(self.wrap_width())
+            .priority_gutter(self.priority_gutter)
+            .selected_categories(if self.highlight_filter_tokens {
+                data.get_selected_categories()
+            } else {
+                Vec::new()
+            })
+            .active(current.filter(|_| !self.highlight_full_row), self.style);
+        let mut block = self.get_block();
+        if self.filter_chips {
+            let chips = data.get_filter_chips();
+            if !chips.is_empty() {
+                block = block.title(Title::from(Line::from(chips)).alignment(Alignment::Right));
+            }
+        }
+        let list = List::new(filtered).block(block);
         if !self.base.focus {
             f.render_widget(list, self.base.chunk)
         } else {
-            let list = list.highlight_style(self.style);
+            let mut list = list.highlight_symbol(&self.selection_symbol);
+            if self.highlight_full_row {
+                list = list.highlight_style(self.style);
+            }
             f.render_stateful_widget(list, self.base.chunk, &mut self.base.state());
         }
     }
@@ -131,17 +350,172 @@ impl State for StateList {
     fn focus_event(&mut self) -> bool {
         let len = self.len();
         self.base.len = len;
+        self.update_heights();
         if self.base.act() >= len && len > 0 {
             self.base.last();
         }
+        self.restore_selected();
         true
     }
 
+    fn unfocus_event(&mut self) {
+        self.remember_selected();
+    }
+
     fn update_chunk_event(&mut self) {
         self.base.set_size(self.base.chunk.height - 2); // Two chars are borders.
+        self.update_heights();
+    }
+
+    fn get_position(&self) -> Option<ListPosition> {
+        Some(self.base.position())
+    }
+
+    fn set_position(&mut self, position: ListPosition) {
+        self.base.len = self.len();
+        self.update_heights();
+        self.base.set_position(position);
+    }
+
+    fn click(&mut self, row: u16) -> bool {
+        self.base.len = self.len();
+        self.update_heights();
+        self.base.click(row)
     }
 
     fn get_internal_event(&self, key: &KeyCode) -> UIEvent {
         self.base.get_event(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        layout::{widget::widget_type::WidgetType, Render},
+        todo::{FilterState, ToDoCategory},
+    };
+    use std::sync::{Arc, Mutex};
+    use tui::layout::Rect;
+
+    #[test]
+    fn keep_selected_task() -> Result<(), todo_txt::Error> {
+        let data = Arc::new(Mutex::new(ToDo::default()));
+        let config = Config::default();
+        let mut list = StateList::new(
+            WidgetList::new(&WidgetType::List, data.clone(), &config),
+            ToDoData::Pending,
+            &config,
+        );
+        {
+            let mut todo = data.lock().unwrap();
+            for i in 0..8 {
+                let project = if i % 2 == 1 { " +odd" } else { "" };
+                todo.new_task(&format!("task {i}{project}"))?;
+            }
+        }
+        list.update_chunk(Rect::new(0, 0, 20, 12));
+        list.focus();
+        for _ in 0..5 {
+            list.handle_event(UIEvent::ListDown);
+        }
+        assert_eq!(list.base.index(), 5);
+
+        list.unfocus();
+        data.lock()
+            .unwrap()
+            .toggle_filter(ToDoCategory::Projects, "odd", FilterState::Select);
+        list.focus();
+        assert_eq!(list.base.index(), 2);
+        assert_eq!(
+            data.lock()
+                .unwrap()
+                .get_filtered_and_sorted(ToDoData::Pending)[list.base.index()]
+            .subject,
+            "task 5 +odd"
+        );
+
+        // Hidden task falls back to the nearest visible index.
+        list.handle_event(UIEvent::ListDown);
+        list.unfocus();
+        data.lock()
+            .unwrap()
+            .toggle_filter(ToDoCategory::Projects, "odd", FilterState::Remove);
+        list.focus();
+        assert_eq!(list.base.index(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn own_sort() -> Result<(), todo_txt::Error> {
+        let data = Arc::new(Mutex::new(ToDo::default()));
+        let global_sort = data.lock().unwrap().get_sort(ToDoData::Pending);
+        let config = Config::default();
+        let mut list = StateList::new(
+            WidgetList::new(&WidgetType::List, data.clone(), &config),
+            ToDoData::Pending,
+            &config,
+        );
+        list.set_sort(TaskSort::AlphanumericReverse);
+        for task in ["a", "c", "b"] {
+            data.lock().unwrap().new_task(task)?;
+        }
+        list.update_chunk(Rect::new(0, 0, 20, 12));
+        list.focus();
+
+        // The first item of the list is the last one of the ToDo data.
+        list.handle_event(UIEvent::Select);
+        assert_eq!(data.lock().unwrap().get_active().unwrap().subject, "c");
+        list.handle_event(UIEvent::ListDown);
+        list.handle_event(UIEvent::ToggleDone);
+        let finished: Vec<_> = data
+            .lock()
+            .unwrap()
+            .pending
+            .iter()
+            .map(|task| task.finished)
+            .collect();
+        assert_eq!(finished, vec![false, false, true]);
+
+        list.handle_event(UIEvent::CycleSort);
+        assert_eq!(list.get_sort(), Some(TaskSort::AlphanumericReverse.next()));
+        assert_eq!(
+            data.lock().unwrap().get_sort(ToDoData::Pending),
+            global_sort
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn selection_symbol() -> Result<(), Box<dyn std::error::Error>> {
+        use tui::{backend::TestBackend, Terminal};
+
+        let data = Arc::new(Mutex::new(ToDo::default()));
+        data.lock().unwrap().new_task("first")?;
+        data.lock().unwrap().new_task("second")?;
+        let config = Config::load_from_buffer(r#"selection_symbol = "->""#.as_bytes());
+        let mut list = StateList::new(
+            WidgetList::new(&WidgetType::List, data, &config),
+            ToDoData::Pending,
+            &config,
+        );
+        list.update_chunk(Rect::new(0, 0, 12, 4));
+        list.focus();
+        list.handle_event(UIEvent::ListDown);
+
+        let mut terminal = Terminal::new(TestBackend::new(12, 4))?;
+        terminal.draw(|f| State::render(&list, f))?;
+        let buffer = terminal.backend().buffer();
+        let row = |y| {
+            (1..11)
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect::<String>()
+        };
+        assert_eq!(row(1), "  first   ");
+        assert_eq!(row(2), "->second  ");
+
+        Ok(())
+    }
+}
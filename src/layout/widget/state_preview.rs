@@ -17,6 +17,7 @@ pub struct StatePreview {
     base: WidgetBase,
     parser: Parser,
     wrap_preview: bool,
+    scroll: u16,
 }
 
 impl StatePreview {
@@ -35,13 +36,32 @@ impl StatePreview {
             base,
             parser: Parser::new(&config.get_preview_format(), Styles::new(config))?,
             wrap_preview: config.get_wrap_preview(),
+            scroll: 0,
         })
     }
+
+    /// Gets the number of lines of the preview content.
+    fn lines_count(&self) -> u16 {
+        let count = self.parser.fill(&self.base.data()).len();
+        u16::try_from(count).unwrap_or(u16::MAX)
+    }
 }
 
 impl State for StatePreview {
-    fn handle_event_state(&mut self, _: UIEvent) -> bool {
-        false
+    fn handle_event_state(&mut self, event: UIEvent) -> bool {
+        match event {
+            UIEvent::ToggleWrap => self.wrap_preview = !self.wrap_preview,
+            UIEvent::ListDown => {
+                if self.scroll + 1 < self.lines_count() {
+                    self.scroll += 1;
+                }
+            }
+            UIEvent::ListUp => self.scroll = self.scroll.saturating_sub(1),
+            UIEvent::ListFirst => self.scroll = 0,
+            UIEvent::ListLast => self.scroll = self.lines_count().saturating_sub(1),
+            _ => return false,
+        }
+        true
     }
 
     fn render<B: Backend>(&self, f: &mut Frame<B>) {
@@ -58,7 +78,8 @@ impl State for StatePreview {
                 })
                 .collect::<Vec<_>>(),
         )
-        .block(self.get_block());
+        .block(self.get_block())
+        .scroll((self.scroll, 0));
         if self.wrap_preview {
             paragraph = paragraph.wrap(Wrap { trim: true })
         }
@@ -72,8 +93,68 @@ impl State for StatePreview {
     fn get_base_mut(&mut self) -> &mut WidgetBase {
         &mut self.base
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        layout::widget::widget_type::WidgetType,
+        todo::{ToDo, ToDoData},
+    };
+    use std::{
+        error::Error,
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn toggle_wrap() -> ToDoRes<()> {
+        let config = Config::default();
+        let data = Arc::new(Mutex::new(ToDo::default()));
+        let mut preview = StatePreview::new(
+            WidgetBase::new(&WidgetType::Preview, data, &config),
+            &config,
+        )?;
+        assert!(preview.wrap_preview);
+
+        assert!(preview.handle_event_state(UIEvent::ToggleWrap));
+        assert!(!preview.wrap_preview);
+        assert!(preview.handle_event_state(UIEvent::ToggleWrap));
+        assert!(preview.wrap_preview);
+
+        assert!(!preview.handle_event_state(UIEvent::Select));
+
+        Ok(())
+    }
+
+    #[test]
+    fn scroll() -> Result<(), Box<dyn Error>> {
+        let config = Config::default();
+        let mut todo = ToDo::default();
+        todo.new_task("task")?;
+        todo.set_active(ToDoData::Pending, 0);
+        let data = Arc::new(Mutex::new(todo));
+        let mut preview = StatePreview::new(
+            WidgetBase::new(&WidgetType::Preview, data, &config),
+            &config,
+        )?;
+        let lines = preview.lines_count();
+        assert!(lines > 1);
+        assert_eq!(preview.scroll, 0);
+
+        assert!(preview.handle_event_state(UIEvent::ListDown));
+        assert_eq!(preview.scroll, 1);
+        assert!(preview.handle_event_state(UIEvent::ListUp));
+        assert_eq!(preview.scroll, 0);
+        assert!(preview.handle_event_state(UIEvent::ListUp));
+        assert_eq!(preview.scroll, 0);
+        assert!(preview.handle_event_state(UIEvent::ListLast));
+        assert_eq!(preview.scroll, lines - 1);
+        assert!(preview.handle_event_state(UIEvent::ListDown));
+        assert_eq!(preview.scroll, lines - 1);
+        assert!(preview.handle_event_state(UIEvent::ListFirst));
+        assert_eq!(preview.scroll, 0);
 
-    fn focus_event(&mut self) -> bool {
-        false
+        Ok(())
     }
 }
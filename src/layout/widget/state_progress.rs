@@ -0,0 +1,110 @@
+use super::{widget_base::WidgetBase, widget_trait::State};
+use crate::{todo::ToDoData, ui::UIEvent};
+use tui::{backend::Backend, style::Style, widgets::Gauge, Frame};
+
+/// Represents the state for a progress widget that displays the ratio
+/// of done tasks to all tasks matching active filters.
+pub struct StateProgress {
+    base: WidgetBase,
+}
+
+impl StateProgress {
+    /// Creates a new `StateProgress` instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `base`: The base properties shared among different widget types.
+    ///
+    /// # Returns
+    ///
+    /// A new `StateProgress` instance.
+    pub fn new(base: WidgetBase) -> Self {
+        Self { base }
+    }
+
+    /// Gets the number of done tasks and the number of all tasks.
+    fn counts(&self) -> (usize, usize) {
+        let data = self.base.data();
+        let done = data.len(ToDoData::Done);
+        (done, done + data.len(ToDoData::Pending))
+    }
+
+    /// Gets the ratio of done tasks, zero if there are no tasks.
+    fn ratio(&self) -> f64 {
+        match self.counts() {
+            (_, 0) => 0.0,
+            (done, all) => done as f64 / all as f64,
+        }
+    }
+}
+
+impl State for StateProgress {
+    fn handle_event_state(&mut self, _: UIEvent) -> bool {
+        false
+    }
+
+    fn render<B: Backend>(&self, f: &mut Frame<B>) {
+        let (done, all) = self.counts();
+        let ratio = self.ratio();
+        let gauge = Gauge::default()
+            .block(self.get_block())
+            .gauge_style(Style::default().fg(self.base.active_color))
+            .ratio(ratio)
+            .label(format!("{done}/{all} ({:.0}%)", ratio * 100.0));
+        f.render_widget(gauge, self.base.chunk);
+    }
+
+    fn get_base(&self) -> &WidgetBase {
+        &self.base
+    }
+
+    fn get_base_mut(&mut self) -> &mut WidgetBase {
+        &mut self.base
+    }
+
+    fn focus_event(&mut self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        layout::widget::widget_type::WidgetType,
+        todo::{FilterState, ToDo, ToDoCategory},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn ratio() -> Result<(), todo_txt::Error> {
+        let data = Arc::new(Mutex::new(ToDo::default()));
+        let progress = StateProgress::new(WidgetBase::new(
+            &WidgetType::Progress,
+            data.clone(),
+            &Config::default(),
+        ));
+        assert_eq!(progress.counts(), (0, 0));
+        assert_eq!(progress.ratio(), 0.0);
+
+        {
+            let mut todo = data.lock().unwrap();
+            todo.new_task("task +project")?;
+            todo.new_task("another task")?;
+            todo.new_task("third task +project")?;
+            todo.new_task("x done task +project")?;
+        }
+        assert_eq!(progress.counts(), (1, 4));
+        assert_eq!(progress.ratio(), 0.25);
+
+        // Only filtered tasks are counted.
+        data.lock()
+            .unwrap()
+            .toggle_filter(ToDoCategory::Projects, "project", FilterState::Select);
+        assert_eq!(progress.counts(), (1, 3));
+        assert!((progress.ratio() - 1.0 / 3.0).abs() < f64::EPSILON);
+
+        Ok(())
+    }
+}
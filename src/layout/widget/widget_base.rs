@@ -31,7 +31,9 @@ impl WidgetBase {
             WidgetType::Project => config.get_category_keybind(),
             WidgetType::Context => config.get_category_keybind(),
             WidgetType::Hashtag => config.get_category_keybind(),
-            WidgetType::Preview => EventHandlerUI::default(),
+            WidgetType::Categories => config.get_category_keybind(),
+            WidgetType::Preview => config.get_preview_keybind(),
+            WidgetType::Progress => EventHandlerUI::default(),
         };
         Self {
             title: widget_type.to_string(),
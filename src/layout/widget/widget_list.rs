@@ -2,9 +2,19 @@ use super::{RCToDo, WidgetBase, WidgetType};
 use crate::config::Config;
 use crate::ui::{EventHandlerUI, HandleEvent, UIEvent};
 use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
 use tui::widgets::ListState;
 
+/// Scroll position and selection of a list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListPosition {
+    /// Index of the first displayed item.
+    pub first: usize,
+    /// Index of the selected item within the entire list.
+    pub selected: usize,
+}
+
 /// Represents a widget that displays a list of items.
 pub struct WidgetList {
     base: WidgetBase,
@@ -12,6 +22,7 @@ pub struct WidgetList {
     pub len: usize,
     first: usize,
     size: usize,
+    heights: Vec<usize>,
     event_handler: EventHandlerUI,
     list_shift: usize,
 }
@@ -34,6 +45,7 @@ impl WidgetList {
             len: 0,
             first: 0,
             size: 0,
+            heights: Vec::new(),
             event_handler: config.get_list_keybind(),
             list_shift: config.get_list_shift(),
         };
@@ -75,19 +87,78 @@ impl WidgetList {
     /// - `size`: The size of the list widget.
     pub fn set_size(&mut self, size: u16) {
         self.size = size as usize;
+        self.keep_visible();
+    }
+
+    /// Sets the number of rows taken by each item of the list. Items without
+    /// the height take one row, empty heights means all items are one row high.
+    ///
+    /// # Parameters
+    ///
+    /// - `heights`: Heights of the items within the entire list.
+    pub fn set_heights(&mut self, heights: Vec<usize>) {
+        self.heights = heights;
+        self.keep_visible();
+    }
+
+    /// Gets the number of rows taken by the item.
+    fn height(&self, index: usize) -> usize {
+        self.heights.get(index).copied().unwrap_or(1)
+    }
+
+    /// Gets the number of items that fit in the list from the first displayed item.
+    /// The first item is always displayed, even if it is higher than the list.
+    fn visible(&self) -> usize {
+        if self.heights.is_empty() {
+            return self.size;
+        }
+        let mut rows = 0;
+        let mut count = 0;
+        while rows + self.height(self.first + count) <= self.size || count == 0 {
+            rows += self.height(self.first + count);
+            count += 1;
+            if self.size == 0 {
+                break;
+            }
+        }
+        count
+    }
+
+    /// Gets the first displayed item so the item on the index is the last fully
+    /// displayed item.
+    fn first_ending_at(&self, index: usize) -> usize {
+        let mut first = index;
+        let mut rows = self.height(index);
+        while first > 0 && rows + self.height(first - 1) <= self.size {
+            first -= 1;
+            rows += self.height(first);
+        }
+        first
+    }
+
+    /// Scrolls the list so the selected item is visible.
+    fn keep_visible(&mut self) {
+        let visible = self.visible();
+        if visible > 0 && self.act() >= visible {
+            let index = self.index();
+            self.first = self.first_ending_at(index);
+            self.state.select(Some(index - self.first));
+        }
     }
 
     /// Moves the selection down the list.
     pub fn down(&mut self) {
         let act = self.act();
-        if self.len <= self.size {
+        let visible = self.visible();
+        if self.len <= visible && self.first == 0 {
             if self.len > act + 1 {
                 self.state.select(Some(act + 1));
             }
-        } else if self.size <= act + 1 + self.list_shift {
-            if self.first + self.size < self.len {
+        } else if visible <= act + 1 + self.list_shift {
+            if self.first + visible < self.len {
                 self.first += 1;
-            } else if self.size > act + 1 {
+                self.keep_visible();
+            } else if visible > act + 1 {
                 self.state.select(Some(act + 1));
             }
         } else {
@@ -160,13 +231,80 @@ impl WidgetList {
 
     /// Moves the selection to the last item in the list.
     pub fn last(&mut self) {
-        let shown_items = self.len - 1;
-        if self.size > shown_items {
-            self.first = 0;
-            self.state.select(Some(shown_items));
+        let last = self.len - 1;
+        self.first = self.first_ending_at(last);
+        self.state.select(Some(last - self.first));
+    }
+
+    /// Moves the selection to the item on the given index. The list is scrolled
+    /// only if the item is not visible.
+    ///
+    /// # Parameters
+    ///
+    /// - `index`: The index of the item within the entire list.
+    pub fn go_to(&mut self, index: usize) {
+        if index >= self.len {
+            return;
+        }
+        if index < self.first {
+            self.first = index;
+        } else if index >= self.first + self.visible() {
+            self.first = self.first_ending_at(index);
+        }
+        self.state.select(Some(index - self.first));
+    }
+
+    /// Gets the scroll position and the selected item of the list.
+    pub fn position(&self) -> ListPosition {
+        ListPosition {
+            first: self.first,
+            selected: self.index(),
+        }
+    }
+
+    /// Sets the scroll position and the selected item of the list.
+    /// The position is clamped to the current length of the list.
+    ///
+    /// # Parameters
+    ///
+    /// - `position`: The position to set.
+    pub fn set_position(&mut self, position: ListPosition) {
+        if self.len == 0 {
+            self.first();
+            return;
+        }
+        let index = position.selected.min(self.len - 1);
+        self.first = position.first.min(index);
+        self.state.select(Some(index - self.first));
+        // Selected item must be visible if the size is already known.
+        self.set_size(self.size as u16);
+    }
+
+    /// Selects the item displayed on the row of the terminal.
+    ///
+    /// # Parameters
+    ///
+    /// - `row`: The row of the terminal.
+    ///
+    /// # Returns
+    ///
+    /// `true` if there is an item on the row.
+    pub fn click(&mut self, row: u16) -> bool {
+        // The first row is the border.
+        let Some(offset) = row.checked_sub(self.chunk.y + 1) else {
+            return false;
+        };
+        let mut offset = offset as usize;
+        let mut index = self.first;
+        while offset >= self.height(index) {
+            offset -= self.height(index);
+            index += 1;
+        }
+        if index < self.first + self.visible() && index < self.len {
+            self.go_to(index);
+            true
         } else {
-            self.first = self.len - self.size;
-            self.state.select(Some(self.size - 1));
+            false
         }
     }
 
@@ -176,7 +314,7 @@ impl WidgetList {
     ///
     /// A tuple containing the indices of the (first, last) items displayed.
     pub fn range(&self) -> (usize, usize) {
-        (self.first, self.first + self.size)
+        (self.first, self.first + self.visible())
     }
 }
 
@@ -237,6 +375,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn go_to() {
+        let mut widget = testing_widget(50);
+
+        widget.go_to(5);
+        assert_eq!(widget.index(), 5);
+        assert_eq!(widget.first, 0);
+
+        widget.go_to(30);
+        assert_eq!(widget.index(), 30);
+        assert_eq!(widget.act(), 9);
+        assert_eq!(widget.first, 21);
+
+        widget.go_to(25);
+        assert_eq!(widget.index(), 25);
+        assert_eq!(widget.first, 21);
+
+        widget.go_to(3);
+        assert_eq!(widget.index(), 3);
+        assert_eq!(widget.act(), 0);
+
+        widget.go_to(50);
+        assert_eq!(widget.index(), 3);
+    }
+
+    #[test]
+    fn set_position() {
+        let mut widget = testing_widget(50);
+
+        widget.set_position(ListPosition {
+            first: 20,
+            selected: 25,
+        });
+        assert_eq!(widget.first, 20);
+        assert_eq!(widget.index(), 25);
+        assert_eq!(widget.position().selected, 25);
+
+        // Selected item out of the view.
+        widget.set_position(ListPosition {
+            first: 5,
+            selected: 30,
+        });
+        assert_eq!(widget.first, 21);
+        assert_eq!(widget.act(), 9);
+
+        // List got shorter.
+        widget.len = 10;
+        widget.set_position(ListPosition {
+            first: 20,
+            selected: 25,
+        });
+        assert_eq!(widget.first, 9);
+        assert_eq!(widget.index(), 9);
+
+        widget.len = 0;
+        widget.set_position(ListPosition {
+            first: 20,
+            selected: 25,
+        });
+        assert_eq!(widget.position(), ListPosition::default());
+    }
+
     #[test]
     fn movement_in_short_list() {
         let mut widget = testing_widget(5);
@@ -419,6 +619,70 @@ mod tests {
         assert_eq!(widget.first, 0);
     }
 
+    #[test]
+    fn click() {
+        let mut widget = testing_widget(20);
+        widget.chunk = tui::prelude::Rect::new(0, 2, 10, 7);
+        widget.set_size(5);
+        assert!(!widget.click(2));
+        assert!(widget.click(3));
+        assert_eq!(widget.index(), 0);
+        assert!(widget.click(7));
+        assert_eq!(widget.index(), 4);
+        assert!(!widget.click(8));
+        assert_eq!(widget.index(), 4);
+
+        widget.last();
+        assert!(widget.click(3));
+        assert_eq!(widget.index(), 15);
+
+        widget.len = 2;
+        widget.first();
+        assert!(!widget.click(5));
+        assert_eq!(widget.index(), 0);
+    }
+
+    #[test]
+    fn item_heights() {
+        let mut widget = testing_widget(10);
+        widget.list_shift = 0;
+        widget.chunk = tui::prelude::Rect::new(0, 2, 10, 7);
+        widget.set_size(5);
+        widget.set_heights(vec![1, 3, 1, 2, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(widget.range(), (0, 3));
+
+        // Rows of the higher item select the same item.
+        for (row, index) in [(3, 0), (4, 1), (6, 1), (7, 2)] {
+            assert!(widget.click(row));
+            assert_eq!(widget.index(), index);
+        }
+        assert!(!widget.click(8));
+
+        widget.first();
+        widget.down();
+        widget.down();
+        assert_eq!((widget.first, widget.act()), (0, 2));
+        widget.down();
+        assert_eq!((widget.first, widget.act()), (2, 1));
+        assert_eq!(widget.index(), 3);
+        assert_eq!(widget.range(), (2, 6));
+
+        widget.last();
+        assert_eq!((widget.first, widget.act()), (5, 4));
+
+        widget.go_to(1);
+        assert_eq!((widget.first, widget.act()), (1, 0));
+        assert_eq!(widget.range(), (1, 3));
+        widget.go_to(3);
+        assert_eq!((widget.first, widget.act()), (2, 1));
+
+        // Item higher than the list is still displayed.
+        widget.set_heights(vec![1, 1, 1, 8]);
+        widget.go_to(3);
+        assert_eq!((widget.first, widget.act()), (3, 0));
+        assert_eq!(widget.range(), (3, 4));
+    }
+
     #[test]
     fn range() {
         let widget = testing_widget(50);
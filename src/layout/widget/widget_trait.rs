@@ -1,5 +1,6 @@
 use super::super::Render;
 use super::widget_base::WidgetBase;
+use super::widget_list::ListPosition;
 use crate::ui::{HandleEvent, UIEvent};
 use crossterm::event::KeyCode;
 use tui::{
@@ -38,7 +39,7 @@ pub trait State {
     fn get_base_mut(&mut self) -> &mut WidgetBase;
 
     // Retrieves the block (border and title) for rendering the widget.
-    fn get_block(&self) -> Block {
+    fn get_block(&self) -> Block<'_> {
         let base = self.get_base();
         let mut block = Block::default()
             .borders(Borders::ALL)
@@ -61,6 +62,31 @@ pub trait State {
     /// Called when the widget's rendering area (chunk) is updated.
     fn update_chunk_event(&mut self) {}
 
+    /// Gets the scroll position of the widget, `None` if the widget is not a list.
+    fn get_position(&self) -> Option<ListPosition> {
+        None
+    }
+
+    /// Restores the scroll position of the widget. Widgets that are not lists ignore it.
+    ///
+    /// # Parameters
+    ///
+    /// - `position`: The position to restore.
+    fn set_position(&mut self, _: ListPosition) {}
+
+    /// Selects the item on the row of the terminal. Widgets that are not lists ignore it.
+    ///
+    /// # Parameters
+    ///
+    /// - `row`: The row of the terminal.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an item was selected.
+    fn click(&mut self, _: u16) -> bool {
+        false
+    }
+
     /// Retrieves an internal UI event based on a key code.
     /// This can be used for custom event handling within a state.
     ///
@@ -9,7 +9,7 @@ use std::str::FromStr;
 
 /// An enumeration representing different types of widgets used in the application.
 /// Widgets are I components with specific functionalities, such as task lists, project lists, and previews.
-#[derive(Default, PartialEq, Debug, Copy, Clone, Serialize, Deserialize, ValueEnum)]
+#[derive(Default, PartialEq, Eq, Debug, Copy, Clone, Serialize, Deserialize, ValueEnum)]
 pub enum WidgetType {
     #[default]
     List,
@@ -17,7 +17,9 @@ pub enum WidgetType {
     Project,
     Context,
     Hashtag,
+    Categories,
     Preview,
+    Progress,
 }
 
 impl fmt::Display for WidgetType {
@@ -29,7 +31,9 @@ impl fmt::Display for WidgetType {
             Project => write!(f, "Projects"),
             Context => write!(f, "Contexts"),
             Hashtag => write!(f, "Hashtags"),
+            Categories => write!(f, "Categories"),
             Preview => write!(f, "Preview"),
+            Progress => write!(f, "Progress"),
         }
     }
 }
@@ -65,7 +69,9 @@ impl FromStr for WidgetType {
             "projects" => Project,
             "contexts" => Context,
             "hashtags" => Hashtag,
+            "categories" => Categories,
             "preview" => Preview,
+            "progress" => Progress,
             _ => return Err(ToDoError::ParseWidgetType(s.to_string())),
         })
     }
@@ -1,11 +1,17 @@
 pub mod config;
 pub mod error;
 pub mod file_worker;
+pub mod headless;
 pub mod layout;
 pub mod todo;
 pub mod ui;
 
+#[cfg(test)]
+mod test_utils;
+
+pub use config::Config;
 pub use error::*;
+pub use todo::{ToDo, ToDoData};
 
 #[macro_use]
 extern crate enum_dispatch;
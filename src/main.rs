@@ -1,13 +1,32 @@
-use std::error::Error;
+use std::{error::Error, io, process};
 use todotxt_tui::{
     config::{Config, Logger},
+    headless::run_command,
+    layout::Layout,
     ui::UI,
 };
 
 fn main() {
     let config = Config::new();
+    if config.get_check_layout() {
+        let check = || -> Result<(), Box<dyn Error>> {
+            config.check_files()?;
+            Layout::check(&config)?;
+            Ok(())
+        };
+        match check() {
+            Ok(()) => println!("Configuration and layout are valid"),
+            Err(e) => {
+                eprintln!("Invalid configuration: {}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
     let run = || -> Result<(), Box<dyn Error>> {
-        if !config.export()? {
+        if let Some(command) = config.get_command() {
+            run_command(&config, command, &mut io::stdout())?;
+        } else if !config.export()? {
             Logger::new(&config).init()?;
             log::trace!("===== START LOGGING =====");
             let mut ui = UI::build(&config)?;
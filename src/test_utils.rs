@@ -0,0 +1,49 @@
+use std::{
+    fs,
+    io::Result as ioResult,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary directory for tests that is removed when dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates a new directory in the system temporary directory. The name is
+    /// made unique for every call so tests running in parallel do not collide.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "todo-tui-{name}-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.0.join(path)
+    }
+
+    /// Writes an executable script into the directory.
+    #[cfg(unix)]
+    pub fn script(&self, name: &str, content: &str) -> ioResult<PathBuf> {
+        use std::os::unix::fs::PermissionsExt;
+        let path = self.join(name);
+        fs::write(&path, content)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        Ok(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
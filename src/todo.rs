@@ -1,25 +1,79 @@
 pub mod autocomplete;
 pub mod category_list;
+pub mod counts;
+pub mod hooks;
+pub mod links;
 pub mod parser;
+pub mod relative_date;
+pub mod search;
 pub mod task_list;
 pub mod todo_state;
 
 pub use self::{
-    autocomplete::autocomplete, category_list::CategoryList, parser::Parser, task_list::TaskList,
-    todo_state::*,
+    autocomplete::autocomplete, category_list::CategoryList, parser::Parser, search::Search,
+    task_list::TaskList, todo_state::*,
 };
 
-use crate::config::{Config, Styles, ToDoConfig};
-use chrono::Utc;
-use std::{collections::btree_set::BTreeSet, str::FromStr};
-use todo_txt::Task;
+use self::{
+    hooks::HookEvent,
+    relative_date::expand_relative_dates,
+    task_list::{get_priority, TaskSort, NOW_TAG, PIN_TAG, PRIORITY_TAG},
+};
+use crate::{
+    config::{Config, FileWorkerConfig, Styles, ToDoConfig},
+    file_worker::FileWorker,
+};
+use chrono::{Local, NaiveDate, Utc};
+use std::{
+    collections::btree_set::BTreeSet,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use todo_txt::{Priority, Task};
+use tui::text::Span;
+
+/// Gets the current local date.
+pub fn get_actual_date() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Gets categories with the prefix from the subject of a task, sorted and
+/// without duplicates as the todo.txt parser stores them.
+fn get_categories(subject: &str, prefix: char) -> Vec<String> {
+    let mut categories: Vec<String> = subject
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix(prefix))
+        .map(|word| {
+            word.chars()
+                .take_while(|c| c.is_alphanumeric() || ['_', '-', '\\'].contains(c))
+                .collect::<String>()
+        })
+        .filter(|category| !category.is_empty())
+        .collect();
+    categories.sort();
+    categories.dedup();
+    categories
+}
 
 /// Struct to manage ToDo tasks and theirs state.
 pub struct ToDo {
     pub pending: Vec<Task>,
     pub done: Vec<Task>,
+    /// Lines of the todo file that are not tasks (comments, blank lines)
+    /// together with their position in the file.
+    pub raw_lines: Vec<(usize, String)>,
+    /// Same as `raw_lines` but for the archive file.
+    pub archive_raw_lines: Vec<(usize, String)>,
     version: usize,
+    /// Tasks as they were last saved or loaded.
+    saved_tasks: Vec<String>,
+    file_conflict: bool,
+    load_error: Option<String>,
+    save_error: Option<String>,
+    todo_file: Option<PathBuf>,
     state: ToDoState,
+    search: Option<Search>,
     config: ToDoConfig,
     styles: Styles,
 }
@@ -34,30 +88,203 @@ impl ToDo {
         Self {
             pending: Vec::new(),
             done: Vec::new(),
+            raw_lines: Vec::new(),
+            archive_raw_lines: Vec::new(),
             version: 0,
+            saved_tasks: Vec::new(),
+            file_conflict: false,
+            load_error: None,
+            save_error: None,
+            todo_file: None,
             state: ToDoState::default(),
+            search: None,
             config: ToDoConfig::new(config),
             styles: Styles::new(config),
         }
     }
 
+    /// Loads tasks in the todo.txt format from the reader, so the todo list
+    /// can be used without the user interface and the file watcher.
+    /// Lines that are not tasks are kept as raw lines, as when the todo
+    /// file is loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the tasks, e.g. a file or a string.
+    /// * `config` - Configuration of the todo list and the file format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use todotxt_tui::{Config, ToDo, ToDoData};
+    ///
+    /// let tasks = "(A) call mom +family\n# shopping\nbuy milk +shop\nx pay bills\n";
+    /// let todo = ToDo::from_reader(tasks.as_bytes(), &Config::default())?;
+    /// assert_eq!(todo.len(ToDoData::Pending), 2);
+    /// assert_eq!(todo.len(ToDoData::Done), 1);
+    /// assert_eq!(todo.raw_lines, [(1, String::from("# shopping"))]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn from_reader(reader: impl Read, config: &Config) -> io::Result<Self> {
+        let mut todo = Self::new(config);
+        todo.raw_lines = FileWorker::load_tasks(reader, &mut todo, &FileWorkerConfig::new(config))?;
+        todo.mark_saved();
+        Ok(todo)
+    }
+
+    /// Gets filtered and sorted tasks as they are shown in the lists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use todotxt_tui::{
+    ///     todo::{FilterState, ToDoCategory},
+    ///     Config, ToDo, ToDoData,
+    /// };
+    ///
+    /// let tasks = "buy milk +shop\n(A) call mom +family\n(B) buy flowers +shop\n";
+    /// let config = Config::load_from_buffer(r#"pending_sort = "Priority""#.as_bytes());
+    /// let mut todo = ToDo::from_reader(tasks.as_bytes(), &config)?;
+    /// todo.toggle_filter(ToDoCategory::Projects, "shop", FilterState::Select);
+    /// let subjects: Vec<_> = todo
+    ///     .filtered_tasks(ToDoData::Pending)
+    ///     .iter()
+    ///     .map(|task| task.subject.as_str())
+    ///     .collect();
+    /// assert_eq!(subjects, ["buy flowers +shop", "buy milk +shop"]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn filtered_tasks(&self, data: ToDoData) -> Vec<&Task> {
+        self.get_filtered_and_sorted(data)
+            .vec
+            .into_iter()
+            .map(|(_, task)| task)
+            .collect()
+    }
+
     /// Moves data from another ToDo instance into this one.
     ///
+    /// Only tasks and problems found while loading them are moved, the state
+    /// (filters, sorting) is kept. The active task stays active if it still
+    /// exists in the new data, even if it moved.
+    ///
     /// # Arguments
     ///
     /// * `other` - The other ToDo instance to move data from.
     pub fn move_data(&mut self, other: Self) {
+        let active = self.get_active().map(|task| task.to_string());
         self.pending = other.pending;
         self.done = other.done;
+        self.raw_lines = other.raw_lines;
+        self.archive_raw_lines = other.archive_raw_lines;
+        if other.load_error.is_some() {
+            self.load_error = other.load_error;
+        }
+        if let (Some((data, index)), Some(active)) = (self.state.active, active) {
+            self.set_active_task(data, index, &active);
+        }
         self.version += 1;
     }
 
+    /// Sets the active task by its text. The task on the index is preferred
+    /// if it is equal, otherwise the first equal task is used. The active task
+    /// is cleared if there is no such task.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of data the task is in.
+    /// * `index` - The expected index of the task in the data.
+    /// * `task` - The text of the task.
+    pub fn set_active_task(&mut self, data: ToDoData, index: usize, task: &str) {
+        let tasks = data.get_data(self);
+        self.state.active = if tasks.get(index).is_some_and(|t| t.to_string() == task) {
+            Some((data, index))
+        } else {
+            tasks
+                .iter()
+                .position(|t| t.to_string() == task)
+                .map(|index| (data, index))
+        };
+    }
+
     /// Gets the current version of the ToDo data.
     /// Version is increased on every data change.
     pub fn get_version(&self) -> usize {
         self.version
     }
 
+    /// Gets all tasks of the todo list as lines.
+    pub fn task_lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.pending.iter().chain(&self.done).map(Task::to_string)
+    }
+
+    /// Remembers the tasks as saved, so later changes can be detected.
+    pub fn mark_saved(&mut self) {
+        self.saved_tasks = self.task_lines().collect();
+    }
+
+    /// Checks if tasks changed since they were last saved or loaded.
+    pub fn has_unsaved_changes(&self) -> bool {
+        !self.task_lines().eq(self.saved_tasks.iter().cloned())
+    }
+
+    /// Marks that the todo file was changed by another program while there
+    /// were unsaved changes, so the user has to decide which changes to keep.
+    pub fn set_file_conflict(&mut self) {
+        self.file_conflict = true;
+        self.version += 1;
+    }
+
+    /// Remembers the error of the last failed load of the file.
+    pub fn set_load_error(&mut self, error: String) {
+        self.load_error = Some(error);
+        self.version += 1;
+    }
+
+    /// Adds a problem found while loading the file to the load error,
+    /// the file is loaded anyway.
+    pub fn add_load_error(&mut self, error: String) {
+        self.load_error = Some(match self.load_error.take() {
+            Some(errors) => format!("{errors}\n{error}"),
+            None => error,
+        });
+        self.version += 1;
+    }
+
+    /// Gets and clears the error set by `set_load_error` or `add_load_error`.
+    pub fn take_load_error(&mut self) -> Option<String> {
+        self.load_error.take()
+    }
+
+    /// Remembers the error of an action done after the file was saved,
+    /// e.g. a failed git commit.
+    pub fn set_save_error(&mut self, error: String) {
+        self.save_error = Some(error);
+        self.version += 1;
+    }
+
+    /// Gets and clears the error set by `set_save_error`.
+    pub fn take_save_error(&mut self) -> Option<String> {
+        self.save_error.take()
+    }
+
+    /// Remembers the todo file the tasks were switched to.
+    pub fn set_todo_file(&mut self, path: PathBuf) {
+        self.todo_file = Some(path);
+        self.version += 1;
+    }
+
+    /// Gets the todo file the tasks were switched to, `None` if the tasks
+    /// are from the configured todo file.
+    pub fn get_todo_file(&self) -> Option<&Path> {
+        self.todo_file.as_deref()
+    }
+
+    /// Gets and clears the mark set by `set_file_conflict`.
+    pub fn take_file_conflict(&mut self) -> bool {
+        std::mem::take(&mut self.file_conflict)
+    }
+
     /// Gets the actual index of an item in the ToDo data without filters.
     ///
     /// # Arguments
@@ -68,7 +295,7 @@ impl ToDo {
     /// # Returns
     ///
     /// The actual index of the item in ToDo data without filtering.
-    fn get_actual_index(&self, data: ToDoData, index: usize) -> Option<usize> {
+    pub fn get_actual_index(&self, data: ToDoData, index: usize) -> Option<usize> {
         self.get_filtered_and_sorted(data).get_actual_index(index)
     }
 
@@ -95,8 +322,27 @@ impl ToDo {
     /// # Returns
     ///
     /// A `CategoryList` containing the filtered categories and their selection status.
-    pub fn get_categories(&self, category: ToDoCategory) -> CategoryList {
-        let tasks = if self.config.use_done {
+    pub fn get_categories(&self, category: ToDoCategory) -> CategoryList<'_> {
+        self.get_categories_with_done(category, self.config.use_done)
+    }
+
+    /// Gets a filtered list of categories from the ToDo data, the configured
+    /// inclusion of done tasks is overridden.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - The type of category to retrieve.
+    /// * `use_done` - Whether to include categories of done tasks.
+    ///
+    /// # Returns
+    ///
+    /// A `CategoryList` containing the filtered categories and their selection status.
+    pub fn get_categories_with_done(
+        &self,
+        category: ToDoCategory,
+        use_done: bool,
+    ) -> CategoryList<'_> {
+        let tasks = if use_done {
             vec![&self.pending, &self.done]
         } else {
             vec![&self.pending]
@@ -114,10 +360,72 @@ impl ToDo {
                 .map(|item| (*item, selected.get(*item).cloned()))
                 .collect(),
             styles: &self.styles,
+            search: None,
+            prefix: None,
+        }
+    }
+
+    /// Gets categories selected by active filters with their prefixes, e.g. `+project`.
+    pub fn get_selected_categories(&self) -> Vec<String> {
+        ToDoCategory::get_all()
+            .iter()
+            .flat_map(|category| {
+                self.state
+                    .get_category(*category)
+                    .iter()
+                    .filter(|(_, state)| **state == FilterState::Select)
+                    .map(|(name, _)| format!("{}{}", category.prefix(), name))
+            })
+            .collect()
+    }
+
+    /// Gets active filters as chips styled by the category select or remove style.
+    ///
+    /// # Returns
+    ///
+    /// Spans of the chips separated by spaces, empty if there are no active filters.
+    pub fn get_filter_chips(&self) -> Vec<Span<'_>> {
+        let mut chips = Vec::new();
+        for category in ToDoCategory::get_all() {
+            for (name, state) in self.state.get_category(*category) {
+                let style = match state {
+                    FilterState::Select => self.styles.category_select_style,
+                    FilterState::Remove => self.styles.category_remove_style,
+                };
+                if !chips.is_empty() {
+                    chips.push(Span::raw(" "));
+                }
+                chips.push(Span::styled(
+                    format!("{}{}", category.prefix(), name),
+                    style.get_style(),
+                ));
+            }
+        }
+        if let Some(state) = self.state.due_filter {
+            let (name, style) = match state {
+                FilterState::Select => ("due", self.styles.category_select_style),
+                FilterState::Remove => ("no due", self.styles.category_remove_style),
+            };
+            if !chips.is_empty() {
+                chips.push(Span::raw(" "));
+            }
+            chips.push(Span::styled(name, style.get_style()));
+        }
+        if let Some(date_filter) = self.state.date_filter {
+            if !chips.is_empty() {
+                chips.push(Span::raw(" "));
+            }
+            chips.push(Span::styled(
+                date_filter.name(),
+                self.styles.category_select_style.get_style(),
+            ));
         }
+        chips
     }
 
     /// Moves a task from one section (Pending or Done) to the other.
+    /// If inline done is enabled, pending task is only marked as completed and stays
+    /// in place until the next save (see `relocate_done`).
     ///
     /// # Arguments
     ///
@@ -132,562 +440,2119 @@ impl ToDo {
                 return;
             }
         };
+        if self.config.inline_done {
+            if let ToDoData::Pending = data {
+                let task = &mut self.pending[index];
+                Self::toggle_task_finished(
+                    task,
+                    self.config.set_final_date,
+                    self.config.preserve_priority_on_done,
+                );
+                let task = task.to_string();
+                self.config.hooks.run(HookEvent::PostMove, &task);
+                return;
+            }
+        }
 
+        let set_final_date = self.config.set_final_date;
+        let preserve_priority = self.config.preserve_priority_on_done;
         let move_task_logic = |from: &mut Vec<Task>, to: &mut Vec<_>| {
             if from.len() <= index {
-                return;
+                return None;
             }
             let mut task = from.remove(index);
-            task.finished = !task.finished;
-            to.push(task)
+            Self::toggle_task_finished(&mut task, set_final_date, preserve_priority);
+            let moved = task.to_string();
+            to.push(task);
+            Some(moved)
         };
         use ToDoData::*;
-        match data {
+        let moved = match data {
             Pending => move_task_logic(&mut self.pending, &mut self.done),
             Done => move_task_logic(&mut self.done, &mut self.pending),
         };
+        if let Some(task) = moved {
+            self.config.hooks.run(HookEvent::PostMove, &task);
+        }
         self.fix_active(index)
     }
 
-    /// Toggles a filter for a specific category.
-    ///
-    /// # Arguments
-    ///
-    /// * `category` - The type of category to which the filter applies (Projects, Contexts, or Hashtags).
-    /// * `filter` - The filter string to toggle.
-    pub fn toggle_filter(
-        &mut self,
-        category: ToDoCategory,
-        filter: &str,
-        filter_state: FilterState,
-    ) {
-        self.state.set_filter(category, filter, filter_state)
-    }
-
-    fn get_filtered_tasks(&self, data: ToDoData) -> Vec<(usize, &Task)> {
-        data.get_data(self)
+    /// Gets indexes of pending tasks matching the active filters
+    /// that are not finished yet.
+    fn filtered_unfinished(&self) -> Vec<usize> {
+        self.get_filtered_tasks(ToDoData::Pending)
             .iter()
-            .enumerate()
-            .filter(|(_, task)| self.state.filter_out(task))
+            .filter(|(_, task)| !task.finished)
+            .map(|(index, _)| *index)
             .collect()
     }
 
-    /// TODO UPDATE DOC NOW IS SORTED
-    /// Gets a filtered list of tasks based on active filters.
-    ///
-    /// # Arguments
-    ///
-    /// * `data` - The type of ToDo data to filter.
+    /// Gets the number of tasks `complete_filtered` would complete.
+    pub fn filtered_unfinished_count(&self) -> usize {
+        self.filtered_unfinished().len()
+    }
+
+    /// Completes all pending tasks matching the active filters. Completed tasks
+    /// are moved to the done list in their original order, or kept in place
+    /// if done tasks are inline.
     ///
     /// # Returns
     ///
-    /// A `TaskList` containing the filtered tasks.
-    pub fn get_filtered_and_sorted(&self, data: ToDoData) -> TaskList {
-        let mut task_list = TaskList {
-            vec: self.get_filtered_tasks(data),
-            styles: &self.styles,
-        };
-        task_list.sort(data.get_sorting(&self.config));
-        task_list
+    /// The number of completed tasks.
+    pub fn complete_filtered(&mut self) -> usize {
+        let indexes = self.filtered_unfinished();
+        if indexes.is_empty() {
+            return 0;
+        }
+        let set_final_date = self.config.set_final_date;
+        let preserve_priority = self.config.preserve_priority_on_done;
+        let mut moved = Vec::new();
+        if self.config.inline_done {
+            for index in &indexes {
+                let task = &mut self.pending[*index];
+                Self::toggle_task_finished(task, set_final_date, preserve_priority);
+                moved.push(task.to_string());
+            }
+        } else {
+            // Tasks are removed from the end, so removals do not shift
+            // indexes of tasks that are not removed yet.
+            let mut tasks: Vec<Task> = indexes
+                .iter()
+                .rev()
+                .map(|index| self.pending.remove(*index))
+                .collect();
+            tasks.reverse();
+            for mut task in tasks {
+                Self::toggle_task_finished(&mut task, set_final_date, preserve_priority);
+                moved.push(task.to_string());
+                self.done.push(task);
+            }
+            if let Some((ToDoData::Pending, active)) = self.state.active {
+                self.state.active = if indexes.contains(&active) {
+                    None
+                } else {
+                    let shift = indexes.iter().filter(|index| **index < active).count();
+                    Some((ToDoData::Pending, active - shift))
+                };
+            }
+        }
+        for task in &moved {
+            self.config.hooks.run(HookEvent::PostMove, task);
+        }
+        self.version += 1;
+        indexes.len()
     }
 
-    /// Adds a new task to the ToDo list using a task string.
+    /// Toggles completion of a task and keeps it on its position in the same list.
+    /// Completed tasks in the pending list are moved to the done list and reopened
+    /// tasks in the done list are moved to the pending list on the next save
+    /// (see `relocate_done`).
     ///
     /// # Arguments
     ///
-    /// * `task` - The task string to parse and add to the ToDo list.
-    ///
-    /// # Returns
-    ///
-    /// A `Result` indicating success or an error if the task string cannot be parsed.
-    pub fn new_task(&mut self, task: &str) -> Result<(), todo_txt::Error> {
+    /// * `data` - The type of ToDo data where the task is located.
+    /// * `index` - The index of the task in the specified data.
+    pub fn toggle_finished(&mut self, data: ToDoData, index: usize) {
+        let index = match self.get_actual_index(data, index) {
+            Some(index) => index,
+            None => {
+                log::warn!("Cannot toggle finished Layout::get_actual_index is None");
+                return;
+            }
+        };
+        let set_final_date = self.config.set_final_date;
+        let preserve_priority = self.config.preserve_priority_on_done;
+        let task = &mut data.get_data_mut(self)[index];
+        Self::toggle_task_finished(task, set_final_date, preserve_priority);
+        let task = task.to_string();
+        self.config.hooks.run(HookEvent::PostMove, &task);
         self.version += 1;
-        let task = task.replace(
-            "due:today ",
-            &format!("due:{}", Utc::now().naive_utc().date()),
-        );
-        let task = task.replace("due: ", &format!("due:{}", Utc::now().naive_utc().date()));
-        let mut task = Task::from_str(&task)?;
-        if task.create_date.is_none() {
-            task.create_date = Some(Utc::now().naive_utc().date());
-        }
+    }
+
+    /// Toggles completion of the task. If `set_final_date` is enabled, finish date
+    /// of tasks with a creation date is set to today. Finish date is removed
+    /// when the task is not completed anymore. If `preserve_priority` is enabled,
+    /// the priority of the completed task is moved to the `pri:` tag and back
+    /// when the task is reopened.
+    fn toggle_task_finished(task: &mut Task, set_final_date: bool, preserve_priority: bool) {
         if task.finished {
-            self.done.push(task);
+            if preserve_priority && task.priority.is_lowest() {
+                let priority = get_priority(task);
+                if !priority.is_lowest() {
+                    task.tags.remove(PRIORITY_TAG);
+                    task.priority = priority;
+                }
+            }
+            task.uncomplete();
+            return;
+        }
+        if set_final_date {
+            task.complete();
         } else {
-            self.pending.push(task);
+            task.finished = true;
+        }
+        if preserve_priority && !task.priority.is_lowest() {
+            task.tags
+                .insert(String::from(PRIORITY_TAG), task.priority.to_string());
+            task.priority = Priority::default();
         }
-        Ok(())
     }
 
-    /// Removes a task from the ToDo list.
+    /// Marks a task as the one the user is currently working on. Only one task
+    /// can be marked at a time, so the mark is removed from all other tasks.
+    /// If the task is already marked, the mark is removed.
     ///
     /// # Arguments
     ///
-    /// * `data` - The type of ToDo data from which to remove the task.
-    /// * `index` - The index of the task to be removed in the specified data.
-    pub fn remove_task(&mut self, data: ToDoData, index: usize) {
-        let index = self.get_actual_index(data, index);
-        if let Some(index) = index {
-            data.get_data_mut(self).remove(index);
-            self.fix_active(index);
-        } else {
-            log::warn!("Layout::get_actual_index is None");
+    /// * `data` - The type of ToDo data where the task is located.
+    /// * `index` - The index of the task in the specified data.
+    pub fn toggle_now(&mut self, data: ToDoData, index: usize) {
+        let index = match self.get_actual_index(data, index) {
+            Some(index) => index,
+            None => {
+                log::warn!("Cannot toggle now Layout::get_actual_index is None");
+                return;
+            }
+        };
+        let is_now = data.get_data(self)[index].tags.contains_key(NOW_TAG);
+        self.pending
+            .iter_mut()
+            .chain(self.done.iter_mut())
+            .for_each(|task| {
+                task.tags.remove(NOW_TAG);
+            });
+        if !is_now {
+            let timestamp = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+            data.get_data_mut(self)[index]
+                .tags
+                .insert(String::from(NOW_TAG), timestamp);
         }
+        self.version += 1;
     }
 
-    /// Swaps the positions of two tasks in the ToDo list.
+    /// Toggles the `pin` tag of the task. Pinned tasks are shown before
+    /// other tasks regardless of the sorting.
     ///
     /// # Arguments
     ///
-    /// * `data` - The type of ToDo data (Pending or Done) in which to swap the tasks.
-    /// * `from` - The index of the first task to be swapped.
-    /// * `to` - The index of the second task to be swapped.
-    pub fn swap_tasks(&mut self, data: ToDoData, from: usize, to: usize) {
-        let from = self.get_actual_index(data, from);
-        let to = self.get_actual_index(data, to);
-        match (from, to) {
-            (Some(from), Some(to)) => {
-                data.get_data_mut(self).swap(from, to);
-                if let Some((_, act_index)) = &mut self.state.active {
-                    if *act_index == from {
-                        *act_index = to;
-                    } else if *act_index == to {
-                        *act_index = from;
-                    }
-                }
-            }
-            _ => {
-                log::warn!("Canot swap from or to is None")
+    /// * `data` - The type of ToDo data where the task is located.
+    /// * `index` - The index of the task in the specified data.
+    pub fn toggle_pin(&mut self, data: ToDoData, index: usize) {
+        let index = match self.get_actual_index(data, index) {
+            Some(index) => index,
+            None => {
+                log::warn!("Cannot toggle pin Layout::get_actual_index is None");
+                return;
             }
+        };
+        let tags = &mut data.get_data_mut(self)[index].tags;
+        if tags.remove(PIN_TAG).is_none() {
+            tags.insert(String::from(PIN_TAG), String::from("1"));
         }
+        self.version += 1;
     }
 
-    /// Sets a task as the active task for potential editing.
+    /// Raises or lowers the priority of a task. Raising a task without
+    /// priority sets the highest priority, lowering the lowest priority
+    /// removes the priority.
     ///
     /// # Arguments
     ///
     /// * `data` - The type of ToDo data where the task is located.
-    /// * `index` - The index of the task to be set as active in the specified data.
-    pub fn set_active(&mut self, data: ToDoData, index: usize) {
-        let index = self.get_actual_index(data, index);
-        if let Some(index) = index {
-            self.state.active = Some((data, index));
-        } else {
-            log::warn!("Layout::get_actual_index is None");
+    /// * `index` - The index of the task in the specified data.
+    /// * `raise` - Raise the priority if `true`, lower it otherwise.
+    pub fn change_priority(&mut self, data: ToDoData, index: usize, raise: bool) {
+        let index = match self.get_actual_index(data, index) {
+            Some(index) => index,
+            None => {
+                log::warn!("Cannot change priority Layout::get_actual_index is None");
+                return;
+            }
+        };
+        let task = &mut data.get_data_mut(self)[index];
+        let priority = u8::from(task.priority.clone());
+        task.priority = match (raise, task.priority.is_lowest()) {
+            (true, true) => 0,
+            (true, false) => priority.saturating_sub(1),
+            (false, _) => (priority + 1).min(u8::from(Priority::lowest())),
         }
+        .into();
+        self.version += 1;
     }
 
-    /// Gets the currently active task for potential editing.
-    ///
-    /// # Returns
-    ///
-    /// An `Option` containing a reference to the active `Task`, or `None` if no task is active.
-    pub fn get_active(&self) -> Option<&Task> {
-        match self.state.active {
-            Some((data, index)) => Some(&data.get_data(self)[index]),
-            None => None,
+    /// Moves tasks completed in place in the pending list to the done list
+    /// and tasks reopened in place in the done list to the pending list.
+    /// The active task is kept active.
+    pub fn relocate_done(&mut self) {
+        if !self.pending.iter().any(|task| task.finished)
+            && self.done.iter().all(|task| task.finished)
+        {
+            return;
         }
+        let kept_pending = self.pending.iter().filter(|task| !task.finished).count();
+        let kept_done = self.done.iter().filter(|task| task.finished).count();
+        let active = self.state.active.map(|(data, index)| {
+            let list = match data {
+                ToDoData::Pending => &self.pending,
+                ToDoData::Done => &self.done,
+            };
+            let finished_before = list[..index].iter().filter(|task| task.finished).count();
+            match (data, list[index].finished) {
+                (ToDoData::Pending, true) => (ToDoData::Done, kept_done + finished_before),
+                (ToDoData::Pending, false) => (ToDoData::Pending, index - finished_before),
+                (ToDoData::Done, true) => (ToDoData::Done, finished_before),
+                (ToDoData::Done, false) => {
+                    (ToDoData::Pending, kept_pending + index - finished_before)
+                }
+            }
+        });
+        let (done, reopened): (Vec<Task>, Vec<Task>) =
+            self.done.drain(..).partition(|task| task.finished);
+        let (completed, pending): (Vec<Task>, Vec<Task>) =
+            self.pending.drain(..).partition(|task| task.finished);
+        self.pending = pending;
+        self.pending.extend(reopened);
+        self.done = done;
+        self.done.extend(completed);
+        self.state.active = active;
     }
 
-    /// Updates the content of the active task.
+    /// Toggles a filter for a specific category.
     ///
     /// # Arguments
     ///
-    /// * `task` - The updated task string.
+    /// * `category` - The type of category to which the filter applies (Projects, Contexts, or Hashtags).
+    /// * `filter` - The filter string to toggle.
+    pub fn toggle_filter(
+        &mut self,
+        category: ToDoCategory,
+        filter: &str,
+        filter_state: FilterState,
+    ) {
+        self.state.set_filter(category, filter, filter_state)
+    }
+
+    /// Saves active filters as a named preset.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// A `Result` indicating success or an error if the updated task string cannot be parsed.
-    pub fn update_active(&mut self, task: &str) -> Result<(), todo_txt::Error> {
-        if let Some((data, index)) = self.state.active {
-            data.get_data_mut(self)[index] = Task::from_str(task)?;
+    /// * `name` - Name of the preset, empty name is ignored.
+    pub fn save_filter_preset(&mut self, name: &str) {
+        let name = name.trim();
+        if !name.is_empty() {
+            self.state.save_preset(name);
         }
-        Ok(())
     }
 
-    /// Fixes the active task index in case of task movements or removals.
-    ///
-    /// This method is used internally to ensure that the active task index remains valid
-    /// after tasks are moved or removed.
+    /// Replaces active filters by the filters saved in the preset.
     ///
     /// # Arguments
     ///
-    /// * `index` - The index of a task that was moved or removed.
-    fn fix_active(&mut self, index: usize) {
-        if let Some((_, act_index)) = &mut self.state.active {
-            log::trace!("act: {}, moved: {}", act_index, index);
-            match index.cmp(act_index) {
-                std::cmp::Ordering::Less => *act_index -= 1,
-                std::cmp::Ordering::Equal => self.state.active = None,
-                std::cmp::Ordering::Greater => {}
-            }
+    /// * `name` - Name of the preset.
+    ///
+    /// # Returns
+    ///
+    /// `false` if there is no preset with the name.
+    pub fn apply_filter_preset(&mut self, name: &str) -> bool {
+        if !self.state.apply_preset(name.trim()) {
+            return false;
         }
+        self.version += 1;
+        true
     }
 
-    /// Gets the number of tasks in the specified ToDo data (Pending or Done).
+    /// Gets names of the saved filter presets.
+    pub fn get_filter_presets(&self) -> impl Iterator<Item = &String> {
+        self.state.filter_presets.keys()
+    }
+
+    /// Removes all active filters, so all tasks are shown again.
+    pub fn clear_all_filters(&mut self) {
+        self.state.clear_filters();
+        self.version += 1;
+    }
+
+    /// Inverts all active filters of the category, see `ToDoState::invert_filters`.
     ///
     /// # Arguments
     ///
-    /// * `data` - The type of ToDo data for which to count the tasks.
+    /// * `category` - The type of category which filters are inverted.
+    pub fn invert_filters(&mut self, category: ToDoCategory) {
+        self.state.invert_filters(category)
+    }
+
+    /// Toggles filter by due date. It combines with the category filters.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// The number of tasks in the specified ToDo data.
-    pub fn len(&self, data: ToDoData) -> usize {
-        self.get_filtered_and_sorted(data).len()
+    /// * `filter_state` - Select shows only tasks with a due date, remove only tasks without it.
+    pub fn toggle_due_filter(&mut self, filter_state: FilterState) {
+        self.state.set_due_filter(filter_state)
     }
 
-    pub fn get_state(&self) -> &ToDoState {
-        &self.state
+    /// Toggles filter by the due date relative to today. It combines with
+    /// the category filters.
+    ///
+    /// # Arguments
+    ///
+    /// * `date_filter` - The filter to toggle.
+    pub fn toggle_date_filter(&mut self, date_filter: DateFilter) {
+        self.state.set_date_filter(date_filter)
     }
 
-    pub fn update_state(&mut self, state: ToDoState) {
-        self.state = state
+    fn get_filtered_tasks(&self, data: ToDoData) -> Vec<(usize, &Task)> {
+        data.get_data(self)
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| self.state.filter_out(task, self.config.week_start))
+            .collect()
     }
-}
 
-impl Default for ToDo {
-    fn default() -> Self {
-        ToDo::new(&Config::default())
+    /// TODO UPDATE DOC NOW IS SORTED
+    /// Gets a filtered list of tasks based on active filters.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data to filter.
+    ///
+    /// # Returns
+    ///
+    /// A `TaskList` containing the filtered tasks.
+    pub fn get_filtered_and_sorted(&self, data: ToDoData) -> TaskList<'_> {
+        self.get_filtered_and_sorted_with(data, self.get_sort(data))
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::naive::NaiveDate;
-    use std::error::Error;
-    use todo_txt::Priority;
 
-    fn example_todo() -> ToDo {
-        let mut todo = ToDo::default();
+    /// Gets the filtered tasks sorted by the given sorting instead of the one
+    /// used for the specified ToDo data.
+    pub fn get_filtered_and_sorted_with(&self, data: ToDoData, sort: TaskSort) -> TaskList<'_> {
+        let mut task_list = TaskList {
+            vec: self.get_filtered_tasks(data),
+            styles: &self.styles,
+        };
+        task_list.sort_with(
+            sort,
+            self.config.sort_tiebreak,
+            self.config.no_project_first,
+        );
+        task_list
+    }
 
-        let mut task = Task::from_str("measure space for 1 +project1 @context1 #hashtag1").unwrap();
-        task.finished = true;
-        task.priority = Priority::from(0);
-        task.create_date = Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap());
-        task.finish_date = Some(NaiveDate::from_ymd_opt(2023, 5, 21).unwrap());
-        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
-        todo.add_task(task);
+    /// Sets the searched term, empty term cancels the search.
+    pub fn set_search(&mut self, term: &str) {
+        self.search = self.new_search(term);
+        self.version += 1;
+    }
 
-        let mut task = Task::from_str("measure space for 2 +project2 @context2").unwrap();
-        task.create_date = Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap());
-        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
-        todo.add_task(task);
+    /// Creates a search for the term respecting the configuration.
+    fn new_search(&self, term: &str) -> Option<Search> {
+        Search::new(term).map(|search| search.word_boundary(self.config.search_word_boundary))
+    }
 
-        let mut task = Task::from_str("measure space for 3 +project3 @context3").unwrap();
-        task.priority = Priority::from(2);
-        task.create_date = Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap());
-        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
-        todo.add_task(task);
+    /// Counts tasks matching the term in the filtered list.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data to search in.
+    /// * `term` - The searched term.
+    /// * `index` - Index of the selected task in the filtered list.
+    ///
+    /// # Returns
+    ///
+    /// Number of matching tasks up to the selected task (including it) and
+    /// number of all matching tasks. `None` if the term is empty.
+    pub fn count_matches(
+        &self,
+        data: ToDoData,
+        term: &str,
+        index: usize,
+    ) -> Option<(usize, usize)> {
+        let search = self.new_search(term)?;
+        let tasks = self.get_filtered_and_sorted(data);
+        let matches: Vec<usize> = (0..tasks.len())
+            .filter(|&i| search.is_match(&tasks[i].subject))
+            .collect();
+        let current = matches.iter().take_while(|&&i| i <= index).count();
+        Some((current, matches.len()))
+    }
 
-        let mut task = Task::from_str("measure space for +project2 @context3 #hashtag1").unwrap();
-        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
-        todo.add_task(task);
+    /// Gets the actual search, `None` if nothing is searched.
+    pub fn get_search(&self) -> Option<&Search> {
+        self.search.as_ref()
+    }
 
-        let mut task = Task::from_str("measure space for 5 +project3 @context3 #hashtag2").unwrap();
-        task.finished = true;
-        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
-        todo.add_task(task);
+    /// Finds the next task matching the search. The search wraps
+    /// around the end (or the beginning) of the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data to search in.
+    /// * `from` - Index in the filtered list the search starts after.
+    /// * `forward` - Search direction.
+    ///
+    /// # Returns
+    ///
+    /// Index of the found task in the filtered list.
+    pub fn find_task(&self, data: ToDoData, from: usize, forward: bool) -> Option<usize> {
+        let search = self.search.as_ref()?;
+        let tasks = self.get_filtered_and_sorted(data);
+        let len = tasks.len();
+        (1..=len)
+            .map(|step| {
+                if forward {
+                    (from + step) % len
+                } else {
+                    (from + len - step % len) % len
+                }
+            })
+            .find(|&index| search.is_match(&tasks[index].subject))
+    }
 
-        let mut task = Task::from_str("measure space for 6 +project3 @context2 #hashtag2").unwrap();
-        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
-        todo.add_task(task);
+    /// Gets the sorting used for the specified ToDo data.
+    pub fn get_sort(&self, data: ToDoData) -> TaskSort {
+        data.get_sorting(&self.config, &self.state)
+    }
 
-        todo
+    /// Changes the sorting used for the specified ToDo data at runtime.
+    /// The sorting is a part of the state, so it is kept between sessions.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data to sort.
+    /// * `change` - Function getting the new sorting from the actual one.
+    pub fn change_sort(&mut self, data: ToDoData, change: impl FnOnce(TaskSort) -> TaskSort) {
+        let sort = change(self.get_sort(data));
+        match data {
+            ToDoData::Pending => self.state.pending_sort = Some(sort),
+            ToDoData::Done => self.state.done_sort = Some(sort),
+        }
     }
 
-    #[test]
-    fn test_add_task() {
-        let mut todo = example_todo();
-        todo.config.use_done = true;
+    /// Adds a new task to the ToDo list using a task string.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The task string to parse and add to the ToDo list.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or an error if the task string cannot be parsed.
+    pub fn new_task(&mut self, task: &str) -> Result<(), todo_txt::Error> {
+        self.version += 1;
+        let mut task = expand_relative_dates(task, get_actual_date());
+        if self.config.inherit_active_category {
+            self.inherit_categories(&mut task);
+        }
+        let task = self
+            .config
+            .hooks
+            .run(HookEvent::PreNew, &task)
+            .unwrap_or(task);
+        let mut task = Task::from_str(&task)?;
+        if task.create_date.is_none() {
+            task.create_date = Some(Utc::now().naive_utc().date());
+        }
+        let new = task.to_string();
+        if task.finished {
+            self.done.push(task);
+        } else {
+            self.pending.push(task);
+        }
+        self.config.hooks.run(HookEvent::PostNew, &new);
+        Ok(())
+    }
 
-        assert_eq!(todo.done.len(), 2);
-        assert_eq!(todo.pending.len(), 4);
+    /// Appends categories that are the only selected filter in their category
+    /// to the task string, if the task does not contain them yet.
+    fn inherit_categories(&self, task: &mut String) {
+        for category in ToDoCategory::get_all() {
+            let mut selected = self
+                .state
+                .get_category(*category)
+                .iter()
+                .filter(|(_, state)| **state == FilterState::Select);
+            if let (Some((name, _)), None) = (selected.next(), selected.next()) {
+                let tag = format!("{}{}", category.prefix(), name);
+                if !task.split_whitespace().any(|word| word == tag) {
+                    task.push(' ');
+                    task.push_str(&tag);
+                }
+            }
+        }
+    }
 
-        assert_eq!(todo.done[0].priority, 0);
-        assert!(todo.done[0].create_date.is_some());
-        assert!(todo.done[0].finish_date.is_some());
-        assert!(todo.done[0].finished);
-        assert_eq!(todo.done[0].threshold_date, None);
-        assert!(todo.done[0].due_date.is_some());
-        assert_eq!(todo.done[0].contexts().len(), 1);
-        assert_eq!(todo.done[0].projects().len(), 1);
-        assert_eq!(todo.done[0].hashtags.len(), 1);
+    /// Removes a task from the ToDo list.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data from which to remove the task.
+    /// * `index` - The index of the task to be removed in the specified data.
+    pub fn remove_task(&mut self, data: ToDoData, index: usize) {
+        let index = self.get_actual_index(data, index);
+        if let Some(index) = index {
+            self.version += 1;
+            let task = data.get_data_mut(self).remove(index);
+            self.fix_active(index);
+            self.config
+                .hooks
+                .run(HookEvent::PostRemove, &task.to_string());
+        } else {
+            log::warn!("Layout::get_actual_index is None");
+        }
+    }
 
-        println!("{:#?}", todo.pending[0]);
+    /// Swaps the positions of two tasks in the ToDo list.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data (Pending or Done) in which to swap the tasks.
+    /// * `from` - The index of the first task to be swapped.
+    /// * `to` - The index of the second task to be swapped.
+    pub fn swap_tasks(&mut self, data: ToDoData, from: usize, to: usize) {
+        let from = self.get_actual_index(data, from);
+        let to = self.get_actual_index(data, to);
+        match (from, to) {
+            (Some(from), Some(to)) => {
+                self.version += 1;
+                data.get_data_mut(self).swap(from, to);
+                if let Some((_, act_index)) = &mut self.state.active {
+                    if *act_index == from {
+                        *act_index = to;
+                    } else if *act_index == to {
+                        *act_index = from;
+                    }
+                }
+            }
+            _ => {
+                log::warn!("Canot swap from or to is None")
+            }
+        }
+    }
 
-        assert!(todo.pending[0].priority.is_lowest());
-        assert!(todo.pending[0].create_date.is_some());
-        assert!(todo.pending[0].finish_date.is_none());
-        assert!(!todo.pending[0].finished);
-        assert_eq!(todo.pending[0].threshold_date, None);
-        assert!(todo.pending[0].due_date.is_some());
-        assert_eq!(todo.pending[0].contexts().len(), 1);
-        assert_eq!(todo.pending[0].projects().len(), 1);
-        assert_eq!(todo.pending[0].hashtags.len(), 0);
+    /// Moves a task to another position in the ToDo list, tasks between
+    /// the positions are shifted by one.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data (Pending or Done) in which to move the task.
+    /// * `from` - The index of the moved task.
+    /// * `to` - The index the task is moved to.
+    pub fn move_to(&mut self, data: ToDoData, from: usize, to: usize) {
+        let from = self.get_actual_index(data, from);
+        let to = self.get_actual_index(data, to);
+        let (Some(from), Some(to)) = (from, to) else {
+            log::warn!("Cannot move task from or to is None");
+            return;
+        };
+        self.version += 1;
+        let tasks = data.get_data_mut(self);
+        let task = tasks.remove(from);
+        tasks.insert(to, task);
+        if let Some((act_data, act_index)) = &mut self.state.active {
+            if *act_data == data {
+                if *act_index == from {
+                    *act_index = to;
+                } else if from < *act_index && *act_index <= to {
+                    *act_index -= 1;
+                } else if to <= *act_index && *act_index < from {
+                    *act_index += 1;
+                }
+            }
+        }
+    }
 
-        assert_eq!(todo.pending[1].priority, 2);
-        assert!(todo.pending[1].create_date.is_some());
-        assert!(todo.pending[1].finish_date.is_none());
-        assert!(!todo.pending[1].finished);
-        assert_eq!(todo.pending[1].threshold_date, None);
-        assert!(todo.pending[1].due_date.is_some());
-        assert_eq!(todo.pending[1].contexts().len(), 1);
-        assert_eq!(todo.pending[1].projects().len(), 1);
-        assert_eq!(todo.pending[1].hashtags.len(), 0);
+    /// Sets a task as the active task for potential editing.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data where the task is located.
+    /// * `index` - The index of the task to be set as active in the specified data.
+    pub fn set_active(&mut self, data: ToDoData, index: usize) {
+        let index = self.get_actual_index(data, index);
+        if let Some(index) = index {
+            self.state.active = Some((data, index));
+        } else {
+            log::warn!("Layout::get_actual_index is None");
+        }
     }
 
-    fn create_vec(items: &[String]) -> Vec<(&String, Option<FilterState>)> {
-        let mut vec: Vec<(&String, Option<FilterState>)> = Vec::new();
-        items.iter().for_each(|item| {
-            vec.push((item, None));
-        });
-        vec
+    /// Gets the currently active task for potential editing.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing a reference to the active `Task`, or `None` if no task is active.
+    pub fn get_active(&self) -> Option<&Task> {
+        match self.state.active {
+            Some((data, index)) => Some(&data.get_data(self)[index]),
+            None => None,
+        }
+    }
+
+    /// Gets URLs of issues referenced by hashtags of the task.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The task to get issue URLs for.
+    ///
+    /// # Returns
+    ///
+    /// URLs of referenced issues, empty if the issue URL template is not set.
+    pub fn get_issue_urls(&self, task: &Task) -> Vec<String> {
+        match &self.config.issue_url_template {
+            Some(template) => task
+                .hashtags
+                .iter()
+                .filter_map(|hashtag| links::issue_url(template, hashtag))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Gets the link of the active task. The link of the task is preferred,
+    /// the URL of the first referenced issue is used otherwise.
+    ///
+    /// # Returns
+    ///
+    /// The URL to open, `None` if there is no active task or it has no link.
+    pub fn get_active_link(&self) -> Option<String> {
+        let task = self.get_active()?;
+        links::task_link(task).or_else(|| self.get_issue_urls(task).into_iter().next())
+    }
+
+    /// Updates the content of the active task.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The updated task string.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or an error if the updated task string cannot be parsed.
+    pub fn update_active(&mut self, task: &str) -> Result<(), todo_txt::Error> {
+        if let Some((data, index)) = self.state.active {
+            self.version += 1;
+            let task = expand_relative_dates(task, get_actual_date());
+            let task = Task::from_str(
+                &self
+                    .config
+                    .hooks
+                    .run(HookEvent::PreUpdate, &task)
+                    .unwrap_or(task),
+            )?;
+            let updated = task.to_string();
+            data.get_data_mut(self)[index] = task;
+            self.config.hooks.run(HookEvent::PostUpdate, &updated);
+        }
+        Ok(())
+    }
+
+    /// Replaces the subject of the active task. Priority, dates and tags are kept,
+    /// categories are taken from the new subject. Only the subject is changed,
+    /// the rest of the task is not parsed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The new subject, empty subject is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or an error if the task rewritten by a hook cannot be parsed.
+    pub fn update_active_subject(&mut self, subject: &str) -> Result<(), todo_txt::Error> {
+        let subject = subject.trim();
+        if subject.is_empty() {
+            return Ok(());
+        }
+        let Some((data, index)) = self.state.active else {
+            return Ok(());
+        };
+        self.version += 1;
+        let task = &mut data.get_data_mut(self)[index];
+        task.subject = subject.to_string();
+        #[allow(deprecated)]
+        {
+            task.projects = get_categories(subject, '+');
+            task.contexts = get_categories(subject, '@');
+        }
+        task.hashtags = get_categories(subject, '#');
+        let task = task.to_string();
+        if let Some(task) = self.config.hooks.run(HookEvent::PreUpdate, &task) {
+            data.get_data_mut(self)[index] = Task::from_str(&task)?;
+        }
+        let task = data.get_data(self)[index].to_string();
+        self.config.hooks.run(HookEvent::PostUpdate, &task);
+        Ok(())
+    }
+
+    /// Appends text to the end of the active task, e.g. a project or a due date.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to append.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or an error if the updated task cannot be parsed.
+    pub fn append_to_active(&mut self, text: &str) -> Result<(), todo_txt::Error> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(());
+        }
+        match self.get_active() {
+            Some(task) => {
+                let task = format!("{task} {text}");
+                self.update_active(&task)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Fixes the active task index in case of task movements or removals.
+    ///
+    /// This method is used internally to ensure that the active task index remains valid
+    /// after tasks are moved or removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of a task that was moved or removed.
+    fn fix_active(&mut self, index: usize) {
+        if let Some((_, act_index)) = &mut self.state.active {
+            log::trace!("act: {}, moved: {}", act_index, index);
+            match index.cmp(act_index) {
+                std::cmp::Ordering::Less => *act_index -= 1,
+                std::cmp::Ordering::Equal => self.state.active = None,
+                std::cmp::Ordering::Greater => {}
+            }
+        }
+    }
+
+    /// Gets the number of tasks in the specified ToDo data (Pending or Done).
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The type of ToDo data for which to count the tasks.
+    ///
+    /// # Returns
+    ///
+    /// The number of tasks in the specified ToDo data.
+    pub fn len(&self, data: ToDoData) -> usize {
+        self.get_filtered_and_sorted(data).len()
+    }
+
+    pub fn get_state(&self) -> &ToDoState {
+        &self.state
+    }
+
+    pub fn update_state(&mut self, state: ToDoState) {
+        self.state = state
+    }
+}
+
+impl Default for ToDo {
+    fn default() -> Self {
+        ToDo::new(&Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{file_worker::FileWorkerCommands, test_utils::TempDir};
+    use chrono::naive::NaiveDate;
+    use std::{
+        error::Error,
+        fs::File,
+        sync::{Arc, Mutex},
+    };
+    use todo_txt::Priority;
+
+    fn example_todo() -> ToDo {
+        let mut todo = ToDo::default();
+
+        let mut task = Task::from_str("measure space for 1 +project1 @context1 #hashtag1").unwrap();
+        task.finished = true;
+        task.priority = Priority::from(0);
+        task.create_date = Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap());
+        task.finish_date = Some(NaiveDate::from_ymd_opt(2023, 5, 21).unwrap());
+        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+        todo.add_task(task);
+
+        let mut task = Task::from_str("measure space for 2 +project2 @context2").unwrap();
+        task.create_date = Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap());
+        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+        todo.add_task(task);
+
+        let mut task = Task::from_str("measure space for 3 +project3 @context3").unwrap();
+        task.priority = Priority::from(2);
+        task.create_date = Some(NaiveDate::from_ymd_opt(2023, 4, 30).unwrap());
+        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+        todo.add_task(task);
+
+        let mut task = Task::from_str("measure space for +project2 @context3 #hashtag1").unwrap();
+        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+        todo.add_task(task);
+
+        let mut task = Task::from_str("measure space for 5 +project3 @context3 #hashtag2").unwrap();
+        task.finished = true;
+        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+        todo.add_task(task);
+
+        let mut task = Task::from_str("measure space for 6 +project3 @context2 #hashtag2").unwrap();
+        task.due_date = Some(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+        todo.add_task(task);
+
+        todo
+    }
+
+    #[test]
+    fn find_task() {
+        let mut todo = example_todo();
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, true), None);
+
+        todo.set_search("SPACE FOR");
+        assert_eq!(todo.get_search().unwrap().term(), "SPACE FOR");
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, true), Some(1));
+        assert_eq!(todo.find_task(ToDoData::Pending, 3, true), Some(0));
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, false), Some(3));
+
+        todo.set_search("for 3 @context3 -#hashtag");
+        assert_eq!(todo.find_task(ToDoData::Pending, 2, true), Some(1));
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, false), Some(1));
+        assert_eq!(todo.find_task(ToDoData::Pending, 1, true), Some(1));
+        assert_eq!(todo.find_task(ToDoData::Done, 0, true), None);
+
+        todo.set_search("space -project2");
+        assert_eq!(todo.find_task(ToDoData::Pending, 1, true), Some(3));
+        assert_eq!(todo.find_task(ToDoData::Pending, 3, true), Some(1));
+
+        todo.set_search("!space");
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, true), None);
+
+        todo.set_search("\\bproject");
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, true), None);
+        todo.config.search_word_boundary = true;
+        todo.set_search("project");
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, true), None);
+        todo.set_search("for 3");
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, true), Some(1));
+        todo.config.search_word_boundary = false;
+        todo.set_search("project");
+        assert_eq!(todo.find_task(ToDoData::Pending, 0, true), Some(1));
+
+        todo.set_search("");
+        assert_eq!(todo.get_search(), None);
+    }
+
+    #[test]
+    fn count_matches() {
+        let todo = example_todo();
+        assert_eq!(todo.count_matches(ToDoData::Pending, "", 0), None);
+        assert_eq!(
+            todo.count_matches(ToDoData::Pending, "space", 0),
+            Some((1, 4))
+        );
+        assert_eq!(
+            todo.count_matches(ToDoData::Pending, "space", 2),
+            Some((3, 4))
+        );
+        assert_eq!(
+            todo.count_matches(ToDoData::Pending, "@context3", 0),
+            Some((0, 2))
+        );
+        assert_eq!(
+            todo.count_matches(ToDoData::Pending, "@context3", 3),
+            Some((2, 2))
+        );
+        assert_eq!(
+            todo.count_matches(ToDoData::Pending, "space -#hashtag", 0),
+            Some((1, 2))
+        );
+        assert_eq!(todo.count_matches(ToDoData::Done, "for 5", 0), Some((0, 1)));
+        assert_eq!(
+            todo.count_matches(ToDoData::Pending, "nothing", 0),
+            Some((0, 0))
+        );
+    }
+
+    #[test]
+    fn move_data_keeps_state() {
+        let mut todo = example_todo();
+        todo.toggle_filter(ToDoCategory::Projects, "project3", FilterState::Select);
+        todo.set_active(ToDoData::Pending, 1);
+        let active = todo.get_active().unwrap().subject.clone();
+        assert_eq!(active, "measure space for 6 +project3 @context2 #hashtag2");
+
+        // Reloaded data with a new task at the beginning.
+        let mut reloaded = example_todo();
+        reloaded
+            .pending
+            .insert(0, Task::from_str("new task +project3 @context3").unwrap());
+        todo.move_data(reloaded);
+
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 3);
+        assert!(filtered
+            .vec
+            .iter()
+            .all(|(_, task)| task.projects().contains(&String::from("project3"))));
+        assert_eq!(todo.get_active().unwrap().subject, active);
+
+        let mut reloaded = example_todo();
+        reloaded.pending.pop();
+        todo.move_data(reloaded);
+        assert!(todo.get_active().is_none());
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Pending).len(), 1);
+    }
+
+    #[test]
+    fn test_add_task() {
+        let mut todo = example_todo();
+        todo.config.use_done = true;
+
+        assert_eq!(todo.done.len(), 2);
+        assert_eq!(todo.pending.len(), 4);
+
+        assert_eq!(todo.done[0].priority, 0);
+        assert!(todo.done[0].create_date.is_some());
+        assert!(todo.done[0].finish_date.is_some());
+        assert!(todo.done[0].finished);
+        assert_eq!(todo.done[0].threshold_date, None);
+        assert!(todo.done[0].due_date.is_some());
+        assert_eq!(todo.done[0].contexts().len(), 1);
+        assert_eq!(todo.done[0].projects().len(), 1);
+        assert_eq!(todo.done[0].hashtags.len(), 1);
+
+        println!("{:#?}", todo.pending[0]);
+
+        assert!(todo.pending[0].priority.is_lowest());
+        assert!(todo.pending[0].create_date.is_some());
+        assert!(todo.pending[0].finish_date.is_none());
+        assert!(!todo.pending[0].finished);
+        assert_eq!(todo.pending[0].threshold_date, None);
+        assert!(todo.pending[0].due_date.is_some());
+        assert_eq!(todo.pending[0].contexts().len(), 1);
+        assert_eq!(todo.pending[0].projects().len(), 1);
+        assert_eq!(todo.pending[0].hashtags.len(), 0);
+
+        assert_eq!(todo.pending[1].priority, 2);
+        assert!(todo.pending[1].create_date.is_some());
+        assert!(todo.pending[1].finish_date.is_none());
+        assert!(!todo.pending[1].finished);
+        assert_eq!(todo.pending[1].threshold_date, None);
+        assert!(todo.pending[1].due_date.is_some());
+        assert_eq!(todo.pending[1].contexts().len(), 1);
+        assert_eq!(todo.pending[1].projects().len(), 1);
+        assert_eq!(todo.pending[1].hashtags.len(), 0);
+    }
+
+    fn create_vec(items: &[String]) -> Vec<(&String, Option<FilterState>)> {
+        let mut vec: Vec<(&String, Option<FilterState>)> = Vec::new();
+        items.iter().for_each(|item| {
+            vec.push((item, None));
+        });
+        vec
+    }
+
+    #[test]
+    fn test_categeries_list() -> Result<(), Box<dyn Error>> {
+        let mut todo = example_todo();
+        assert_eq!(
+            todo.get_categories(ToDoCategory::Projects).vec,
+            create_vec(&[String::from("project2"), String::from("project3")])
+        );
+        assert_eq!(
+            todo.get_categories(ToDoCategory::Contexts).vec,
+            create_vec(&[String::from("context2"), String::from("context3")])
+        );
+        assert_eq!(
+            todo.get_categories(ToDoCategory::Hashtags).vec,
+            create_vec(&[String::from("hashtag1"), String::from("hashtag2")])
+        );
+
+        todo.config.use_done = true;
+        assert_eq!(
+            todo.get_categories(ToDoCategory::Projects).vec,
+            create_vec(&[
+                String::from("project1"),
+                String::from("project2"),
+                String::from("project3"),
+            ])
+        );
+        assert_eq!(
+            todo.get_categories(ToDoCategory::Contexts).vec,
+            create_vec(&[
+                String::from("context1"),
+                String::from("context2"),
+                String::from("context3"),
+            ])
+        );
+        assert_eq!(
+            todo.get_categories(ToDoCategory::Hashtags).vec,
+            create_vec(&[String::from("hashtag1"), String::from("hashtag2")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filtering() -> Result<(), Box<dyn Error>> {
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str("task 1").unwrap());
+        todo.add_task(Task::from_str("task 2 +project1").unwrap());
+        todo.add_task(Task::from_str("task 3 +project1 +project2").unwrap());
+        todo.add_task(Task::from_str("task 4 +project1 +project3").unwrap());
+        todo.add_task(Task::from_str("task 5 +project1 +project2 +project3").unwrap());
+        todo.add_task(Task::from_str("task 6 +project3 @context2 #hashtag2 #hashtag1").unwrap());
+        todo.add_task(Task::from_str("task 7 +project2 @context1 #hashtag1 #hashtag2").unwrap());
+        todo.add_task(Task::from_str("task 8 +project2 @context2").unwrap());
+        todo.add_task(Task::from_str("task 9 +projects3 @context3").unwrap());
+        todo.add_task(Task::from_str("task 10 +project2 @context3 #hashtag1 #hashtag2").unwrap());
+        todo.add_task(Task::from_str("task 11 +project3 @context3 #hashtag2 #hashtag3").unwrap());
+        todo.add_task(Task::from_str("task 12 +project3 @context2 #hashtag2").unwrap());
+
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 12);
+
+        todo.state
+            .project_filters
+            .insert(String::from("project9999"), FilterState::Select);
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 0);
+
+        todo.state.project_filters.clear();
+        todo.state
+            .project_filters
+            .insert(String::from("project1"), FilterState::Select);
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 4);
+        assert_eq!(filtered[0].subject, "task 2 +project1");
+        assert_eq!(filtered[1].subject, "task 3 +project1 +project2");
+        assert_eq!(filtered[2].subject, "task 4 +project1 +project3");
+        assert_eq!(filtered[3].subject, "task 5 +project1 +project2 +project3");
+
+        todo.state
+            .project_filters
+            .insert(String::from("project2"), FilterState::Select);
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].subject, "task 3 +project1 +project2");
+        assert_eq!(filtered[1].subject, "task 5 +project1 +project2 +project3");
+
+        todo.state
+            .project_filters
+            .insert(String::from("project3"), FilterState::Select);
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].subject, "task 5 +project1 +project2 +project3");
+
+        todo.state
+            .project_filters
+            .insert(String::from("project1"), FilterState::Select);
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].subject, "task 5 +project1 +project2 +project3");
+
+        todo.state.project_filters.clear();
+        todo.state
+            .context_filters
+            .insert(String::from("context1"), FilterState::Select);
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(
+            filtered[0].subject,
+            "task 7 +project2 @context1 #hashtag1 #hashtag2"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn actual_consistency_move() {
+        let mut todo = example_todo();
+        todo.set_active(ToDoData::Pending, 2);
+        let subject = todo.get_active().unwrap().subject.clone();
+        // Item after
+        todo.move_task(ToDoData::Pending, 3);
+        assert_eq!(todo.get_active().unwrap().subject, subject);
+
+        // Item before
+        todo.move_task(ToDoData::Pending, 0);
+        assert_eq!(todo.get_active().unwrap().subject, subject);
+
+        // Active item
+        todo.move_task(ToDoData::Pending, 1);
+        assert!(todo.get_active().is_none());
+    }
+
+    #[test]
+    fn actual_consistency_remove() {
+        let mut todo = example_todo();
+        todo.set_active(ToDoData::Pending, 2);
+        let subject = todo.get_active().unwrap().subject.clone();
+        // Item after
+        todo.remove_task(ToDoData::Pending, 3);
+        assert_eq!(todo.get_active().unwrap().subject, subject);
+
+        // Item before
+        todo.remove_task(ToDoData::Pending, 0);
+        assert_eq!(todo.get_active().unwrap().subject, subject);
+
+        // Active item
+        todo.remove_task(ToDoData::Pending, 1);
+        assert!(todo.get_active().is_none());
+    }
+
+    #[test]
+    fn actual_consistency_swap() {
+        let mut todo = example_todo();
+        todo.set_active(ToDoData::Pending, 2);
+        let subject = todo.get_active().unwrap().subject.clone();
+        // Item outside
+        todo.swap_tasks(ToDoData::Pending, 0, 1);
+        assert_eq!(todo.get_active().unwrap().subject, subject);
+
+        // Item from
+        todo.swap_tasks(ToDoData::Pending, 2, 0);
+        assert_eq!(todo.get_active().unwrap().subject, subject);
+
+        // Item to
+        todo.swap_tasks(ToDoData::Pending, 1, 2);
+        assert_eq!(todo.get_active().unwrap().subject, subject);
+    }
+
+    #[test]
+    fn move_to() -> io::Result<()> {
+        let mut todo = example_todo();
+        let subjects = |todo: &ToDo| -> Vec<String> {
+            todo.pending.iter().map(|t| t.subject.clone()).collect()
+        };
+        let original = subjects(&todo);
+        let last = original.len() - 1;
+        todo.set_active(ToDoData::Pending, 2);
+
+        // Active task to the top
+        todo.move_to(ToDoData::Pending, 2, 0);
+        let mut expected = original.clone();
+        let task = expected.remove(2);
+        expected.insert(0, task);
+        assert_eq!(subjects(&todo), expected);
+        assert_eq!(todo.state.active, Some((ToDoData::Pending, 0)));
+
+        // Other task to the bottom, the active task stays
+        todo.move_to(ToDoData::Pending, 0, last);
+        todo.move_to(ToDoData::Pending, 1, last);
+        let task = expected.remove(0);
+        expected.push(task);
+        let task = expected.remove(1);
+        expected.push(task);
+        assert_eq!(subjects(&todo), expected);
+        assert_eq!(todo.state.active, Some((ToDoData::Pending, last - 1)));
+        assert_eq!(todo.get_active().unwrap().subject, original[2]);
+
+        // Other task to the top before the active task
+        todo.move_to(ToDoData::Pending, last, 0);
+        assert_eq!(todo.state.active, Some((ToDoData::Pending, last)));
+        assert_eq!(todo.get_active().unwrap().subject, original[2]);
+
+        todo.move_to(ToDoData::Pending, 0, last + 1);
+        assert_eq!(todo.pending[0].subject, expected[last]);
+
+        // The new order is saved.
+        let dir = TempDir::new("move-to");
+        let path = dir.join("todo.txt");
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let todo = Arc::new(Mutex::new(todo));
+        let mut worker = FileWorker::new(config, todo.clone());
+        worker.handle_command(FileWorkerCommands::Save)?;
+        let before = subjects(&todo.lock().unwrap());
+        todo.lock().unwrap().move_to(ToDoData::Pending, 0, last);
+        let moved = subjects(&todo.lock().unwrap());
+        assert_ne!(moved, before);
+        worker.handle_command(FileWorkerCommands::Save)?;
+        let saved = ToDo::from_reader(File::open(&path)?, &Config::default())?;
+        assert_eq!(subjects(&saved), moved);
+
+        Ok(())
+    }
+
+    #[test]
+    fn move_data() {
+        let todo = example_todo();
+        let mut empty = ToDo::default();
+        assert!(empty.pending.is_empty());
+        assert!(empty.done.is_empty());
+        empty.move_data(example_todo());
+        assert_eq!(todo.pending, empty.pending);
+        assert_eq!(todo.done, empty.done);
+    }
+
+    #[test]
+    fn unsaved_changes() -> Result<(), todo_txt::Error> {
+        let mut todo = ToDo::default();
+        assert!(!todo.has_unsaved_changes());
+        todo.new_task("task")?;
+        assert!(todo.has_unsaved_changes());
+        todo.mark_saved();
+        assert!(!todo.has_unsaved_changes());
+
+        // Changes of the view are not changes of tasks.
+        todo.set_search("task");
+        todo.toggle_filter(ToDoCategory::Projects, "project", FilterState::Select);
+        assert!(!todo.has_unsaved_changes());
+        todo.toggle_filter(ToDoCategory::Projects, "project", FilterState::Select);
+
+        todo.toggle_finished(ToDoData::Pending, 0);
+        assert!(todo.has_unsaved_changes());
+        todo.toggle_finished(ToDoData::Pending, 0);
+        assert!(!todo.has_unsaved_changes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn version() {
+        let mut todo = ToDo::default();
+        assert_eq!(todo.get_version(), 0);
+        todo.move_data(example_todo());
+        assert_eq!(todo.get_version(), 1);
+        todo.move_task(ToDoData::Done, 1);
+    }
+
+    #[test]
+    fn toggle_filter() {
+        let mut todo = example_todo();
+        assert!(todo.state.project_filters.is_empty());
+        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Select);
+        assert_eq!(
+            todo.state.project_filters.get("project1"),
+            Some(&FilterState::Select)
+        );
+        assert_eq!(todo.state.project_filters.len(), 1);
+        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Select);
+        assert!(todo.state.project_filters.is_empty());
+
+        todo.toggle_filter(ToDoCategory::Contexts, "context1", FilterState::Select);
+        assert_eq!(
+            todo.state.context_filters.get("context1"),
+            Some(&FilterState::Select)
+        );
+        assert_eq!(todo.state.context_filters.len(), 1);
+        todo.toggle_filter(ToDoCategory::Contexts, "context1", FilterState::Select);
+        assert!(todo.state.context_filters.is_empty());
+
+        todo.toggle_filter(ToDoCategory::Hashtags, "hashtag1", FilterState::Select);
+        assert_eq!(
+            todo.state.hashtag_filters.get("hashtag1"),
+            Some(&FilterState::Select)
+        );
+        assert_eq!(todo.state.hashtag_filters.len(), 1);
+        todo.toggle_filter(ToDoCategory::Hashtags, "hashtag1", FilterState::Select);
+        assert!(todo.state.hashtag_filters.is_empty());
+    }
+
+    #[test]
+    fn new_task() -> Result<(), todo_txt::Error> {
+        let mut todo = ToDo::default();
+        todo.new_task("Some pending task")?;
+        assert_eq!(todo.pending.len(), 1);
+        assert_eq!(todo.pending[0].subject, "Some pending task");
+        todo.new_task("x Some done task")?;
+        assert_eq!(todo.done.len(), 1);
+        assert_eq!(todo.done[0].subject, "Some done task");
+
+        let today = get_actual_date();
+        todo.new_task("Task for today due:today t:tomorrow")?;
+        assert_eq!(todo.pending[1].subject, "Task for today");
+        assert_eq!(todo.pending[1].due_date, Some(today));
+        assert_eq!(todo.pending[1].threshold_date, today.succ_opt());
+
+        todo.new_task("Threshold today t:today")?;
+        assert_eq!(todo.pending[2].subject, "Threshold today");
+        assert_eq!(todo.pending[2].threshold_date, Some(today));
+        todo.new_task("Empty threshold t:")?;
+        assert_eq!(todo.pending[3].subject, "Empty threshold");
+        assert_eq!(todo.pending[3].threshold_date, Some(today));
+
+        Ok(())
+    }
+
+    #[test]
+    fn inherit_active_category() -> Result<(), todo_txt::Error> {
+        let mut todo = ToDo::default();
+        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Select);
+        todo.new_task("not inherited")?;
+        assert_eq!(todo.pending[0].projects(), &[] as &[String]);
+
+        todo.config.inherit_active_category = true;
+        todo.new_task("inherited task")?;
+        assert_eq!(todo.pending[1].subject, "inherited task +project1");
+        assert_eq!(todo.pending[1].projects(), &["project1"]);
+        todo.new_task("typed +project1 already")?;
+        assert_eq!(todo.pending[2].subject, "typed +project1 already");
+
+        // Removed filters and multiple selected filters are not inherited.
+        todo.toggle_filter(ToDoCategory::Contexts, "home", FilterState::Remove);
+        todo.toggle_filter(ToDoCategory::Hashtags, "tag1", FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Hashtags, "tag2", FilterState::Select);
+        todo.new_task("another task")?;
+        assert_eq!(todo.pending[3].subject, "another task +project1");
+        assert_eq!(
+            todo.get_filtered_and_sorted(ToDoData::Pending).len(),
+            0,
+            "multiple selected hashtags are not inherited"
+        );
+
+        todo.toggle_filter(ToDoCategory::Hashtags, "tag2", FilterState::Select);
+        todo.new_task("last task @work")?;
+        assert_eq!(todo.pending[4].subject, "last task @work +project1 #tag1");
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Pending).len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn slow_post_hook() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("slow-hook");
+        let release = dir.join("release");
+        let log = dir.join("post.log");
+        // The hook blocks until the test releases it, so a synchronous hook
+        // would write the log before the task is returned.
+        let hook = dir.script(
+            "slow.sh",
+            &format!(
+                "#!/bin/sh\ni=0\nwhile [ ! -f {} ] && [ $i -lt 500 ]; do sleep 0.01; i=$((i+1)); done\necho \"$1\" >> {}\n",
+                release.display(),
+                log.display()
+            ),
+        )?;
+        let config = Config::load_from_buffer(
+            format!("[hook_paths]\npost_new = \"{}\"\n", hook.display()).as_bytes(),
+        );
+
+        assert_eq!(config.get_hook_paths().post_new, Some(hook));
+
+        let mut todo = ToDo::new(&config);
+        todo.new_task("Some pending task")?;
+        assert_eq!(todo.pending.len(), 1);
+        assert!(!log.exists());
+
+        std::fs::write(&release, "")?;
+        drop(todo);
+        assert!(std::fs::read_to_string(&log)?.ends_with("Some pending task\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_active() -> Result<(), todo_txt::Error> {
+        let mut todo = example_todo();
+        todo.state.active = Some((ToDoData::Pending, 0));
+        todo.update_active("New subject")?;
+        assert_eq!(todo.pending[0].subject, "New subject");
+
+        todo.state.active = Some((ToDoData::Done, 0));
+        todo.update_active("New done subject")?;
+        assert_eq!(todo.done[0].subject, "New done subject");
+
+        Ok(())
+    }
+
+    #[test]
+    fn issue_urls() {
+        let task = Task::from_str("fix crash #123 #bug #45").unwrap();
+        let mut todo = ToDo::default();
+        assert!(todo.get_issue_urls(&task).is_empty());
+
+        todo.config.issue_url_template =
+            Some(String::from("https://github.com/org/repo/issues/{n}"));
+        assert_eq!(
+            todo.get_issue_urls(&task),
+            vec![
+                String::from("https://github.com/org/repo/issues/123"),
+                String::from("https://github.com/org/repo/issues/45"),
+            ]
+        );
+    }
+
+    #[test]
+    fn active_link() -> Result<(), todo_txt::Error> {
+        let mut todo = ToDo::default();
+        todo.config.issue_url_template =
+            Some(String::from("https://github.com/org/repo/issues/{n}"));
+        todo.new_task("fix crash #123 #45")?;
+        todo.new_task("read docs https://example.com #123")?;
+        assert_eq!(todo.get_active_link(), None);
+
+        todo.state.active = Some((ToDoData::Pending, 0));
+        assert_eq!(
+            todo.get_active_link(),
+            Some(String::from("https://github.com/org/repo/issues/123"))
+        );
+
+        todo.state.active = Some((ToDoData::Pending, 1));
+        assert_eq!(
+            todo.get_active_link(),
+            Some(String::from("https://example.com"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn toggle_now() {
+        let mut todo = example_todo();
+        todo.toggle_now(ToDoData::Pending, 0);
+        assert!(todo.pending[0].tags.contains_key(NOW_TAG));
+
+        todo.toggle_now(ToDoData::Pending, 1);
+        assert!(!todo.pending[0].tags.contains_key(NOW_TAG));
+        assert!(todo.pending[1].tags.contains_key(NOW_TAG));
+        let sorted = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(sorted.get_actual_index(0), Some(1));
+        assert_eq!(sorted.get_actual_index(1), Some(0));
+
+        todo.toggle_now(ToDoData::Pending, 0);
+        assert!(todo.pending.iter().all(|t| !t.tags.contains_key(NOW_TAG)));
+    }
+
+    #[test]
+    fn toggle_pin() {
+        let mut todo = example_todo();
+        todo.change_sort(ToDoData::Pending, |_| TaskSort::Alphanumeric);
+        let subjects = |todo: &ToDo| -> Vec<String> {
+            todo.get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .map(|(_, task)| task.subject.clone())
+                .collect()
+        };
+        let unpinned = subjects(&todo);
+
+        todo.toggle_pin(ToDoData::Pending, 3);
+        let last = unpinned[3].clone();
+        assert!(todo
+            .pending
+            .iter()
+            .any(|task| task.subject == last && task.tags.contains_key(PIN_TAG)));
+        let pinned = subjects(&todo);
+        assert_eq!(pinned[0], last);
+        assert_eq!(&pinned[1..], [&unpinned[..3], &unpinned[4..]].concat());
+
+        todo.toggle_pin(ToDoData::Pending, 0);
+        assert!(todo.pending.iter().all(|t| !t.tags.contains_key(PIN_TAG)));
+        assert_eq!(subjects(&todo), unpinned);
+    }
+
+    #[test]
+    fn cycle_sort() {
+        let mut todo = ToDo::default();
+        for task in ["(B) b due:2023-05-02", "c", "(A) a due:2023-05-03"] {
+            todo.new_task(task).unwrap();
+        }
+        let subjects = |todo: &ToDo| -> Vec<String> {
+            todo.get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .map(|(_, task)| task.subject.clone())
+                .collect()
+        };
+        assert_eq!(subjects(&todo), ["b", "c", "a"]);
+
+        let mut orders = Vec::new();
+        for _ in 0..4 {
+            todo.change_sort(ToDoData::Pending, TaskSort::next);
+            orders.push(subjects(&todo));
+        }
+        assert_eq!(todo.get_sort(ToDoData::Pending), TaskSort::FinishDate);
+        assert_eq!(
+            orders,
+            [
+                ["a", "b", "c"],
+                ["b", "a", "c"],
+                ["a", "b", "c"],
+                ["b", "c", "a"]
+            ]
+        );
+
+        todo.change_sort(ToDoData::Pending, |_| TaskSort::Alphanumeric);
+        todo.change_sort(ToDoData::Pending, TaskSort::reversed);
+        assert_eq!(subjects(&todo), ["c", "b", "a"]);
+        assert_eq!(todo.get_sort(ToDoData::Done), TaskSort::FinishDateReverse);
+
+        // The sorting is restored with the state.
+        let state = todo.get_state().clone();
+        let mut new_todo = ToDo::default();
+        new_todo.update_state(state);
+        assert_eq!(
+            new_todo.get_sort(ToDoData::Pending),
+            TaskSort::AlphanumericReverse
+        );
+    }
+
+    #[test]
+    fn change_priority() {
+        let mut todo = example_todo();
+        let index = |todo: &ToDo, subject: &str| {
+            todo.get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .position(|(_, t)| t.subject.starts_with(subject))
+                .unwrap()
+        };
+        assert!(todo.pending[0].priority.is_lowest());
+        todo.change_priority(ToDoData::Pending, index(&todo, "measure space for 2"), true);
+        assert_eq!(todo.pending[0].priority, 'A');
+        todo.change_priority(ToDoData::Pending, index(&todo, "measure space for 2"), true);
+        assert_eq!(todo.pending[0].priority, 'A');
+        todo.change_priority(
+            ToDoData::Pending,
+            index(&todo, "measure space for 2"),
+            false,
+        );
+        assert_eq!(todo.pending[0].priority, 'B');
+
+        assert_eq!(todo.pending[1].priority, 'C');
+        todo.change_priority(ToDoData::Pending, index(&todo, "measure space for 3"), true);
+        assert_eq!(todo.pending[1].priority, 'B');
+
+        todo.pending[0].priority = Priority::from(25);
+        todo.change_priority(
+            ToDoData::Pending,
+            index(&todo, "measure space for 2"),
+            false,
+        );
+        assert!(todo.pending[0].priority.is_lowest());
+        todo.change_priority(
+            ToDoData::Pending,
+            index(&todo, "measure space for 2"),
+            false,
+        );
+        assert!(todo.pending[0].priority.is_lowest());
+    }
+
+    #[test]
+    fn toggle_finished() {
+        let mut todo = example_todo();
+        let pending = todo.pending.clone();
+        let index = |todo: &ToDo, subject: &str| {
+            todo.get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .position(|(_, t)| t.subject.starts_with(subject))
+                .unwrap()
+        };
+
+        todo.toggle_finished(ToDoData::Pending, index(&todo, "measure space for 3"));
+        assert_eq!(todo.pending.len(), pending.len());
+        assert!(todo.pending[1].finished);
+        assert_eq!(todo.pending[1].subject, pending[1].subject);
+        assert_eq!(todo.pending[1].finish_date, None);
+
+        todo.toggle_finished(ToDoData::Pending, index(&todo, "measure space for 3"));
+        assert_eq!(todo.pending, pending);
+
+        todo.config.set_final_date = true;
+        todo.toggle_finished(ToDoData::Pending, index(&todo, "measure space for 3"));
+        assert!(todo.pending[1].finished);
+        assert_eq!(todo.pending[1].finish_date, Some(get_actual_date()));
+        todo.toggle_finished(ToDoData::Pending, index(&todo, "measure space for 3"));
+        assert_eq!(todo.pending, pending);
+
+        // Reopened done tasks stay in the done list until they are relocated.
+        let done = todo.done.clone();
+        todo.state.active = Some((ToDoData::Done, 0));
+        todo.toggle_finished(ToDoData::Done, 0);
+        assert_eq!(todo.done.len(), done.len());
+        assert!(!todo.done[0].finished);
+        assert_eq!(todo.done[0].finish_date, None);
+
+        todo.relocate_done();
+        assert_eq!(todo.done, done[1..]);
+        assert_eq!(todo.pending.len(), pending.len() + 1);
+        assert_eq!(todo.pending[pending.len()].subject, done[0].subject);
+        assert!(!todo.pending[pending.len()].finished);
+        assert_eq!(todo.state.active, Some((ToDoData::Pending, pending.len())));
     }
 
     #[test]
-    fn test_categeries_list() -> Result<(), Box<dyn Error>> {
+    fn inline_done() {
         let mut todo = example_todo();
-        assert_eq!(
-            todo.get_categories(ToDoCategory::Projects).vec,
-            create_vec(&[String::from("project2"), String::from("project3")])
+        todo.config.inline_done = true;
+        let pending = todo.pending.len();
+        let done = todo.done.len();
+        let subject = todo.pending[1].subject.clone();
+        todo.state.active = Some((ToDoData::Pending, 2));
+
+        todo.move_task(ToDoData::Pending, 1);
+        assert_eq!(todo.pending.len(), pending);
+        assert!(todo.pending[1].finished);
+        assert_eq!(todo.pending[1].subject, subject);
+
+        todo.relocate_done();
+        assert_eq!(todo.pending.len(), pending - 1);
+        assert_eq!(todo.done.len(), done + 1);
+        assert_eq!(todo.done[done].subject, subject);
+        assert!(todo.pending.iter().all(|task| !task.finished));
+        assert_eq!(todo.state.active, Some((ToDoData::Pending, 1)));
+    }
+
+    #[test]
+    fn preserve_priority_on_done() -> Result<(), Box<dyn Error>> {
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str("(A) important task")?);
+        todo.add_task(Task::from_str("task without priority")?);
+
+        // Disabled by default, the priority is kept.
+        todo.move_task(ToDoData::Pending, 0);
+        assert_eq!(todo.done[0].to_string(), "x (A) important task");
+        todo.move_task(ToDoData::Done, 0);
+        assert_eq!(todo.pending[1].to_string(), "(A) important task");
+
+        todo.config.preserve_priority_on_done = true;
+        let index = |todo: &ToDo, data, subject: &str| {
+            todo.get_filtered_and_sorted(data)
+                .vec
+                .iter()
+                .position(|(_, task)| task.subject == subject)
+                .unwrap()
+        };
+        todo.move_task(
+            ToDoData::Pending,
+            index(&todo, ToDoData::Pending, "important task"),
         );
-        assert_eq!(
-            todo.get_categories(ToDoCategory::Contexts).vec,
-            create_vec(&[String::from("context2"), String::from("context3")])
+        assert_eq!(todo.done[0].to_string(), "x important task pri:A");
+        todo.move_task(ToDoData::Done, 0);
+        assert_eq!(todo.pending[1].to_string(), "(A) important task");
+
+        // Tasks without priority are not changed.
+        todo.move_task(
+            ToDoData::Pending,
+            index(&todo, ToDoData::Pending, "task without priority"),
         );
+        assert_eq!(todo.done[0].to_string(), "x task without priority");
+        todo.move_task(ToDoData::Done, 0);
+        assert_eq!(todo.pending[1].to_string(), "task without priority");
+
+        // Inline completion converts the priority too.
+        todo.config.inline_done = true;
+        let important = index(&todo, ToDoData::Pending, "important task");
+        todo.toggle_finished(ToDoData::Pending, important);
+        let important = index(&todo, ToDoData::Pending, "important task");
+        let actual = todo.get_actual_index(ToDoData::Pending, important).unwrap();
+        assert_eq!(todo.pending[actual].to_string(), "x important task pri:A");
+        todo.toggle_finished(ToDoData::Pending, important);
+        assert_eq!(todo.pending[actual].to_string(), "(A) important task");
+
+        Ok(())
+    }
+
+    #[test]
+    fn complete_filtered() -> Result<(), Box<dyn Error>> {
+        let mut todo = ToDo::default();
+        for task in [
+            "2023-01-01 first +done_project",
+            "other task",
+            "2023-01-01 second +done_project",
+            "2023-01-01 third +done_project",
+            "last task",
+        ] {
+            todo.add_task(Task::from_str(task)?);
+        }
+        todo.config.set_final_date = true;
+        todo.state.active = Some((ToDoData::Pending, 4));
+        todo.toggle_filter(ToDoCategory::Projects, "done_project", FilterState::Select);
+
+        assert_eq!(todo.filtered_unfinished_count(), 3);
+        assert_eq!(todo.complete_filtered(), 3);
+        let subjects = |tasks: &[Task]| tasks.iter().map(|t| t.subject.clone()).collect::<Vec<_>>();
+        assert_eq!(subjects(&todo.pending), ["other task", "last task"]);
         assert_eq!(
-            todo.get_categories(ToDoCategory::Hashtags).vec,
-            create_vec(&[String::from("hashtag1"), String::from("hashtag2")])
+            subjects(&todo.done),
+            [
+                "first +done_project",
+                "second +done_project",
+                "third +done_project"
+            ]
         );
+        assert!(todo
+            .done
+            .iter()
+            .all(|task| task.finished && task.finish_date == Some(get_actual_date())));
+        assert_eq!(todo.state.active, Some((ToDoData::Pending, 1)));
+        assert_eq!(todo.complete_filtered(), 0);
+
+        todo.config.set_final_date = false;
+        todo.config.inline_done = true;
+        todo.state.clear_filters();
+        assert_eq!(todo.complete_filtered(), 2);
+        assert_eq!(todo.pending.len(), 2);
+        assert_eq!(todo.filtered_unfinished_count(), 0);
+        assert!(todo
+            .pending
+            .iter()
+            .all(|task| task.finished && task.finish_date.is_none()));
 
-        todo.config.use_done = true;
+        Ok(())
+    }
+
+    #[test]
+    fn filter_chips() {
+        let mut todo = example_todo();
+        assert!(todo.get_filter_chips().is_empty());
+
+        todo.toggle_filter(ToDoCategory::Projects, "project2", FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Hashtags, "hashtag1", FilterState::Remove);
+        let chips = todo.get_filter_chips();
+        assert_eq!(chips.len(), 3);
+        assert_eq!(chips[0].content, "+project2");
         assert_eq!(
-            todo.get_categories(ToDoCategory::Projects).vec,
-            create_vec(&[
-                String::from("project1"),
-                String::from("project2"),
-                String::from("project3"),
-            ])
+            chips[0].style,
+            todo.styles.category_select_style.get_style()
         );
+        assert_eq!(chips[1].content, " ");
+        assert_eq!(chips[2].content, "#hashtag1");
         assert_eq!(
-            todo.get_categories(ToDoCategory::Contexts).vec,
-            create_vec(&[
-                String::from("context1"),
-                String::from("context2"),
-                String::from("context3"),
-            ])
+            chips[2].style,
+            todo.styles.category_remove_style.get_style()
         );
+
+        todo.toggle_due_filter(FilterState::Remove);
+        let chips = todo.get_filter_chips();
+        assert_eq!(chips.len(), 5);
+        assert_eq!(chips[4].content, "no due");
         assert_eq!(
-            todo.get_categories(ToDoCategory::Hashtags).vec,
-            create_vec(&[String::from("hashtag1"), String::from("hashtag2")])
+            chips[4].style,
+            todo.styles.category_remove_style.get_style()
         );
-
-        Ok(())
     }
 
     #[test]
-    fn test_filtering() -> Result<(), Box<dyn Error>> {
+    fn due_filter() -> Result<(), Box<dyn Error>> {
         let mut todo = ToDo::default();
-        todo.add_task(Task::from_str("task 1").unwrap());
-        todo.add_task(Task::from_str("task 2 +project1").unwrap());
-        todo.add_task(Task::from_str("task 3 +project1 +project2").unwrap());
-        todo.add_task(Task::from_str("task 4 +project1 +project3").unwrap());
-        todo.add_task(Task::from_str("task 5 +project1 +project2 +project3").unwrap());
-        todo.add_task(Task::from_str("task 6 +project3 @context2 #hashtag2 #hashtag1").unwrap());
-        todo.add_task(Task::from_str("task 7 +project2 @context1 #hashtag1 #hashtag2").unwrap());
-        todo.add_task(Task::from_str("task 8 +project2 @context2").unwrap());
-        todo.add_task(Task::from_str("task 9 +projects3 @context3").unwrap());
-        todo.add_task(Task::from_str("task 10 +project2 @context3 #hashtag1 #hashtag2").unwrap());
-        todo.add_task(Task::from_str("task 11 +project3 @context3 #hashtag2 #hashtag3").unwrap());
-        todo.add_task(Task::from_str("task 12 +project3 @context2 #hashtag2").unwrap());
-
-        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
-        assert_eq!(filtered.len(), 12);
-
-        todo.state
-            .project_filters
-            .insert(String::from("project9999"), FilterState::Select);
-        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
-        assert_eq!(filtered.len(), 0);
-
-        todo.state.project_filters.clear();
-        todo.state
-            .project_filters
-            .insert(String::from("project1"), FilterState::Select);
-        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
-        assert_eq!(filtered.len(), 4);
-        assert_eq!(filtered[0].subject, "task 2 +project1");
-        assert_eq!(filtered[1].subject, "task 3 +project1 +project2");
-        assert_eq!(filtered[2].subject, "task 4 +project1 +project3");
-        assert_eq!(filtered[3].subject, "task 5 +project1 +project2 +project3");
+        todo.add_task(Task::from_str("task 1 +project1 due:2023-11-01")?);
+        todo.add_task(Task::from_str("task 2 +project1")?);
+        todo.add_task(Task::from_str("task 3 +project2 due:2023-11-02")?);
+        todo.add_task(Task::from_str("task 4")?);
+        todo.add_task(Task::from_str("x task 5 +project1 due:2023-11-03")?);
 
-        todo.state
-            .project_filters
-            .insert(String::from("project2"), FilterState::Select);
+        todo.toggle_due_filter(FilterState::Select);
         let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
         assert_eq!(filtered.len(), 2);
-        assert_eq!(filtered[0].subject, "task 3 +project1 +project2");
-        assert_eq!(filtered[1].subject, "task 5 +project1 +project2 +project3");
+        assert_eq!(filtered[0].subject, "task 1 +project1");
+        assert_eq!(filtered[1].subject, "task 3 +project2");
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Done).len(), 1);
 
-        todo.state
-            .project_filters
-            .insert(String::from("project3"), FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Select);
         let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
         assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].subject, "task 5 +project1 +project2 +project3");
+        assert_eq!(filtered[0].subject, "task 1 +project1");
 
-        todo.state
-            .project_filters
-            .insert(String::from("project1"), FilterState::Select);
+        todo.toggle_due_filter(FilterState::Remove);
+        assert_eq!(todo.state.due_filter, Some(FilterState::Remove));
         let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
         assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].subject, "task 5 +project1 +project2 +project3");
+        assert_eq!(filtered[0].subject, "task 2 +project1");
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Done).len(), 0);
 
-        todo.state.project_filters.clear();
-        todo.state
-            .context_filters
-            .insert(String::from("context1"), FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Remove);
         let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
         assert_eq!(filtered.len(), 1);
-        assert_eq!(
-            filtered[0].subject,
-            "task 7 +project2 @context1 #hashtag1 #hashtag2"
-        );
+        assert_eq!(filtered[0].subject, "task 4");
+
+        todo.toggle_due_filter(FilterState::Remove);
+        assert_eq!(todo.state.due_filter, None);
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].subject, "task 3 +project2");
+        assert_eq!(filtered[1].subject, "task 4");
 
         Ok(())
     }
 
     #[test]
-    fn actual_consistency_move() {
+    fn invert_filters() {
         let mut todo = example_todo();
-        todo.set_active(ToDoData::Pending, 2);
-        let subject = todo.get_active().unwrap().subject.clone();
-        // Item after
-        todo.move_task(ToDoData::Pending, 3);
-        assert_eq!(todo.get_active().unwrap().subject, subject);
+        let subjects = |todo: &ToDo| -> Vec<String> {
+            todo.get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .map(|(_, task)| task.subject.clone())
+                .collect()
+        };
+        let all = subjects(&todo);
 
-        // Item before
-        todo.move_task(ToDoData::Pending, 0);
-        assert_eq!(todo.get_active().unwrap().subject, subject);
+        todo.toggle_filter(ToDoCategory::Projects, "project2", FilterState::Select);
+        let selected = subjects(&todo);
+        todo.invert_filters(ToDoCategory::Projects);
+        assert_eq!(
+            todo.state.project_filters.get("project2"),
+            Some(&FilterState::Remove)
+        );
+        let inverted = subjects(&todo);
+        assert!(!selected.is_empty());
+        assert!(!inverted.is_empty());
+        assert_eq!(selected.len() + inverted.len(), all.len());
+        assert!(inverted.iter().all(|subject| !selected.contains(subject)));
 
-        // Active item
-        todo.move_task(ToDoData::Pending, 1);
-        assert!(todo.get_active().is_none());
+        // Only filters of the category are inverted.
+        todo.toggle_filter(ToDoCategory::Hashtags, "hashtag1", FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Projects, "project3", FilterState::Select);
+        todo.invert_filters(ToDoCategory::Projects);
+        assert_eq!(
+            todo.state.project_filters.get("project2"),
+            Some(&FilterState::Select)
+        );
+        assert_eq!(
+            todo.state.project_filters.get("project3"),
+            Some(&FilterState::Remove)
+        );
+        assert_eq!(
+            todo.state.hashtag_filters.get("hashtag1"),
+            Some(&FilterState::Select)
+        );
+
+        // Inverting twice restores the original filters.
+        todo.invert_filters(ToDoCategory::Hashtags);
+        todo.invert_filters(ToDoCategory::Hashtags);
+        assert_eq!(
+            todo.state.hashtag_filters.get("hashtag1"),
+            Some(&FilterState::Select)
+        );
     }
 
     #[test]
-    fn actual_consistency_remove() {
-        let mut todo = example_todo();
-        todo.set_active(ToDoData::Pending, 2);
-        let subject = todo.get_active().unwrap().subject.clone();
-        // Item after
-        todo.remove_task(ToDoData::Pending, 3);
-        assert_eq!(todo.get_active().unwrap().subject, subject);
+    fn due_today_filter() -> Result<(), Box<dyn Error>> {
+        let today = get_actual_date();
+        let mut todo = ToDo::default();
+        for (subject, date) in [
+            ("yesterday +work", today.pred_opt().unwrap()),
+            ("today +work", today),
+            ("tomorrow +work", today.succ_opt().unwrap()),
+            ("today +home", today),
+        ] {
+            todo.add_task(Task::from_str(&format!(
+                "{subject} due:{}",
+                date.format("%Y-%m-%d")
+            ))?);
+        }
+        todo.add_task(Task::from_str("no due date +work")?);
+        let subjects = |todo: &ToDo| -> Vec<String> {
+            todo.get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .map(|(_, task)| task.subject.clone())
+                .collect()
+        };
 
-        // Item before
-        todo.remove_task(ToDoData::Pending, 0);
-        assert_eq!(todo.get_active().unwrap().subject, subject);
+        todo.toggle_date_filter(DateFilter::DueToday);
+        assert_eq!(subjects(&todo), ["today +work", "today +home"]);
+        assert_eq!(
+            todo.get_filter_chips(),
+            [Span::styled(
+                "due today",
+                todo.styles.category_select_style.get_style()
+            )]
+        );
 
-        // Active item
-        todo.remove_task(ToDoData::Pending, 1);
-        assert!(todo.get_active().is_none());
+        todo.toggle_filter(ToDoCategory::Projects, "work", FilterState::Select);
+        assert_eq!(subjects(&todo), ["today +work"]);
+
+        todo.toggle_date_filter(DateFilter::DueToday);
+        assert_eq!(todo.state.date_filter, None);
+        assert_eq!(
+            subjects(&todo),
+            [
+                "yesterday +work",
+                "today +work",
+                "tomorrow +work",
+                "no due date +work"
+            ]
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn actual_consistency_swap() {
-        let mut todo = example_todo();
-        todo.set_active(ToDoData::Pending, 2);
-        let subject = todo.get_active().unwrap().subject.clone();
-        // Item outside
-        todo.swap_tasks(ToDoData::Pending, 0, 1);
-        assert_eq!(todo.get_active().unwrap().subject, subject);
+    fn overdue_filter() -> Result<(), Box<dyn Error>> {
+        let today = get_actual_date();
+        let date = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str(&format!(
+            "last week +work due:{}",
+            date(today - chrono::Duration::days(7))
+        ))?);
+        todo.add_task(Task::from_str(&format!(
+            "yesterday +home due:{}",
+            date(today.pred_opt().unwrap())
+        ))?);
+        todo.add_task(Task::from_str(&format!("today due:{}", date(today)))?);
+        todo.add_task(Task::from_str(&format!(
+            "tomorrow due:{}",
+            date(today.succ_opt().unwrap())
+        ))?);
+        todo.add_task(Task::from_str("no due date")?);
+        todo.add_task(Task::from_str(&format!(
+            "x done yesterday due:{}",
+            date(today.pred_opt().unwrap())
+        ))?);
+        let subjects = |todo: &ToDo, data| -> Vec<String> {
+            todo.get_filtered_and_sorted(data)
+                .vec
+                .iter()
+                .map(|(_, task)| task.subject.clone())
+                .collect()
+        };
 
-        // Item from
-        todo.swap_tasks(ToDoData::Pending, 2, 0);
-        assert_eq!(todo.get_active().unwrap().subject, subject);
+        todo.toggle_date_filter(DateFilter::Overdue);
+        assert_eq!(
+            subjects(&todo, ToDoData::Pending),
+            ["last week +work", "yesterday +home"]
+        );
+        assert!(subjects(&todo, ToDoData::Done).is_empty());
 
-        // Item to
-        todo.swap_tasks(ToDoData::Pending, 1, 2);
-        assert_eq!(todo.get_active().unwrap().subject, subject);
+        todo.toggle_filter(ToDoCategory::Projects, "home", FilterState::Select);
+        assert_eq!(subjects(&todo, ToDoData::Pending), ["yesterday +home"]);
+        todo.toggle_filter(ToDoCategory::Projects, "home", FilterState::Select);
+
+        // Another date filter replaces the active one.
+        todo.toggle_date_filter(DateFilter::DueToday);
+        assert_eq!(subjects(&todo, ToDoData::Pending), ["today"]);
+        todo.toggle_date_filter(DateFilter::Overdue);
+        todo.toggle_date_filter(DateFilter::Overdue);
+        assert_eq!(todo.state.date_filter, None);
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Pending).len(), 5);
+
+        Ok(())
     }
 
     #[test]
-    fn move_data() {
-        let todo = example_todo();
-        let mut empty = ToDo::default();
-        assert!(empty.pending.is_empty());
-        assert!(empty.done.is_empty());
-        empty.move_data(example_todo());
-        assert_eq!(todo.pending, empty.pending);
-        assert_eq!(todo.done, empty.done);
+    fn clear_all_filters() {
+        let mut todo = example_todo();
+        let pending = todo.get_filtered_and_sorted(ToDoData::Pending).len();
+        let done = todo.get_filtered_and_sorted(ToDoData::Done).len();
+
+        todo.toggle_filter(ToDoCategory::Projects, "project3", FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Contexts, "context2", FilterState::Remove);
+        todo.toggle_filter(ToDoCategory::Hashtags, "hashtag2", FilterState::Select);
+        todo.toggle_due_filter(FilterState::Remove);
+        todo.toggle_date_filter(DateFilter::DueToday);
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Pending).len(), 0);
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Done).len(), 0);
+
+        let version = todo.get_version();
+        todo.clear_all_filters();
+        assert!(todo.get_version() > version);
+        assert!(todo.get_filter_chips().is_empty());
+        assert_eq!(
+            todo.get_filtered_and_sorted(ToDoData::Pending).len(),
+            pending
+        );
+        assert_eq!(todo.get_filtered_and_sorted(ToDoData::Done).len(), done);
     }
 
     #[test]
-    fn version() {
-        let mut todo = ToDo::default();
-        assert_eq!(todo.get_version(), 0);
-        todo.move_data(example_todo());
-        assert_eq!(todo.get_version(), 1);
-        todo.move_task(ToDoData::Done, 1);
+    fn filter_presets() {
+        let mut todo = example_todo();
+        let subjects = |todo: &ToDo| -> Vec<String> {
+            todo.get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .map(|(_, task)| task.subject.clone())
+                .collect()
+        };
+
+        todo.toggle_filter(ToDoCategory::Projects, "project3", FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Contexts, "context3", FilterState::Remove);
+        todo.toggle_due_filter(FilterState::Select);
+        let filtered = subjects(&todo);
+        assert_eq!(
+            filtered,
+            vec!["measure space for 6 +project3 @context2 #hashtag2"]
+        );
+        todo.save_filter_preset(" work ");
+        todo.save_filter_preset("");
+        assert_eq!(todo.get_filter_presets().collect::<Vec<_>>(), vec!["work"]);
+
+        todo.clear_all_filters();
+        assert_ne!(subjects(&todo), filtered);
+
+        let version = todo.get_version();
+        assert!(!todo.apply_filter_preset("unknown"));
+        assert_eq!(todo.get_version(), version);
+        assert!(todo.apply_filter_preset("work"));
+        assert!(todo.get_version() > version);
+        assert_eq!(subjects(&todo), filtered);
+
+        // Changing filters after the apply does not change the preset.
+        todo.toggle_filter(ToDoCategory::Projects, "project3", FilterState::Select);
+        assert!(todo.apply_filter_preset("work"));
+        assert_eq!(subjects(&todo), filtered);
     }
 
     #[test]
-    fn toggle_filter() {
+    fn update_active_subject() -> Result<(), todo_txt::Error> {
         let mut todo = example_todo();
-        assert!(todo.state.project_filters.is_empty());
-        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Select);
+        todo.state.active = Some((ToDoData::Pending, 1));
+        let old = todo.pending[1].clone();
+        assert_eq!(old.priority, Priority::from(2));
+        let version = todo.get_version();
+        todo.update_active_subject(" New subject +project4 @context4 #tag +project4 a+b ")?;
+        assert!(todo.get_version() > version);
+
+        let task = &todo.pending[1];
         assert_eq!(
-            todo.state.project_filters.get("project1"),
-            Some(&FilterState::Select)
+            task.subject,
+            "New subject +project4 @context4 #tag +project4 a+b"
         );
-        assert_eq!(todo.state.project_filters.len(), 1);
-        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Select);
-        assert!(todo.state.project_filters.is_empty());
-
-        todo.toggle_filter(ToDoCategory::Contexts, "context1", FilterState::Select);
+        assert_eq!(task.projects(), &["project4"]);
+        assert_eq!(task.contexts(), &["context4"]);
+        assert_eq!(task.hashtags, ["tag"]);
         assert_eq!(
-            todo.state.context_filters.get("context1"),
-            Some(&FilterState::Select)
+            task,
+            &Task::from_str(&format!("(C) 2023-04-30 {} due:2023-06-30", task.subject))?
         );
-        assert_eq!(todo.state.context_filters.len(), 1);
-        todo.toggle_filter(ToDoCategory::Contexts, "context1", FilterState::Select);
-        assert!(todo.state.context_filters.is_empty());
+        assert_eq!(task.priority, old.priority);
+        assert_eq!(task.create_date, old.create_date);
+        assert_eq!(task.due_date, old.due_date);
+        assert_eq!(task.tags, old.tags);
 
-        todo.toggle_filter(ToDoCategory::Hashtags, "hashtag1", FilterState::Select);
+        todo.update_active_subject("")?;
         assert_eq!(
-            todo.state.hashtag_filters.get("hashtag1"),
-            Some(&FilterState::Select)
+            todo.pending[1].subject,
+            "New subject +project4 @context4 #tag +project4 a+b"
         );
-        assert_eq!(todo.state.hashtag_filters.len(), 1);
-        todo.toggle_filter(ToDoCategory::Hashtags, "hashtag1", FilterState::Select);
-        assert!(todo.state.hashtag_filters.is_empty());
-    }
 
-    #[test]
-    fn new_task() -> Result<(), todo_txt::Error> {
-        let mut todo = ToDo::default();
-        todo.new_task("Some pending task")?;
-        assert_eq!(todo.pending.len(), 1);
-        assert_eq!(todo.pending[0].subject, "Some pending task");
-        todo.new_task("x Some done task")?;
-        assert_eq!(todo.done.len(), 1);
-        assert_eq!(todo.done[0].subject, "Some done task");
+        todo.state.active = Some((ToDoData::Done, 0));
+        todo.update_active_subject("Renamed done task")?;
+        assert_eq!(todo.done[0].subject, "Renamed done task");
+        assert!(todo.done[0].finished);
+        assert_eq!(todo.done[0].priority, Priority::from(0));
 
         Ok(())
     }
 
     #[test]
-    fn update_active() -> Result<(), todo_txt::Error> {
+    fn append_to_active() -> Result<(), todo_txt::Error> {
         let mut todo = example_todo();
-        todo.state.active = Some((ToDoData::Pending, 0));
-        todo.update_active("New subject")?;
-        assert_eq!(todo.pending[0].subject, "New subject");
-
-        todo.state.active = Some((ToDoData::Done, 0));
-        todo.update_active("New done subject")?;
-        assert_eq!(todo.done[0].subject, "New done subject");
+        todo.append_to_active("+ignored")?;
+        assert!(todo
+            .pending
+            .iter()
+            .all(|t| !t.projects().contains(&String::from("ignored"))));
+
+        todo.state.active = Some((ToDoData::Pending, 1));
+        let original = todo.pending[1].clone();
+        todo.append_to_active(" +new due:2023-07-01 ")?;
+        let task = todo.pending[1].clone();
+        assert_eq!(task.subject, "measure space for 3 +project3 @context3 +new");
+        assert_eq!(task.priority, original.priority);
+        assert_eq!(task.create_date, original.create_date);
+        assert_eq!(task.due_date, NaiveDate::from_ymd_opt(2023, 7, 1));
+        assert!(task.projects().contains(&String::from("new")));
+
+        todo.append_to_active("")?;
+        assert_eq!(todo.pending[1], task);
 
         Ok(())
     }
@@ -1,6 +1,7 @@
-use super::FilterState;
+use super::{FilterState, Search};
 use crate::config::Styles;
-use tui::text::Span;
+use tui::style::Style;
+use tui::text::{Line, Span};
 use tui::widgets::ListItem;
 
 /// Represents a list of categories, where each category is a tuple of `(&'a String, bool)`.
@@ -9,9 +10,33 @@ use tui::widgets::ListItem;
 pub struct CategoryList<'a> {
     pub vec: Vec<(&'a String, Option<FilterState>)>,
     pub styles: &'a Styles,
+    pub search: Option<&'a Search>,
+    pub prefix: Option<char>,
 }
 
 impl<'a> CategoryList<'a> {
+    /// Sets the search to highlight in the category names. Terms of the
+    /// search are highlighted with the same matching rules as in tasks.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The search to highlight.
+    pub fn search(mut self, search: Option<&'a Search>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Sets the character shown before the category names, so categories
+    /// of different types can be shown in one list.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The character starting the category in a task.
+    pub fn prefix(mut self, prefix: char) -> Self {
+        self.prefix = Some(prefix);
+        self
+    }
+
     /// Returns a vector of references to categories that start with the specified pattern.
     ///
     /// # Arguments
@@ -63,16 +88,24 @@ impl<'a> From<CategoryList<'a>> for Vec<ListItem<'a>> {
             .iter()
             .map(|(category, active)| {
                 use FilterState::*;
-                match active {
-                    Some(Select) => ListItem::new(Span::styled(
-                        (*category).clone(),
-                        val.styles.category_select_style.get_style(),
-                    )),
-                    Some(Remove) => ListItem::new(Span::styled(
-                        (*category).clone(),
-                        val.styles.category_remove_style.get_style(),
-                    )),
-                    None => ListItem::new((*category).clone()),
+                let style = match active {
+                    Some(Select) => val.styles.category_select_style.get_style(),
+                    Some(Remove) => val.styles.category_remove_style.get_style(),
+                    None => Style::default(),
+                };
+                let text = match val.prefix {
+                    Some(prefix) => format!("{prefix}{category}"),
+                    None => (*category).clone(),
+                };
+                let terms = val.search.map(|search| search.find_terms(&text));
+                let span = Span::styled(text, style);
+                match terms {
+                    Some(terms) => ListItem::new(Line::from(terms.highlight(
+                        vec![span],
+                        val.styles.highlight.get_style(),
+                        None,
+                    ))),
+                    None => ListItem::new(span),
                 }
             })
             .collect()
@@ -100,6 +133,8 @@ mod tests {
                 (&third2, None),
             ],
             styles: &styles,
+            search: None,
+            prefix: None,
         };
 
         assert!(!categories.is_empty());
@@ -121,6 +156,8 @@ mod tests {
                 (&third2, None),
             ],
             styles: &styles,
+            search: None,
+            prefix: None,
         };
         assert!(categories.start_with("none").is_empty());
 
@@ -149,6 +186,8 @@ mod tests {
                 (&third2, None),
             ],
             styles: &styles,
+            search: None,
+            prefix: None,
         };
 
         let items = Vec::<ListItem>::from(categories);
@@ -164,4 +203,79 @@ mod tests {
         );
         assert_eq!(items[3], ListItem::new(third2.clone()));
     }
+
+    #[test]
+    fn search_highlight() {
+        let styles = Styles::new(&Config::default());
+        let highlight = styles.highlight.get_style();
+        let names = [
+            String::from("čeština"),
+            String::from("日本語プロジェクト"),
+            String::from("other"),
+        ];
+        let search = Search::new("ŠTI プロ").unwrap();
+        let categories = CategoryList {
+            vec: names.iter().map(|name| (name, None)).collect(),
+            styles: &styles,
+            search: None,
+            prefix: None,
+        }
+        .search(Some(&search));
+        let items = Vec::<ListItem>::from(categories);
+        assert_eq!(
+            items[0],
+            ListItem::new(Line::from(vec![
+                Span::raw("če"),
+                Span::styled("šti", highlight),
+                Span::raw("na"),
+            ]))
+        );
+        assert_eq!(
+            items[1],
+            ListItem::new(Line::from(vec![
+                Span::raw("日本語"),
+                Span::styled("プロ", highlight),
+                Span::raw("ジェクト"),
+            ]))
+        );
+        assert_eq!(items[2], ListItem::new(names[2].clone()));
+
+        // Word boundary is respected as in the task search.
+        let search = Search::new("\\bšti").unwrap();
+        let categories = CategoryList {
+            vec: names.iter().map(|name| (name, None)).collect(),
+            styles: &styles,
+            search: Some(&search),
+            prefix: None,
+        };
+        let items = Vec::<ListItem>::from(categories);
+        assert_eq!(items[0], ListItem::new(names[0].clone()));
+    }
+
+    #[test]
+    fn prefix() {
+        let styles = Styles::new(&Config::default());
+        let names = [String::from("project"), String::from("other")];
+        let search = Search::new("+pro").unwrap();
+        let categories = CategoryList {
+            vec: vec![(&names[0], Some(FilterState::Remove)), (&names[1], None)],
+            styles: &styles,
+            search: None,
+            prefix: None,
+        }
+        .prefix('+')
+        .search(Some(&search));
+        assert_eq!(categories.get_name(0), "project");
+
+        let items = Vec::<ListItem>::from(categories);
+        let remove = styles.category_remove_style.get_style();
+        assert_eq!(
+            items[0],
+            ListItem::new(Line::from(vec![
+                Span::styled("+pro", remove.patch(styles.highlight.get_style())),
+                Span::styled("ject", remove),
+            ]))
+        );
+        assert_eq!(items[1], ListItem::new("+other"));
+    }
 }
@@ -0,0 +1,130 @@
+use super::{ToDo, ToDoData};
+use chrono::{NaiveDate, Weekday};
+use todo_txt::Task;
+
+/// Checks if the pending task is after its due date.
+pub fn is_overdue(task: &Task, today: NaiveDate) -> bool {
+    !task.finished && task.due_date.is_some_and(|due| due < today)
+}
+
+/// Checks if the pending task is due today.
+pub fn is_due_today(task: &Task, today: NaiveDate) -> bool {
+    !task.finished && task.due_date == Some(today)
+}
+
+/// Checks if the pending task is due within the week containing today.
+///
+/// # Arguments
+///
+/// * `task` - The checked task.
+/// * `today` - The date that determines the current week.
+/// * `week_start` - The first day of the week.
+pub fn is_due_this_week(task: &Task, today: NaiveDate, week_start: Weekday) -> bool {
+    let week = today.week(week_start);
+    !task.finished
+        && task
+            .due_date
+            .is_some_and(|due| week.first_day() <= due && due <= week.last_day())
+}
+
+/// Numbers of tasks matching the active filters.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TaskCounts {
+    pub pending: usize,
+    pub done: usize,
+    pub overdue: usize,
+    pub due_today: usize,
+}
+
+impl TaskCounts {
+    /// Counts tasks of the todo list.
+    ///
+    /// # Arguments
+    ///
+    /// * `todo` - The todo list, only tasks matching its filters are counted.
+    /// * `today` - The date overdue and due today tasks are counted to.
+    pub fn new(todo: &ToDo, today: NaiveDate) -> Self {
+        let pending = todo.get_filtered_and_sorted(ToDoData::Pending);
+        let count = |predicate: fn(&Task, NaiveDate) -> bool| {
+            pending
+                .vec
+                .iter()
+                .filter(|(_, task)| predicate(task, today))
+                .count()
+        };
+        Self {
+            pending: pending.len(),
+            done: todo.len(ToDoData::Done),
+            overdue: count(is_overdue),
+            due_today: count(is_due_today),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::{FilterState, ToDoCategory};
+
+    #[test]
+    fn counts() -> Result<(), todo_txt::Error> {
+        let today = NaiveDate::from_ymd_opt(2023, 11, 1).unwrap();
+        let mut todo = ToDo::default();
+        todo.new_task("overdue task due:2023-10-31 +work")?;
+        todo.new_task("due today due:2023-11-01")?;
+        todo.new_task("another due today due:2023-11-01 +work")?;
+        todo.new_task("due later due:2023-11-02 +work")?;
+        todo.new_task("without due date")?;
+        todo.new_task("x done overdue task due:2023-10-01 +work")?;
+
+        assert_eq!(
+            TaskCounts::new(&todo, today),
+            TaskCounts {
+                pending: 5,
+                done: 1,
+                overdue: 1,
+                due_today: 2,
+            }
+        );
+
+        todo.toggle_filter(ToDoCategory::Projects, "work", FilterState::Select);
+        assert_eq!(
+            TaskCounts::new(&todo, today),
+            TaskCounts {
+                pending: 3,
+                done: 1,
+                overdue: 1,
+                due_today: 1,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn due_this_week() -> Result<(), todo_txt::Error> {
+        use std::str::FromStr;
+        // Sunday, the last day of a Monday week and the first of a Sunday week.
+        let today = NaiveDate::from_ymd_opt(2024, 5, 19).unwrap();
+        let monday = Task::from_str("monday due:2024-05-13")?;
+        let sunday = Task::from_str("sunday due:2024-05-19")?;
+        let next_monday = Task::from_str("next monday due:2024-05-20")?;
+        let next_saturday = Task::from_str("next saturday due:2024-05-25")?;
+
+        assert!(is_due_this_week(&monday, today, Weekday::Mon));
+        assert!(is_due_this_week(&sunday, today, Weekday::Mon));
+        assert!(!is_due_this_week(&next_monday, today, Weekday::Mon));
+
+        assert!(!is_due_this_week(&monday, today, Weekday::Sun));
+        assert!(is_due_this_week(&sunday, today, Weekday::Sun));
+        assert!(is_due_this_week(&next_monday, today, Weekday::Sun));
+        assert!(is_due_this_week(&next_saturday, today, Weekday::Sun));
+
+        let done = Task::from_str("x done due:2024-05-19")?;
+        assert!(!is_due_this_week(&done, today, Weekday::Mon));
+        let no_due = Task::from_str("no due")?;
+        assert!(!is_due_this_week(&no_due, today, Weekday::Mon));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,396 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    io::{ErrorKind, Result as ioResult, Write},
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    str::FromStr,
+    sync::{
+        mpsc::{self, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+use todo_txt::Task;
+
+/// Paths to scripts that are executed on task and file events.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct HookPaths {
+    pub pre_new: Option<PathBuf>,
+    pub post_new: Option<PathBuf>,
+    pub pre_update: Option<PathBuf>,
+    pub post_update: Option<PathBuf>,
+    pub post_move: Option<PathBuf>,
+    pub post_remove: Option<PathBuf>,
+    pub pre_save: Option<PathBuf>,
+    pub post_save: Option<PathBuf>,
+    pub pre_load: Option<PathBuf>,
+    pub post_load: Option<PathBuf>,
+}
+
+impl HookPaths {
+    /// Gets only the hooks of task events.
+    pub fn task_hooks(&self) -> Self {
+        Self {
+            pre_new: self.pre_new.clone(),
+            post_new: self.post_new.clone(),
+            pre_update: self.pre_update.clone(),
+            post_update: self.post_update.clone(),
+            post_move: self.post_move.clone(),
+            post_remove: self.post_remove.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Gets only the hooks of file events.
+    pub fn file_hooks(&self) -> Self {
+        Self {
+            pre_save: self.pre_save.clone(),
+            post_save: self.post_save.clone(),
+            pre_load: self.pre_load.clone(),
+            post_load: self.post_load.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Format of the task passed to hook scripts.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, ValueEnum)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum HookFormat {
+    /// Task string is passed only as the first argument.
+    #[default]
+    Text,
+    /// Task string is passed as the first argument and the task
+    /// as a JSON object to the standard input.
+    Json,
+}
+
+/// Events on which hooks are executed.
+#[derive(Clone, Copy, Debug)]
+pub enum HookEvent {
+    PreNew,
+    PostNew,
+    PreUpdate,
+    PostUpdate,
+    PostMove,
+    PostRemove,
+    PreSave,
+    PostSave,
+    PreLoad,
+    PostLoad,
+}
+
+impl HookEvent {
+    /// Pre hooks can rewrite the task by its standard output.
+    pub fn is_pre(&self) -> bool {
+        matches!(self, HookEvent::PreNew | HookEvent::PreUpdate)
+    }
+
+    /// File hooks get the path of the file instead of the task.
+    pub fn is_file(&self) -> bool {
+        use HookEvent::*;
+        matches!(self, PreSave | PostSave | PreLoad | PostLoad)
+    }
+
+    fn path<'a>(&self, paths: &'a HookPaths) -> Option<&'a PathBuf> {
+        use HookEvent::*;
+        match self {
+            PreNew => paths.pre_new.as_ref(),
+            PostNew => paths.post_new.as_ref(),
+            PreUpdate => paths.pre_update.as_ref(),
+            PostUpdate => paths.post_update.as_ref(),
+            PostMove => paths.post_move.as_ref(),
+            PostRemove => paths.post_remove.as_ref(),
+            PreSave => paths.pre_save.as_ref(),
+            PostSave => paths.post_save.as_ref(),
+            PreLoad => paths.pre_load.as_ref(),
+            PostLoad => paths.post_load.as_ref(),
+        }
+    }
+}
+
+/// Executes hook scripts for task and file events.
+///
+/// Pre hooks are executed synchronously because their output can rewrite the task.
+/// Post hooks are executed on a background thread so slow scripts do not block the UI.
+/// Post hooks are executed one by one in the order in which the events occurred,
+/// always after the change of the task has been applied.
+/// File hooks are executed synchronously, they are run from the file worker thread.
+/// Dropping the hooks waits until all queued post hooks are executed.
+#[derive(Default)]
+pub struct Hooks {
+    executor: Arc<HookExecutor>,
+    post_tx: Option<Sender<(HookEvent, String)>>,
+    post_thread: Option<JoinHandle<()>>,
+}
+
+impl Hooks {
+    pub fn new(paths: HookPaths, format: HookFormat) -> Self {
+        let has_post_hooks = paths.post_new.is_some()
+            || paths.post_update.is_some()
+            || paths.post_move.is_some()
+            || paths.post_remove.is_some();
+        let executor = Arc::new(HookExecutor { paths, format });
+        let (post_tx, post_thread) = if has_post_hooks {
+            let (tx, rx) = mpsc::channel::<(HookEvent, String)>();
+            let executor = executor.clone();
+            let handle = thread::spawn(move || {
+                // Ends when the hooks are dropped.
+                for (event, task) in rx {
+                    executor.run(event, &task);
+                }
+            });
+            (Some(tx), Some(handle))
+        } else {
+            (None, None)
+        };
+        Self {
+            executor,
+            post_tx,
+            post_thread,
+        }
+    }
+
+    /// Runs the hook script for the event if it is set. Failures are only logged.
+    /// Post hooks are only queued to the background thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event that occurred.
+    /// * `task` - The task string the event occurred on.
+    ///
+    /// # Returns
+    ///
+    /// The new task string for pre hooks that printed anything to the standard output,
+    /// `None` otherwise.
+    pub fn run(&self, event: HookEvent, task: &str) -> Option<String> {
+        if event.is_pre() || event.is_file() {
+            return self.executor.run(event, task);
+        }
+        event.path(&self.executor.paths)?;
+        if let Some(tx) = &self.post_tx {
+            if let Err(e) = tx.send((event, task.to_string())) {
+                log::error!("Cannot queue hook {:?}: {}", event, e);
+            }
+        }
+        None
+    }
+}
+
+impl Drop for Hooks {
+    fn drop(&mut self) {
+        // Closing the channel ends the thread after the queued hooks are executed.
+        self.post_tx.take();
+        if let Some(handle) = self.post_thread.take() {
+            if handle.join().is_err() {
+                log::error!("Post hooks thread panicked");
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct HookExecutor {
+    paths: HookPaths,
+    format: HookFormat,
+}
+
+impl HookExecutor {
+    fn run(&self, event: HookEvent, task: &str) -> Option<String> {
+        let path = event.path(&self.paths)?;
+        let output = match self.execute(event, path, task) {
+            Ok(output) => output,
+            Err(e) => {
+                log::error!("Hook {:?} ({}) failed: {}", event, path.display(), e);
+                return None;
+            }
+        };
+        if !output.status.success() {
+            log::error!(
+                "Hook {:?} ({}) exited with {}",
+                event,
+                path.display(),
+                output.status
+            );
+            return None;
+        }
+        if !event.is_pre() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = stdout.trim();
+        if stdout.is_empty() {
+            None
+        } else {
+            Some(stdout.to_string())
+        }
+    }
+
+    fn execute(&self, event: HookEvent, path: &Path, task: &str) -> ioResult<Output> {
+        let mut command = Command::new(path);
+        command
+            .arg(task)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        match self.format {
+            HookFormat::Json if !event.is_file() => {
+                let mut child = command.stdin(Stdio::piped()).spawn()?;
+                // Task::from_str never fails, parse errors are part of the subject.
+                let task = Task::from_str(task).unwrap_or_default();
+                if let Some(mut stdin) = child.stdin.take() {
+                    // Hooks are not required to read the stdin.
+                    match stdin.write_all(task_to_json(&task).to_string().as_bytes()) {
+                        Err(e) if e.kind() != ErrorKind::BrokenPipe => return Err(e),
+                        _ => {}
+                    }
+                }
+                child.wait_with_output()
+            }
+            _ => {
+                command.stdin(Stdio::null());
+                command.output()
+            }
+        }
+    }
+}
+
+/// Converts the task to the JSON object passed to hooks.
+pub fn task_to_json(task: &Task) -> Value {
+    let date = |date: &Option<todo_txt::Date>| date.map(|d| d.to_string());
+    json!({
+        "subject": task.subject,
+        "priority": if task.priority.is_lowest() {
+            None
+        } else {
+            Some(char::from(task.priority.clone()).to_string())
+        },
+        "finished": task.finished,
+        "create_date": date(&task.create_date),
+        "finish_date": date(&task.finish_date),
+        "due_date": date(&task.due_date),
+        "threshold_date": date(&task.threshold_date),
+        "projects": task.projects(),
+        "contexts": task.contexts(),
+        "hashtags": task.hashtags,
+        "tags": task.tags,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_utils::TempDir;
+    use std::fs;
+
+    #[test]
+    fn json_on_stdin() -> ioResult<()> {
+        let dir = TempDir::new("hooks");
+        let hooks = Hooks::new(
+            HookPaths {
+                pre_new: Some(dir.script("cat.sh", "#!/bin/sh\ncat\n")?),
+                ..Default::default()
+            },
+            HookFormat::Json,
+        );
+        let task = "(A) 2023-06-01 task +project @context #tag due:2023-07-01 key:value";
+        let output = hooks.run(HookEvent::PreNew, task).unwrap();
+        let json: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json["subject"], "task +project @context #tag");
+        assert_eq!(json["priority"], "A");
+        assert_eq!(json["create_date"], "2023-06-01");
+        assert_eq!(json["due_date"], "2023-07-01");
+        assert_eq!(json["finish_date"], Value::Null);
+        assert_eq!(json["projects"], json!(["project"]));
+        assert_eq!(json["contexts"], json!(["context"]));
+        assert_eq!(json["hashtags"], json!(["tag"]));
+        assert_eq!(json["tags"]["key"], "value");
+
+        Ok(())
+    }
+
+    #[test]
+    fn json_not_read() -> ioResult<()> {
+        let dir = TempDir::new("hooks");
+        let hooks = Hooks::new(
+            HookPaths {
+                pre_new: Some(dir.script("ignore.sh", "#!/bin/sh\necho hooked\n")?),
+                ..Default::default()
+            },
+            HookFormat::Json,
+        );
+        // The JSON does not fit into the pipe buffer, so writing it fails
+        // when the hook exits without reading it.
+        let task = "x".repeat(100_000);
+        assert_eq!(
+            hooks.run(HookEvent::PreNew, &task),
+            Some(String::from("hooked"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn post_hooks_in_order() -> ioResult<()> {
+        let dir = TempDir::new("hooks");
+        let release = dir.join("release");
+        let log = dir.join("post.log");
+        fs::write(&log, "")?;
+        // Hooks wait until the test releases them, so hooks executed on
+        // the calling thread would write the log before `run` returns.
+        let hooks = Hooks::new(
+            HookPaths {
+                post_new: Some(dir.script(
+                    "post.sh",
+                    &format!(
+                        "#!/bin/sh\ni=0\nwhile [ ! -f {} ] && [ $i -lt 500 ]; do sleep 0.01; i=$((i+1)); done\necho \"$1\" >> {}\n",
+                        release.display(),
+                        log.display()
+                    ),
+                )?),
+                ..Default::default()
+            },
+            HookFormat::Text,
+        );
+        assert_eq!(hooks.run(HookEvent::PostNew, "first"), None);
+        assert_eq!(hooks.run(HookEvent::PostNew, "second"), None);
+        assert_eq!(fs::read_to_string(&log)?, "");
+
+        fs::write(&release, "")?;
+        // Drop waits for the queued hooks.
+        drop(hooks);
+        assert_eq!(fs::read_to_string(&log)?, "first\nsecond\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_argument() -> ioResult<()> {
+        let dir = TempDir::new("hooks");
+        let hook = dir.script("append.sh", "#!/bin/sh\nread input\necho \"$1 +hooked\"\n")?;
+        let paths = HookPaths {
+            pre_update: Some(hook.clone()),
+            post_update: Some(hook),
+            ..Default::default()
+        };
+
+        // Stdin is not provided in text format so read fails and only argument is used.
+        let hooks = Hooks::new(paths.clone(), HookFormat::Text);
+        assert_eq!(
+            hooks.run(HookEvent::PreUpdate, "task"),
+            Some(String::from("task +hooked"))
+        );
+        assert_eq!(hooks.run(HookEvent::PostUpdate, "task"), None);
+        assert_eq!(hooks.run(HookEvent::PreNew, "task"), None);
+
+        let hooks = Hooks::new(paths, HookFormat::Json);
+        assert_eq!(
+            hooks.run(HookEvent::PreUpdate, "task"),
+            Some(String::from("task +hooked"))
+        );
+
+        Ok(())
+    }
+}
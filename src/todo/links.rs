@@ -0,0 +1,100 @@
+use std::io;
+use std::process::{Command, Stdio};
+use todo_txt::Task;
+
+/// Placeholder in the issue URL template that is replaced by the issue number.
+const ISSUE_PLACEHOLDER: &str = "{n}";
+
+/// Tag that holds the link of the task.
+const LINK_TAG: &str = "link";
+
+/// Resolves a hashtag that references an issue to its URL.
+///
+/// # Arguments
+///
+/// * `template` - URL template, `{n}` is replaced by the issue number.
+/// * `hashtag` - Hashtag without the leading `#`.
+///
+/// # Returns
+///
+/// The URL of the issue or `None` if the hashtag is not an issue number.
+pub fn issue_url(template: &str, hashtag: &str) -> Option<String> {
+    if hashtag.is_empty() || !hashtag.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(template.replace(ISSUE_PLACEHOLDER, hashtag))
+}
+
+/// Extracts the link of the task.
+///
+/// The value of the `link:` tag is preferred, otherwise the first
+/// `http://` or `https://` token in the subject is used.
+///
+/// # Returns
+///
+/// The URL or `None` if the task contains no link.
+pub fn task_link(task: &Task) -> Option<String> {
+    if let Some(link) = task.tags.get(LINK_TAG) {
+        return Some(link.clone());
+    }
+    task.subject
+        .split_whitespace()
+        .find(|word| word.starts_with("http://") || word.starts_with("https://"))
+        .map(String::from)
+}
+
+/// Opens the URL by the opener of the operating system.
+pub fn open_link(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn resolve_issue_url() {
+        const TEMPLATE: &str = "https://github.com/org/repo/issues/{n}";
+        assert_eq!(
+            issue_url(TEMPLATE, "123"),
+            Some(String::from("https://github.com/org/repo/issues/123"))
+        );
+        assert_eq!(issue_url(TEMPLATE, "hashtag"), None);
+        assert_eq!(issue_url(TEMPLATE, "12a"), None);
+        assert_eq!(issue_url(TEMPLATE, ""), None);
+    }
+
+    #[test]
+    fn extract_task_link() {
+        let task = Task::from_str("Read docs https://example.com/docs later").unwrap();
+        assert_eq!(
+            task_link(&task),
+            Some(String::from("https://example.com/docs"))
+        );
+
+        let task = Task::from_str("Read http://a.org and https://b.org").unwrap();
+        assert_eq!(task_link(&task), Some(String::from("http://a.org")));
+
+        let task = Task::from_str("Read https://b.org link:https://a.org").unwrap();
+        assert_eq!(task_link(&task), Some(String::from("https://a.org")));
+
+        let task = Task::from_str("Task without link +project").unwrap();
+        assert_eq!(task_link(&task), None);
+    }
+}
@@ -10,11 +10,18 @@ use parts::Parts;
 use std::iter::Peekable;
 use tui::style::Style;
 
+/// Format string parsed to lines. The format is parsed only once when
+/// the parser is created, filling does not parse the format again.
 pub struct Parser {
     lines: Vec<Line>,
     styles: Styles,
 }
 
+#[cfg(test)]
+thread_local! {
+    static PARSE_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 impl Parser {
     pub fn new(value: &str, styles: Styles) -> ToDoRes<Self> {
         let lines = Parser::parse(value, &styles)?;
@@ -22,6 +29,9 @@ impl Parser {
         Ok(Parser { lines, styles })
     }
 
+    /// Reads the block until the delimiter. Escape sequences are kept
+    /// in the block, they are resolved when the variables are parsed,
+    /// so escaped `$` is not taken as a variable.
     fn read_block(iter: &mut Peekable<std::str::Chars<'_>>, delimiter: char) -> ToDoRes<String> {
         let mut read = String::default();
         loop {
@@ -30,10 +40,13 @@ impl Parser {
                 None => return Err(ToDoError::ParseBlockNotClosed(read.to_string())),
             };
             match c {
-                '\\' => read.push(match iter.next() {
-                    Some(ch) => ch,
-                    None => return Err(ToDoError::ParseBlockEscapeOnEnd(read + "\\")),
-                }),
+                '\\' => {
+                    read.push(c);
+                    read.push(match iter.next() {
+                        Some(ch) => ch,
+                        None => return Err(ToDoError::ParseBlockEscapeOnEnd(read)),
+                    });
+                }
                 c if c == delimiter => break,
                 _ => read.push(c),
             };
@@ -42,6 +55,8 @@ impl Parser {
     }
 
     fn parse(template: &str, styles: &Styles) -> ToDoRes<Vec<Line>> {
+        #[cfg(test)]
+        PARSE_COUNT.with(|count| count.set(count.get() + 1));
         let mut ret = Vec::new();
         let mut line = Line::default();
         let mut act = String::default();
@@ -57,12 +72,15 @@ impl Parser {
                         iter.next();
                         style = Some(Parser::read_block(&mut iter, ')')?);
                     }
-                    line.add_span_styled(&block, style, styles)?;
+                    line.add_block_styled(&block, style, styles)?;
+                }
+                '\\' => {
+                    act.push(c);
+                    act.push(match iter.next() {
+                        Some(ch) => ch,
+                        None => return Err(ToDoError::ParseBlockEscapeOnEnd(act)),
+                    });
                 }
-                '\\' => act.push(match iter.next() {
-                    Some(ch) => ch,
-                    None => return Err(ToDoError::ParseBlockEscapeOnEnd(act + "\\")),
-                }),
                 '\n' => {
                     line.add_span_styled(&act, None, styles)?;
                     act = String::default();
@@ -89,6 +107,9 @@ impl Parser {
 mod tests {
     use super::Line;
     use super::*;
+    use crate::config::TextStyle;
+    use std::str::FromStr;
+    use todo_txt::Task;
     use tui::style::Color;
     use tui::style::Modifier;
 
@@ -111,7 +132,7 @@ mod tests {
             .peekable();
         assert_eq!(
             &Parser::read_block(&mut iter, ']')?,
-            "block to parse ] with some \\ escapes"
+            "block to parse \\] with some \\\\ escapes"
         );
         assert_eq!(&iter.collect::<String>(), "");
 
@@ -146,6 +167,7 @@ mod tests {
             Line(vec![LineBlock {
                 parts: vec![Parts::Text("some text".to_string())],
                 style: styles.get_style_default(),
+                alternative: None,
             }])
         );
         assert_eq!(
@@ -153,6 +175,7 @@ mod tests {
             Line(vec![LineBlock {
                 parts: vec![Parts::Text("some text [ with escapes ]".to_string())],
                 style: styles.get_style_default(),
+                alternative: None,
             }])
         );
         assert_eq!(
@@ -160,6 +183,7 @@ mod tests {
             Line(vec![LineBlock {
                 parts: vec![Parts::Text("some text".to_string())],
                 style: styles.get_style_from_style(Style::default().fg(Color::Red)),
+                alternative: None,
             }])
         );
         assert_eq!(
@@ -168,10 +192,12 @@ mod tests {
                 LineBlock {
                     parts: vec![Parts::Text("some text".to_string())],
                     style: styles.get_style_default(),
+                    alternative: None,
                 },
                 LineBlock {
                     parts: vec![Parts::Text(" and another text".to_string())],
                     style: styles.get_style_default(),
+                    alternative: None,
                 }
             ])
         );
@@ -181,10 +207,12 @@ mod tests {
                 LineBlock {
                     parts: vec![Parts::Text("some text".to_string())],
                     style: styles.get_style_default(),
+                    alternative: None,
                 },
                 LineBlock {
                     parts: vec![Parts::Text("[ and escaped text ]".to_string())],
                     style: styles.get_style_default(),
+                    alternative: None,
                 }
             ])
         );
@@ -193,6 +221,7 @@ mod tests {
             Line(vec![LineBlock {
                 parts: vec![Parts::Text("some text".to_string())],
                 style: styles.get_style_default(),
+                alternative: None,
             }])
         );
         assert_eq!(
@@ -204,10 +233,12 @@ mod tests {
                 LineBlock {
                     parts: vec![Parts::Text("some text".to_string())],
                     style: styles.get_style_from_style(Style::default().fg(Color::Red)),
+                    alternative: None,
                 },
                 LineBlock {
                     parts: vec![Parts::Text(" text between ".to_string())],
                     style: styles.get_style_default(),
+                    alternative: None,
                 },
                 LineBlock {
                     parts: vec![Parts::Text("another text".to_string())],
@@ -216,6 +247,7 @@ mod tests {
                             .fg(Color::Blue)
                             .add_modifier(Modifier::BOLD)
                     ),
+                    alternative: None,
                 }
             ])
         );
@@ -224,8 +256,52 @@ mod tests {
             Line(vec![LineBlock {
                 parts: vec![Parts::Text("some text".to_string())],
                 style: styles.get_style_from_style(Style::default().fg(Color::Red)),
+                alternative: None,
             },])
         );
+        assert_eq!(
+            Parser::parse("costs \\$5 and \\\\", &Styles::default())?[0],
+            Line(vec![LineBlock {
+                parts: vec![Parts::Text("costs $5 and \\".to_string())],
+                style: styles.get_style_default(),
+                alternative: None,
+            }])
+        );
+        assert_eq!(
+            Parser::parse("[\\$subject \\] text](red)", &Styles::default())?[0],
+            Line(vec![LineBlock {
+                parts: vec![Parts::Text("$subject ] text".to_string())],
+                style: styles.get_style_from_style(Style::default().fg(Color::Red)),
+                alternative: None,
+            }])
+        );
+        assert_eq!(
+            Parser::parse("\\[$subject\\] (100%)", &Styles::default())?[0],
+            Line(vec![LineBlock {
+                parts: vec![
+                    Parts::Text("[".to_string()),
+                    Parts::Subject,
+                    Parts::Text("] (100%)".to_string())
+                ],
+                style: styles.get_style_default(),
+                alternative: None,
+            }])
+        );
+        assert_eq!(
+            Parser::parse("Priority: [$priority|none](red)", &Styles::default())?[0],
+            Line(vec![
+                LineBlock {
+                    parts: vec![Parts::Text("Priority: ".to_string())],
+                    style: styles.get_style_default(),
+                    alternative: None,
+                },
+                LineBlock {
+                    parts: vec![Parts::Text("".to_string()), Parts::Priority],
+                    style: styles.get_style_from_style(Style::default().fg(Color::Red)),
+                    alternative: Some(vec![Parts::Text("none".to_string())]),
+                }
+            ])
+        );
         let parse = Parser::parse("some text\nnew line", &Styles::default())?;
         assert_eq!(parse.len(), 2);
         assert_eq!(
@@ -233,6 +309,7 @@ mod tests {
             Line(vec![LineBlock {
                 parts: vec![Parts::Text("some text".to_string())],
                 style: styles.get_style_default(),
+                alternative: None,
             }])
         );
         assert_eq!(
@@ -240,6 +317,7 @@ mod tests {
             Line(vec![LineBlock {
                 parts: vec![Parts::Text("new line".to_string())],
                 style: styles.get_style_default(),
+                alternative: None,
             }])
         );
 
@@ -274,6 +352,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_only_once() -> ToDoRes<()> {
+        let before = PARSE_COUNT.with(|count| count.get());
+        let parser = Parser::new(
+            "[$subject](red) [$due_date|no due] $done",
+            Styles::default(),
+        )?;
+        let mut todo = ToDo::default();
+        todo.new_task("task").unwrap();
+        todo.set_active(ToDoData::Pending, 0);
+        for _ in 0..100 {
+            parser.fill(&todo);
+        }
+        assert_eq!(PARSE_COUNT.with(|count| count.get()), before + 1);
+
+        Ok(())
+    }
+
     #[test]
     fn fill_counts() -> ToDoRes<()> {
         let parser = Parser::new("Done: $done Pending: $pending", Styles::default())?;
@@ -289,4 +385,119 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn fill_checkbox() -> ToDoRes<()> {
+        let parser = Parser::new("[$checkbox](green) $subject", Styles::default())?;
+        let mut todo = ToDo::default();
+        todo.new_task("task").unwrap();
+        todo.new_task("x done task").unwrap();
+
+        let green = Style::default().fg(Color::Green);
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(
+            parser.fill(&todo),
+            vec![vec![
+                (String::from("[ ]"), green),
+                (String::from(" task"), Style::default())
+            ]]
+        );
+        todo.set_active(ToDoData::Done, 0);
+        assert_eq!(parser.fill(&todo)[0][0], (String::from("[x]"), green));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fill_age() -> ToDoRes<()> {
+        let parser = Parser::new("Task[ is $age old]", Styles::default())?;
+        let mut todo = ToDo::default();
+        let created = crate::todo::get_actual_date() - chrono::Duration::days(3);
+        todo.new_task(&format!("{} task", created)).unwrap();
+        todo.add_task(Task::from_str("task").unwrap());
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(
+            parser.fill(&todo),
+            vec![vec![
+                (String::from("Task"), Style::default()),
+                (String::from(" is 3d old"), Style::default())
+            ]]
+        );
+
+        // Block without create date collapses.
+        todo.set_active(ToDoData::Pending, 1);
+        assert_eq!(
+            parser.fill(&todo),
+            vec![vec![(String::from("Task"), Style::default())]]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fill_finish_relative() -> ToDoRes<()> {
+        let parser = Parser::new("Task[ done $finish_relative]", Styles::default())?;
+        let mut todo = ToDo::default();
+        let finished = crate::todo::get_actual_date() - chrono::Duration::days(2);
+        todo.add_task(Task::from_str(&format!("x {finished} {finished} task")).unwrap());
+        todo.add_task(Task::from_str("x task").unwrap());
+
+        todo.set_active(ToDoData::Done, 0);
+        assert_eq!(
+            parser.fill(&todo),
+            vec![vec![
+                (String::from("Task"), Style::default()),
+                (String::from(" done 2 days ago"), Style::default())
+            ]]
+        );
+
+        // Block without finish date collapses.
+        todo.set_active(ToDoData::Done, 1);
+        assert_eq!(
+            parser.fill(&todo),
+            vec![vec![(String::from("Task"), Style::default())]]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fill_progress() -> ToDoRes<()> {
+        let styles = Styles {
+            progress_styles: vec![
+                (0, TextStyle::default().fg(Color::Red)),
+                (50, TextStyle::default().fg(Color::Green)),
+            ],
+            ..Default::default()
+        };
+        let parser = Parser::new("Task[ $progress%](progress)", styles)?;
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str("task pct:40").unwrap());
+        todo.add_task(Task::from_str("task pct:60").unwrap());
+        todo.add_task(Task::from_str("task").unwrap());
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(
+            parser.fill(&todo),
+            vec![vec![
+                (String::from("Task"), Style::default()),
+                (String::from(" 40%"), Style::default().fg(Color::Red))
+            ]]
+        );
+        todo.set_active(ToDoData::Pending, 1);
+        assert_eq!(
+            parser.fill(&todo)[0][1],
+            (String::from(" 60%"), Style::default().fg(Color::Green))
+        );
+
+        // Block without progress collapses.
+        todo.set_active(ToDoData::Pending, 2);
+        assert_eq!(
+            parser.fill(&todo),
+            vec![vec![(String::from("Task"), Style::default())]]
+        );
+
+        Ok(())
+    }
 }
@@ -22,6 +22,19 @@ impl Line {
         Ok(())
     }
 
+    pub fn add_block_styled(
+        &mut self,
+        block: &str,
+        style: Option<String>,
+        styles: &Styles,
+    ) -> ToDoRes<()> {
+        if !block.is_empty() {
+            self.0
+                .push(LineBlock::try_from_block(block, style, styles)?);
+        }
+        Ok(())
+    }
+
     pub fn fill(&self, todo: &ToDo, styles: &Styles) -> Option<Vec<(String, Style)>> {
         if self.0.is_empty() {
             return None;
@@ -9,9 +9,17 @@ use tui::style::Style;
 pub struct LineBlock {
     pub parts: Vec<Parts>,
     pub style: StylesValue,
+    /// Parts rendered instead of `parts` if any of their variables is empty.
+    pub alternative: Option<Vec<Parts>>,
 }
 
 impl LineBlock {
+    /// Checks if the character can be a part of a variable name without braces.
+    /// Names follow todo.txt tag keys, so they can contain hyphens and underscores.
+    fn is_variable_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '-' || c == '_'
+    }
+
     fn parse_variables(block: &str) -> ToDoRes<Vec<Parts>> {
         let mut ret = Vec::new();
         let mut iter = block.chars();
@@ -19,6 +27,11 @@ impl LineBlock {
         let mut variable_block = false;
         let mut read = String::new();
         while let Some(c) = iter.next() {
+            if read_variable && !variable_block && !Self::is_variable_char(c) {
+                read_variable = false;
+                ret.push(Parts::from(read));
+                read = String::new();
+            }
             match c {
                 '$' => {
                     read_variable = true;
@@ -39,11 +52,6 @@ impl LineBlock {
                     ret.push(Parts::from(read));
                     read = String::new();
                 }
-                c if read_variable && !variable_block && c.is_whitespace() => {
-                    read_variable = false;
-                    ret.push(Parts::from(read));
-                    read = String::from(c);
-                }
                 '\\' => read.push(match iter.next() {
                     Some(ch) => ch,
                     None => return Err(ToDoError::ParseBlockEscapeOnEnd(block.to_string())),
@@ -63,11 +71,19 @@ impl LineBlock {
         Ok(ret)
     }
 
-    pub fn fill(&self, todo: &ToDo, styles: &Styles) -> Option<(String, Style)> {
+    fn fill_parts(parts: &[Parts], todo: &ToDo) -> Option<String> {
         let mut ret = String::new();
-        for part in &self.parts {
-            ret += &part.fill(todo)?;
+        for part in parts {
+            part.fill_into(todo, &mut ret)?;
         }
+        Some(ret)
+    }
+
+    pub fn fill(&self, todo: &ToDo, styles: &Styles) -> Option<(String, Style)> {
+        let ret = match Self::fill_parts(&self.parts, todo) {
+            Some(ret) => ret,
+            None => Self::fill_parts(self.alternative.as_ref()?, todo)?,
+        };
         Some((
             ret,
             match todo.get_active() {
@@ -84,13 +100,34 @@ impl LineBlock {
                 Some(style) => styles.get_style(&style)?,
                 None => styles.get_style_default(),
             },
+            alternative: None,
         })
     }
+
+    /// Creates a block with alternative text. The alternative is separated
+    /// by the first not escaped `|` and it is rendered if any variable
+    /// of the block is empty.
+    pub fn try_from_block(value: &str, style: Option<String>, styles: &Styles) -> ToDoRes<Self> {
+        let mut escaped = false;
+        let separator = value.char_indices().find(|(_, c)| {
+            let found = !escaped && *c == '|';
+            escaped = !escaped && *c == '\\';
+            found
+        });
+        match separator {
+            Some((index, _)) => Ok(LineBlock {
+                alternative: Some(Self::parse_variables(&value[index + 1..])?),
+                ..Self::try_from_styled(&value[..index], style, styles)?
+            }),
+            None => Self::try_from_styled(value, style, styles),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::todo::ToDoData;
 
     #[test]
     fn parse_variables() -> ToDoRes<()> {
@@ -114,9 +151,33 @@ mod tests {
         assert_eq!(parts[0], Parts::Text("special task text ".into()));
         assert_eq!(parts[1], Parts::Special("some-special".into()));
 
+        let parts = LineBlock::parse_variables("$energy_level and ($priority), $Subject")?;
+        assert_eq!(parts[0], Parts::Text("".into()));
+        assert_eq!(parts[1], Parts::Special("energy_level".into()));
+        assert_eq!(parts[2], Parts::Text(" and (".into()));
+        assert_eq!(parts[3], Parts::Priority);
+        assert_eq!(parts[4], Parts::Text("), ".into()));
+        assert_eq!(parts[5], Parts::Subject);
+
+        let parts = LineBlock::parse_variables("$subject-text$done")?;
+        assert_eq!(parts[1], Parts::Special("subject-text".into()));
+        assert_eq!(parts[2], Parts::Text("".into()));
+        assert_eq!(parts[3], Parts::Done);
+
         let parts = LineBlock::parse_variables("special \\$ character")?;
         assert_eq!(parts[0], Parts::Text("special $ character".into()));
 
+        let parts = LineBlock::parse_variables("$subject\\] \\[x\\]")?;
+        assert_eq!(parts[0], Parts::Text("".into()));
+        assert_eq!(parts[1], Parts::Subject);
+        assert_eq!(parts[2], Parts::Text("] [x]".into()));
+
+        let parts = LineBlock::parse_variables("[${subject:30}] $subject:30")?;
+        assert_eq!(parts[1], Parts::SubjectWidth(30));
+        assert_eq!(parts[2], Parts::Text("] ".into()));
+        assert_eq!(parts[3], Parts::Subject);
+        assert_eq!(parts[4], Parts::Text(":30".into()));
+
         let parts = LineBlock::parse_variables("Pending: $pending Done: $done")?;
         assert_eq!(parts[0], Parts::Text("Pending: ".into()));
         assert_eq!(parts[1], Parts::Pending);
@@ -126,6 +187,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn alternative() -> ToDoRes<()> {
+        let styles = Styles::default();
+        let block = LineBlock::try_from_block("Priority: $priority|none", None, &styles)?;
+        assert_eq!(block.parts[0], Parts::Text("Priority: ".into()));
+        assert_eq!(block.parts[1], Parts::Priority);
+        assert_eq!(block.alternative, Some(vec![Parts::Text("none".into())]));
+
+        let block = LineBlock::try_from_block("a \\| b", None, &styles)?;
+        assert_eq!(block.parts, vec![Parts::Text("a | b".into())]);
+        assert_eq!(block.alternative, None);
+
+        let mut todo = ToDo::default();
+        todo.new_task("(A) task due:2023-07-01").unwrap();
+        todo.new_task("task").unwrap();
+        let block = LineBlock::try_from_block("due $due_date|no due date", None, &styles)?;
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(
+            block.fill(&todo, &styles),
+            Some((String::from("due 2023-07-01"), Style::default()))
+        );
+
+        todo.set_active(ToDoData::Pending, 1);
+        assert_eq!(
+            block.fill(&todo, &styles),
+            Some((String::from("no due date"), Style::default()))
+        );
+
+        let block = LineBlock::try_from_block("$due_date|$priority", None, &styles)?;
+        assert_eq!(block.fill(&todo, &styles), None);
+
+        Ok(())
+    }
+
     #[test]
     fn parse_variables_error() {
         assert_eq!(
@@ -1,5 +1,11 @@
 use super::ToDo;
 use super::ToDoData;
+use crate::todo::{get_actual_date, task_list::get_progress};
+use chrono::NaiveDate;
+use std::fmt::Write;
+
+/// Number of characters inside the progress bar.
+const PROGRESS_BAR_WIDTH: usize = 10;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Parts {
@@ -7,59 +13,150 @@ pub enum Parts {
     Pending,
     Done,
     Subject,
+    /// Subject truncated to the number of characters.
+    SubjectWidth(usize),
     Priority,
     CreateDate,
     FinishDate,
+    /// Finish date relative to today, e.g. `2 days ago`.
+    FinishRelative,
     Finished,
     TresholdDate,
     DueDate,
+    Age,
     Contexts,
     Projects,
     Hashtags,
+    IssueUrls,
+    /// Percentage of the task completion from the `pct:` tag.
+    Progress,
+    /// Bar drawn by the percentage of the task completion.
+    ProgressBar,
+    /// `[x]` for finished tasks, `[ ]` otherwise.
+    Checkbox,
+    /// Symbol of the priority from the `priority_symbols` option.
+    PrioritySymbol,
     Special(String),
 }
 
 impl Parts {
+    #[cfg(test)]
     pub fn fill(&self, todo: &ToDo) -> Option<String> {
+        let mut ret = String::new();
+        self.fill_into(todo, &mut ret)?;
+        Some(ret)
+    }
+
+    /// Appends the text truncated to the number of characters to the output.
+    /// Truncated text ends with an ellipsis, that is counted to the width.
+    fn truncate_into(text: &str, width: usize, out: &mut String) {
+        match text.char_indices().nth(width) {
+            None => out.push_str(text),
+            Some(_) if width == 0 => {}
+            Some(_) => {
+                let end = text.char_indices().nth(width - 1).map_or(0, |(i, _)| i);
+                out.push_str(&text[..end]);
+                out.push('…');
+            }
+        }
+    }
+
+    /// Appends the date relative to today to the output.
+    fn relative_date_into(date: NaiveDate, out: &mut String) -> Option<()> {
+        match get_actual_date().signed_duration_since(date).num_days() {
+            0 => out.push_str("today"),
+            1 => out.push_str("yesterday"),
+            -1 => out.push_str("tomorrow"),
+            days if days > 0 => write!(out, "{days} days ago").ok()?,
+            days => write!(out, "in {} days", -days).ok()?,
+        }
+        Some(())
+    }
+
+    /// Appends the bar filled by the percentage to the output.
+    fn progress_bar_into(progress: u8, out: &mut String) {
+        let filled = (usize::from(progress) * PROGRESS_BAR_WIDTH + 50) / 100;
+        out.push('[');
+        out.extend(std::iter::repeat_n('#', filled));
+        out.extend(std::iter::repeat_n('-', PROGRESS_BAR_WIDTH - filled));
+        out.push(']');
+    }
+
+    /// Appends the value of the part to the output. This is used while rendering,
+    /// so values are written directly to the output without temporary strings.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the value is empty, the output can be partially written in that case.
+    pub fn fill_into(&self, todo: &ToDo, out: &mut String) -> Option<()> {
         use Parts::*;
-        let process_vec = |vec: &[String]| {
+        let process_vec = |vec: &[String], out: &mut String| {
             if vec.is_empty() {
-                None
-            } else {
-                Some(vec.join(", "))
+                return None;
+            }
+            for (i, item) in vec.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                out.push_str(item);
             }
+            Some(())
         };
-        match todo.get_active() {
-            Some(task) => match self {
-                Text(text) => Some(text.to_string()),
-                Pending => Some(todo.len(ToDoData::Pending).to_string()),
-                Done => Some(todo.len(ToDoData::Done).to_string()),
-                Subject => Some(task.subject.clone()),
-                Priority => {
-                    if task.priority.is_lowest() {
-                        None
-                    } else {
-                        Some(task.priority.to_string())
-                    }
+        let task = todo.get_active()?;
+        let date_format = &todo.config.date_format;
+        match self {
+            Text(text) => out.push_str(text),
+            Pending => write!(out, "{}", todo.len(ToDoData::Pending)).ok()?,
+            Done => write!(out, "{}", todo.len(ToDoData::Done)).ok()?,
+            Subject => out.push_str(&task.subject),
+            SubjectWidth(width) => Self::truncate_into(&task.subject, *width, out),
+            Priority => {
+                if task.priority.is_lowest() {
+                    return None;
                 }
-                CreateDate => task.create_date.map(|d| d.to_string()),
-                FinishDate => task.finish_date.map(|d| d.to_string()),
-                Finished => Some(task.finished.to_string()),
-                TresholdDate => task.threshold_date.map(|d| d.to_string()),
-                DueDate => task.due_date.map(|d| d.to_string()),
-                Contexts => process_vec(task.contexts()),
-                Projects => process_vec(task.projects()),
-                Hashtags => process_vec(&task.hashtags),
-                Special(special) => task.tags.get(special).cloned(),
-            },
-            None => None,
+                write!(out, "{}", task.priority).ok()?
+            }
+            CreateDate => write!(out, "{}", date_format.format(&task.create_date?)).ok()?,
+            FinishDate => write!(out, "{}", date_format.format(&task.finish_date?)).ok()?,
+            FinishRelative => Self::relative_date_into(task.finish_date?, out)?,
+            Finished => write!(out, "{}", task.finished).ok()?,
+            TresholdDate => write!(out, "{}", date_format.format(&task.threshold_date?)).ok()?,
+            DueDate => write!(out, "{}", date_format.format(&task.due_date?)).ok()?,
+            Age => {
+                let age = get_actual_date().signed_duration_since(task.create_date?);
+                write!(out, "{}d", age.num_days()).ok()?
+            }
+            Contexts => process_vec(task.contexts(), out)?,
+            Projects => process_vec(task.projects(), out)?,
+            Hashtags => process_vec(&task.hashtags, out)?,
+            IssueUrls => process_vec(&todo.get_issue_urls(task), out)?,
+            Progress => write!(out, "{}", get_progress(task)?).ok()?,
+            ProgressBar => Self::progress_bar_into(get_progress(task)?, out),
+            Checkbox => out.push_str(if task.finished { "[x]" } else { "[ ]" }),
+            PrioritySymbol => {
+                if task.priority.is_lowest() {
+                    return None;
+                }
+                out.push_str(
+                    todo.config
+                        .priority_symbols
+                        .get(&task.priority.to_string())?,
+                )
+            }
+            Special(special) => out.push_str(task.tags.get(special)?),
         }
+        Some(())
     }
 }
 
 impl From<String> for Parts {
     fn from(value: String) -> Self {
         use Parts::*;
+        if let Some((name, width)) = value.split_once(':') {
+            if let (true, Ok(width)) = (name.eq_ignore_ascii_case("subject"), width.parse()) {
+                return SubjectWidth(width);
+            }
+        }
         match value.to_lowercase().as_str() {
             "pending" => Pending,
             "done" => Done,
@@ -67,12 +164,19 @@ impl From<String> for Parts {
             "priority" => Priority,
             "create_date" => CreateDate,
             "finish_date" => FinishDate,
+            "finish_relative" => FinishRelative,
             "finished" => Finished,
             "treshold_date" => TresholdDate,
             "due_date" => DueDate,
+            "age" => Age,
             "contexts" => Contexts,
             "projects" => Projects,
             "hashtags" => Hashtags,
+            "issue_urls" => IssueUrls,
+            "progress" => Progress,
+            "progress_bar" => ProgressBar,
+            "checkbox" => Checkbox,
+            "priority_symbol" => PrioritySymbol,
             _ => Special(value),
         }
     }
@@ -81,7 +185,10 @@ impl From<String> for Parts {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{Config, DateFormat};
     use crate::error::ToDoRes;
+    use std::str::FromStr;
+    use todo_txt::Task;
 
     #[test]
     fn fill() -> ToDoRes<()> {
@@ -94,7 +201,8 @@ mod tests {
         todo.new_task("task @context").unwrap();
         todo.new_task("task +project").unwrap();
         todo.new_task("task #hashtag").unwrap();
-        todo.new_task("task spec:some-text").unwrap();
+        todo.new_task("task spec:some-text energy-level:high")
+            .unwrap();
         todo.new_task("x 2023-11-12 2023-11-12 done task").unwrap();
 
         assert_eq!(Parts::Text("Text".to_string()).fill(&todo), None);
@@ -168,7 +276,200 @@ mod tests {
             Parts::Special(String::from("spec")).fill(&todo),
             Some(String::from("some-text"))
         );
+        assert_eq!(
+            Parts::Special(String::from("energy-level")).fill(&todo),
+            Some(String::from("high"))
+        );
 
         Ok(())
     }
+
+    #[test]
+    fn fill_date_format() {
+        let config = Config::load_from_buffer("date_format = \"%d.%m.%Y\"".as_bytes());
+        let mut todo = ToDo::new(&config);
+        todo.new_task("x 2023-11-12 2023-10-01 task t:2023-11-02 due:2023-12-24")
+            .unwrap();
+        todo.set_active(ToDoData::Done, 0);
+        assert_eq!(
+            Parts::CreateDate.fill(&todo),
+            Some(String::from("01.10.2023"))
+        );
+        assert_eq!(
+            Parts::FinishDate.fill(&todo),
+            Some(String::from("12.11.2023"))
+        );
+        assert_eq!(
+            Parts::TresholdDate.fill(&todo),
+            Some(String::from("02.11.2023"))
+        );
+        assert_eq!(Parts::DueDate.fill(&todo), Some(String::from("24.12.2023")));
+
+        // Invalid format is not loaded.
+        let config = Config::load_from_buffer("date_format = \"%d.%\"".as_bytes());
+        assert_eq!(config.get_date_format(), DateFormat::default());
+    }
+
+    #[test]
+    fn fill_age() {
+        let mut todo = ToDo::default();
+        let created = get_actual_date() - chrono::Duration::days(12);
+        todo.new_task(&format!("{} task", created)).unwrap();
+        todo.new_task(&format!("{} new task", get_actual_date()))
+            .unwrap();
+        todo.add_task(Task::from_str("task without date").unwrap());
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(Parts::Age.fill(&todo), Some(String::from("12d")));
+        todo.set_active(ToDoData::Pending, 1);
+        assert_eq!(Parts::Age.fill(&todo), Some(String::from("0d")));
+        todo.set_active(ToDoData::Pending, 2);
+        assert_eq!(Parts::Age.fill(&todo), None);
+        assert_eq!(Parts::from(String::from("age")), Parts::Age);
+    }
+
+    #[test]
+    fn fill_finish_relative() {
+        let today = get_actual_date();
+        for (days, expected) in [
+            (0, "today"),
+            (1, "yesterday"),
+            (2, "2 days ago"),
+            (-1, "tomorrow"),
+            (-3, "in 3 days"),
+        ] {
+            let finished = today - chrono::Duration::days(days);
+            let mut todo = ToDo::default();
+            todo.add_task(Task::from_str(&format!("x {finished} {finished} task")).unwrap());
+            todo.set_active(ToDoData::Done, 0);
+            assert_eq!(
+                Parts::FinishRelative.fill(&todo),
+                Some(String::from(expected))
+            );
+        }
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str("x task").unwrap());
+        todo.set_active(ToDoData::Done, 0);
+        assert_eq!(Parts::FinishRelative.fill(&todo), None);
+        assert_eq!(
+            Parts::from(String::from("finish_relative")),
+            Parts::FinishRelative
+        );
+    }
+
+    #[test]
+    fn fill_progress() {
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str("task pct:40").unwrap());
+        todo.add_task(Task::from_str("task progress:100%").unwrap());
+        todo.add_task(Task::from_str("task pct:unknown").unwrap());
+        todo.add_task(Task::from_str("task").unwrap());
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(Parts::Progress.fill(&todo), Some(String::from("40")));
+        assert_eq!(
+            Parts::ProgressBar.fill(&todo),
+            Some(String::from("[####------]"))
+        );
+        todo.set_active(ToDoData::Pending, 1);
+        assert_eq!(Parts::Progress.fill(&todo), Some(String::from("100")));
+        assert_eq!(
+            Parts::ProgressBar.fill(&todo),
+            Some(String::from("[##########]"))
+        );
+        for index in [2, 3] {
+            todo.set_active(ToDoData::Pending, index);
+            assert_eq!(Parts::Progress.fill(&todo), None);
+            assert_eq!(Parts::ProgressBar.fill(&todo), None);
+        }
+        assert_eq!(Parts::from(String::from("progress")), Parts::Progress);
+        assert_eq!(
+            Parts::from(String::from("progress_bar")),
+            Parts::ProgressBar
+        );
+    }
+
+    #[test]
+    fn fill_checkbox() {
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str("pending task").unwrap());
+        todo.add_task(Task::from_str("x done task").unwrap());
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(Parts::Checkbox.fill(&todo), Some(String::from("[ ]")));
+        todo.set_active(ToDoData::Done, 0);
+        assert_eq!(Parts::Checkbox.fill(&todo), Some(String::from("[x]")));
+        assert_eq!(Parts::from(String::from("checkbox")), Parts::Checkbox);
+    }
+
+    #[test]
+    fn fill_priority_symbol() {
+        let mut todo = ToDo::default();
+        todo.config
+            .priority_symbols
+            .insert(String::from("A"), String::from("!!"));
+        todo.add_task(Task::from_str("(A) important task").unwrap());
+        todo.add_task(Task::from_str("(B) less important task").unwrap());
+        todo.add_task(Task::from_str("task").unwrap());
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(Parts::PrioritySymbol.fill(&todo), Some(String::from("!!")));
+        for index in [1, 2] {
+            todo.set_active(ToDoData::Pending, index);
+            assert_eq!(Parts::PrioritySymbol.fill(&todo), None);
+        }
+        assert_eq!(
+            Parts::from(String::from("priority_symbol")),
+            Parts::PrioritySymbol
+        );
+    }
+
+    #[test]
+    fn fill_subject_width() {
+        assert_eq!(
+            Parts::from(String::from("subject:30")),
+            Parts::SubjectWidth(30)
+        );
+        assert_eq!(
+            Parts::from(String::from("subject:abc")),
+            Parts::Special(String::from("subject:abc"))
+        );
+
+        let mut todo = ToDo::default();
+        todo.add_task(Task::from_str("some long task subject").unwrap());
+        todo.add_task(Task::from_str("žluťoučký kůň úpěl").unwrap());
+        todo.add_task(Task::from_str("short").unwrap());
+
+        todo.set_active(ToDoData::Pending, 0);
+        assert_eq!(
+            Parts::SubjectWidth(10).fill(&todo),
+            Some(String::from("some long…"))
+        );
+        assert_eq!(Parts::SubjectWidth(0).fill(&todo), Some(String::new()));
+        assert_eq!(Parts::SubjectWidth(1).fill(&todo), Some(String::from("…")));
+
+        todo.set_active(ToDoData::Pending, 1);
+        assert_eq!(
+            Parts::SubjectWidth(10).fill(&todo),
+            Some(String::from("žluťoučký…"))
+        );
+        assert_eq!(
+            Parts::SubjectWidth(18).fill(&todo),
+            Some(String::from("žluťoučký kůň úpěl"))
+        );
+        assert_eq!(
+            Parts::SubjectWidth(17).fill(&todo),
+            Some(String::from("žluťoučký kůň úp…"))
+        );
+
+        todo.set_active(ToDoData::Pending, 2);
+        assert_eq!(
+            Parts::SubjectWidth(5).fill(&todo),
+            Some(String::from("short"))
+        );
+        assert_eq!(
+            Parts::SubjectWidth(30).fill(&todo),
+            Some(String::from("short"))
+        );
+    }
 }
@@ -0,0 +1,182 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// Keys of tags which values can be written as relative dates.
+const DATE_KEYS: [&str; 2] = ["due:", "t:"];
+
+/// Parses a date written relatively to today. Supported forms are `today`,
+/// `tomorrow`, `yesterday`, weekday names (`monday` or `mon`) meaning
+/// the next such day, and offsets `+3d` or `+2w`.
+///
+/// # Arguments
+///
+/// * `value` - The relative date, case insensitive.
+/// * `today` - The date relative dates are computed from.
+///
+/// # Returns
+///
+/// The date or `None` if the value is not a relative date.
+pub fn parse_relative_date(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let value = value.to_lowercase();
+    match value.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.succ_opt(),
+        "yesterday" => return today.pred_opt(),
+        _ => {}
+    }
+    if let Some(offset) = value.strip_prefix('+') {
+        let (count, unit) = offset.split_at(offset.len().checked_sub(1)?);
+        let count = i64::from(count.parse::<u32>().ok()?);
+        let days = match unit {
+            "d" => count,
+            "w" => count.checked_mul(7)?,
+            _ => return None,
+        };
+        return today.checked_add_signed(Duration::try_days(days)?);
+    }
+    let weekday = value.parse::<Weekday>().ok()?;
+    let days = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    today.checked_add_signed(Duration::days(if days == 0 { 7 } else { days.into() }))
+}
+
+/// Replaces relative dates in `due:` and `t:` tags of the task by dates.
+/// Empty tag value is replaced by today. Tags are matched as whole words
+/// separated by any whitespace, other parts of the task are not changed.
+///
+/// # Arguments
+///
+/// * `task` - The task string.
+/// * `today` - The date relative dates are computed from.
+///
+/// # Returns
+///
+/// The task with expanded dates.
+pub fn expand_relative_dates(task: &str, today: NaiveDate) -> String {
+    task.split_inclusive(char::is_whitespace)
+        .map(|part| {
+            let word = part.trim_end_matches(char::is_whitespace);
+            let separator = &part[word.len()..];
+            DATE_KEYS
+                .iter()
+                .find_map(|key| {
+                    let value = word.strip_prefix(key)?;
+                    let date = if value.is_empty() {
+                        today
+                    } else {
+                        parse_relative_date(value, today)?
+                    };
+                    Some(format!("{key}{}{separator}", date.format("%Y-%m-%d")))
+                })
+                .unwrap_or_else(|| part.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        // Wednesday
+        let today = date(11, 1);
+        assert_eq!(parse_relative_date("today", today), Some(today));
+        assert_eq!(parse_relative_date("Tomorrow", today), Some(date(11, 2)));
+        assert_eq!(parse_relative_date("yesterday", today), Some(date(10, 31)));
+        assert_eq!(parse_relative_date("monday", today), Some(date(11, 6)));
+        assert_eq!(parse_relative_date("fri", today), Some(date(11, 3)));
+        assert_eq!(parse_relative_date("wednesday", today), Some(date(11, 8)));
+        assert_eq!(parse_relative_date("+3d", today), Some(date(11, 4)));
+        assert_eq!(parse_relative_date("+0d", today), Some(today));
+        assert_eq!(parse_relative_date("+2w", today), Some(date(11, 15)));
+        assert_eq!(parse_relative_date("+31d", today), Some(date(12, 2)));
+
+        for value in ["", "+", "+d", "+3", "+3y", "+-3d", "someday", "2023-11-01"] {
+            assert_eq!(parse_relative_date(value, today), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn expand() {
+        let today = date(11, 1);
+        assert_eq!(
+            expand_relative_dates("task due:today", today),
+            "task due:2023-11-01"
+        );
+        assert_eq!(
+            expand_relative_dates("task due:tomorrow +project", today),
+            "task due:2023-11-02 +project"
+        );
+        assert_eq!(
+            expand_relative_dates("task due:monday t:+3d", today),
+            "task due:2023-11-06 t:2023-11-04"
+        );
+        assert_eq!(
+            expand_relative_dates("task t: due:", today),
+            "task t:2023-11-01 due:2023-11-01"
+        );
+        assert_eq!(
+            expand_relative_dates("task  due:2023-12-24  t:later", today),
+            "task  due:2023-12-24  t:later"
+        );
+    }
+
+    #[test]
+    fn expand_any_position() {
+        let today = date(11, 1);
+        assert_eq!(
+            expand_relative_dates("task due:", today),
+            "task due:2023-11-01"
+        );
+        assert_eq!(
+            expand_relative_dates("task due:today", today),
+            "task due:2023-11-01"
+        );
+        assert_eq!(
+            expand_relative_dates("task due:today +project", today),
+            "task due:2023-11-01 +project"
+        );
+        assert_eq!(
+            expand_relative_dates("due: task", today),
+            "due:2023-11-01 task"
+        );
+        assert_eq!(
+            expand_relative_dates("task\tdue:\n", today),
+            "task\tdue:2023-11-01\n"
+        );
+        assert_eq!(expand_relative_dates("", today), "");
+    }
+
+    #[test]
+    fn expand_threshold() {
+        let today = date(11, 1);
+        assert_eq!(
+            expand_relative_dates("task t:today", today),
+            "task t:2023-11-01"
+        );
+        assert_eq!(
+            expand_relative_dates("task t: +project", today),
+            "task t:2023-11-01 +project"
+        );
+        assert_eq!(
+            expand_relative_dates("task t:TODAY due:tomorrow", today),
+            "task t:2023-11-01 due:2023-11-02"
+        );
+    }
+
+    #[test]
+    fn expand_only_tags() {
+        let today = date(11, 1);
+        for task in [
+            "do it today",
+            "finish today's report tomorrow",
+            "today: due:someday",
+            "overdue:today est:today",
+        ] {
+            assert_eq!(expand_relative_dates(task, today), task);
+        }
+    }
+}
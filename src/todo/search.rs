@@ -0,0 +1,427 @@
+use std::{borrow::Cow, ops::Range};
+use tui::{style::Style, text::Span};
+
+/// Prefix of the query that enables matching only whole words.
+const WORD_BOUNDARY_PREFIX: &str = "\\b";
+
+/// Case insensitive search of terms in task texts.
+///
+/// The query is split by whitespaces to terms. Text matches if it contains
+/// all include terms and none of exclude terms, that are prefixed by `-` or `!`.
+/// Query prefixed by `\b` matches terms only on word boundaries.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct Search {
+    term: String,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    word_boundary: bool,
+}
+
+impl Search {
+    /// Creates a new search, `None` if the query has no terms.
+    pub fn new(term: &str) -> Option<Self> {
+        let (query, word_boundary) = match term.strip_prefix(WORD_BOUNDARY_PREFIX) {
+            Some(query) => (query, true),
+            None => (term, false),
+        };
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        for word in query.split_whitespace() {
+            match word.strip_prefix(['-', '!']) {
+                Some(excluded) if !excluded.is_empty() => exclude.push(excluded.to_string()),
+                _ => include.push(word.to_string()),
+            }
+        }
+        if include.is_empty() && exclude.is_empty() {
+            None
+        } else {
+            Some(Self {
+                term: term.to_string(),
+                include,
+                exclude,
+                word_boundary,
+            })
+        }
+    }
+
+    /// Enables matching only whole words. Search that is already
+    /// matching whole words is not changed.
+    pub fn word_boundary(mut self, word_boundary: bool) -> Self {
+        self.word_boundary |= word_boundary;
+        self
+    }
+
+    /// Gets the searched query.
+    pub fn term(&self) -> &str {
+        &self.term
+    }
+
+    fn eq_ignore_case(a: char, b: char) -> bool {
+        a == b || a.to_lowercase().eq(b.to_lowercase())
+    }
+
+    /// Tries to match the term at the byte position `start` of the text.
+    ///
+    /// # Returns
+    ///
+    /// Byte position of the end of the match.
+    fn match_at(term: &str, text: &str, start: usize) -> Option<usize> {
+        let mut chars = text[start..].char_indices();
+        let mut end = start;
+        for term_char in term.chars() {
+            let (index, c) = chars.next()?;
+            if !Self::eq_ignore_case(c, term_char) {
+                return None;
+            }
+            end = start + index + c.len_utf8();
+        }
+        Some(end)
+    }
+
+    fn is_word_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+
+    /// Checks if the match of the term is not a part of a longer word.
+    /// Like `\b` in regular expressions, boundary is required only next
+    /// to word characters of the term.
+    fn on_boundary(term: &str, text: &str, start: usize, end: usize) -> bool {
+        let touches = |term_char: Option<char>, text_char: Option<char>| {
+            term_char.is_some_and(Self::is_word_char) && text_char.is_some_and(Self::is_word_char)
+        };
+        !touches(term.chars().next(), text[..start].chars().next_back())
+            && !touches(term.chars().next_back(), text[end..].chars().next())
+    }
+
+    /// Tries to match the term at the byte position `start` of the text,
+    /// respecting word boundaries if they are required.
+    fn match_term(&self, term: &str, text: &str, start: usize) -> Option<usize> {
+        Self::match_at(term, text, start)
+            .filter(|&end| !self.word_boundary || Self::on_boundary(term, text, start, end))
+    }
+
+    /// Checks if the text contains the term.
+    fn contains(&self, term: &str, text: &str) -> bool {
+        text.char_indices()
+            .any(|(start, _)| self.match_term(term, text, start).is_some())
+    }
+
+    /// Checks if the text contains all include terms and no exclude term.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.include.iter().all(|term| self.contains(term, text))
+            && !self.exclude.iter().any(|term| self.contains(term, text))
+    }
+
+    /// Finds all not overlapping occurrences of include terms in the text.
+    /// Nothing is found if the text does not match the search.
+    pub fn find(&self, text: &str) -> SearchMatches {
+        if !self.is_match(text) {
+            return SearchMatches::default();
+        }
+        self.find_terms(text)
+    }
+
+    /// Finds all not overlapping occurrences of include terms in the text,
+    /// even if the text does not match the whole search. It is used for
+    /// short texts like category names that cannot contain all the terms.
+    pub fn find_terms(&self, text: &str) -> SearchMatches {
+        let mut matches = Vec::new();
+        let mut next = 0;
+        for (start, _) in text.char_indices() {
+            if start < next {
+                continue;
+            }
+            let end = self
+                .include
+                .iter()
+                .filter_map(|term| self.match_term(term, text, start))
+                .max();
+            if let Some(end) = end {
+                matches.push(start..end);
+                next = end;
+            }
+        }
+        SearchMatches(matches)
+    }
+}
+
+/// Byte ranges of the search matches in a text.
+#[derive(Debug, Default)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct SearchMatches(pub Vec<Range<usize>>);
+
+impl SearchMatches {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Highlights the matches in spans of the text the matches were found in.
+    /// Spans are split on match boundaries, so the highlight style is applied
+    /// only to the matched text.
+    ///
+    /// # Arguments
+    ///
+    /// * `spans` - Spans that together contain the whole searched text.
+    /// * `style` - Style patched to the matches.
+    /// * `current` - Index of the current match and its style, that is used
+    ///   instead of `style`.
+    ///
+    /// # Returns
+    ///
+    /// Spans with highlighted matches.
+    pub fn highlight<'a>(
+        &self,
+        spans: Vec<Span<'a>>,
+        style: Style,
+        current: Option<(usize, Style)>,
+    ) -> Vec<Span<'a>> {
+        if self.is_empty() {
+            return spans;
+        }
+        let match_style = |index: usize| match current {
+            Some((current, current_style)) if current == index => current_style,
+            _ => style,
+        };
+        let mut ret = Vec::new();
+        let mut offset = 0;
+        for span in spans {
+            let span_range = offset..offset + span.content.len();
+            offset = span_range.end;
+            let mut pos = span_range.start;
+            for (index, m) in self.0.iter().enumerate() {
+                let start = m.start.max(span_range.start);
+                let end = m.end.min(span_range.end);
+                if start >= end {
+                    continue;
+                }
+                if pos < start {
+                    ret.push(Self::sub_span(&span, &span_range, pos..start, span.style));
+                }
+                ret.push(Self::sub_span(
+                    &span,
+                    &span_range,
+                    start..end,
+                    span.style.patch(match_style(index)),
+                ));
+                pos = end;
+            }
+            if pos == span_range.start {
+                ret.push(span);
+            } else if pos < span_range.end {
+                ret.push(Self::sub_span(
+                    &span,
+                    &span_range,
+                    pos..span_range.end,
+                    span.style,
+                ));
+            }
+        }
+        ret
+    }
+
+    /// Creates a span from a part of the span. Range is in bytes of the whole text.
+    fn sub_span<'a>(
+        span: &Span<'a>,
+        span_range: &Range<usize>,
+        range: Range<usize>,
+        style: Style,
+    ) -> Span<'a> {
+        let range = range.start - span_range.start..range.end - span_range.start;
+        let content = match &span.content {
+            Cow::Borrowed(content) => Cow::Borrowed(&content[range]),
+            Cow::Owned(content) => Cow::Owned(content[range].to_string()),
+        };
+        Span::styled(content, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::style::Color;
+
+    #[test]
+    fn find() {
+        assert_eq!(Search::new(""), None);
+
+        let search = Search::new("Task").unwrap();
+        assert_eq!(search.term(), "Task");
+        assert!(search.is_match("some task"));
+        assert!(!search.is_match("some tas"));
+        assert_eq!(
+            search.find("task TASK taskTask"),
+            SearchMatches(vec![0..4, 5..9, 10..14, 14..18])
+        );
+
+        let search = Search::new("aa").unwrap();
+        assert_eq!(search.find("aaa").0, vec![Range { start: 0, end: 2 }]);
+    }
+
+    #[test]
+    fn exclude() {
+        let search = Search::new("report -draft").unwrap();
+        assert_eq!(search.term(), "report -draft");
+        assert!(search.is_match("Write report"));
+        assert!(!search.is_match("Write report DRAFT"));
+        assert!(!search.is_match("Write draft"));
+        assert_eq!(search.find("report").0, vec![Range { start: 0, end: 6 }]);
+        assert!(search.find("report draft").is_empty());
+
+        let search = Search::new("report !draft !old").unwrap();
+        assert!(search.is_match("report new"));
+        assert!(!search.is_match("report old"));
+
+        // All include terms are required.
+        let search = Search::new("report task -draft").unwrap();
+        assert!(!search.is_match("report"));
+        assert!(search.is_match("task report"));
+        assert_eq!(search.find("task report"), SearchMatches(vec![0..4, 5..11]));
+
+        // Lonely prefix is searched.
+        let search = Search::new("a - b").unwrap();
+        assert!(search.is_match("a - b"));
+        assert!(!search.is_match("a b"));
+    }
+
+    #[test]
+    fn only_exclude() {
+        assert_eq!(Search::new("  "), None);
+        let search = Search::new("-draft").unwrap();
+        assert!(search.is_match("report"));
+        assert!(search.is_match(""));
+        assert!(!search.is_match("report draft"));
+        // There is nothing to highlight.
+        assert!(search.find("report").is_empty());
+    }
+
+    #[test]
+    fn word_boundary() {
+        let search = Search::new("cat").unwrap().word_boundary(true);
+        assert!(search.is_match("cat"));
+        assert!(search.is_match("the cat"));
+        assert!(search.is_match("the Cat, dog"));
+        assert!(!search.is_match("category"));
+        assert!(!search.is_match("educate"));
+        assert_eq!(
+            search.find("category cat educate (cat)"),
+            SearchMatches(vec![9..12, 22..25])
+        );
+        // Without word boundaries everything matches.
+        let search = Search::new("cat").unwrap().word_boundary(false);
+        assert!(search.is_match("category"));
+        assert!(search.is_match("educate"));
+
+        // Prefix of the query enables word boundaries.
+        let search = Search::new("\\bcat -dog").unwrap();
+        assert_eq!(search.term(), "\\bcat -dog");
+        assert!(search.is_match("the cat"));
+        assert!(!search.is_match("category"));
+        assert!(search.is_match("the cat and hotdog"));
+        assert!(!search.is_match("the cat and dog"));
+        assert_eq!(search.clone().word_boundary(false), search);
+        assert_eq!(Search::new("\\b"), None);
+
+        // Terms starting by a not word character.
+        let search = Search::new("+proj").unwrap().word_boundary(true);
+        assert!(search.is_match("task +proj"));
+        assert!(search.is_match("task+proj"));
+        assert!(!search.is_match("task +project"));
+        let search = Search::new("café").unwrap().word_boundary(true);
+        assert!(search.is_match("a café"));
+        assert!(!search.is_match("cafés"));
+    }
+
+    #[test]
+    fn find_multi_byte() {
+        let text = "café @cafetería";
+        let search = Search::new("caf").unwrap();
+        let matches = search.find(text);
+        assert_eq!(matches, SearchMatches(vec![0..3, 7..10]));
+        assert_eq!(&text[matches.0[1].clone()], "caf");
+
+        let search = Search::new("CAFÉ").unwrap();
+        let matches = search.find(text);
+        assert_eq!(matches.0, vec![Range { start: 0, end: 5 }]);
+        assert_eq!(&text[matches.0[0].clone()], "café");
+
+        let search = Search::new("ría").unwrap();
+        assert_eq!(search.find(text).0, vec![Range { start: 13, end: 17 }]);
+
+        let text = "🎉 party 🎉";
+        let search = Search::new("🎉").unwrap();
+        assert_eq!(search.find(text), SearchMatches(vec![0..4, 11..15]));
+        // Term longer than the rest of the text.
+        let search = Search::new("🎉 partyy").unwrap();
+        assert!(!search.is_match(text));
+    }
+
+    #[test]
+    fn highlight_multi_byte() {
+        let style = Style::default().bg(Color::Yellow);
+        let text = "café @cafetería";
+        let spans = vec![Span::raw("café "), Span::raw("@cafetería")];
+        for term in ["c", "ca", "caf", "café", "é", "ía"] {
+            let search = Search::new(term).unwrap();
+            let highlighted = search.find(text).highlight(spans.clone(), style, None);
+            let content: String = highlighted.iter().map(|s| s.content.as_ref()).collect();
+            assert_eq!(content, text);
+        }
+
+        let search = Search::new("caf").unwrap();
+        let highlighted = search.find(text).highlight(spans, style, None);
+        assert_eq!(
+            highlighted,
+            vec![
+                Span::styled("caf", style),
+                Span::raw("é "),
+                Span::raw("@"),
+                Span::styled("caf", style),
+                Span::raw("etería"),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight() {
+        let style = Style::default().bg(Color::Yellow);
+        let current = Style::default().bg(Color::Red);
+        let search = Search::new("ab").unwrap();
+        let text = "ab cd ab ab";
+        let matches = search.find(text);
+        assert_eq!(matches.len(), 3);
+
+        let spans = vec![Span::raw("ab c"), Span::raw("d ab ab")];
+        let highlighted = matches.highlight(spans, style, Some((1, current)));
+        assert_eq!(
+            highlighted,
+            vec![
+                Span::styled("ab", style),
+                Span::raw(" c"),
+                Span::raw("d "),
+                Span::styled("ab", current),
+                Span::raw(" "),
+                Span::styled("ab", style),
+            ]
+        );
+
+        // Match split between spans.
+        let spans = vec![Span::raw("a"), Span::raw("b cd ab ab")];
+        let highlighted = matches.highlight(spans, style, None);
+        assert_eq!(highlighted[0], Span::styled("a", style));
+        assert_eq!(highlighted[1], Span::styled("b", style));
+        assert_eq!(highlighted[2], Span::raw(" cd "));
+
+        let spans = vec![Span::raw("no match")];
+        assert_eq!(
+            search
+                .find("no match")
+                .highlight(spans.clone(), style, None),
+            spans
+        );
+    }
+}
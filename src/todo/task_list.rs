@@ -1,25 +1,179 @@
+use super::search::Search;
 use crate::config::Styles;
+use chrono::NaiveDate;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::convert::From;
-use std::ops::Index;
-use todo_txt::Task;
+use std::ops::{Index, Range};
+use todo_txt::{Priority, Task};
+use tui::style::Style;
 use tui::text::Line;
 use tui::text::Span;
 use tui::widgets::ListItem;
 
 type Item<'a> = (usize, &'a Task);
 
+/// Tag marking the task the user is currently working on.
+pub const NOW_TAG: &str = "now";
+
+/// Tag marking tasks that are always shown before other tasks.
+pub const PIN_TAG: &str = "pin";
+
+/// Tag with the priority of finished tasks, the todo.txt format
+/// has no place for the priority of completed tasks.
+pub const PRIORITY_TAG: &str = "pri";
+
+/// Gets the priority of the task. Finished tasks without the priority
+/// use the priority from the `pri:` tag, e.g. `pri:A`.
+pub fn get_priority(task: &Task) -> Priority {
+    if task.finished && task.priority.is_lowest() {
+        let priority = task
+            .tags
+            .get(PRIORITY_TAG)
+            .filter(|value| value.chars().count() == 1)
+            .and_then(|value| value.chars().next())
+            .and_then(|value| Priority::try_from(value).ok());
+        if let Some(priority) = priority {
+            return priority;
+        }
+    }
+    task.priority.clone()
+}
+
+/// Number of cells taken by the priority gutter before each task.
+pub const PRIORITY_GUTTER_WIDTH: usize = 2;
+
+/// Tags with the percentage of the task completion.
+pub const PROGRESS_TAGS: [&str; 2] = ["pct", "progress"];
+
+/// Gets the percentage of the task completion from the `pct:` or `progress:` tag.
+/// Values can end with `%` and are limited to 100.
+///
+/// # Returns
+///
+/// `None` if the task has no progress tag or its value is not a number.
+pub fn get_progress(task: &Task) -> Option<u8> {
+    let value = PROGRESS_TAGS.iter().find_map(|tag| task.tags.get(*tag))?;
+    let value = value.strip_suffix('%').unwrap_or(value);
+    Some(value.parse::<u32>().ok()?.min(100) as u8)
+}
+
 /// Represents the possible sorting options for tasks.
-#[derive(Clone, Copy, Serialize, Deserialize, Default, ValueEnum)]
-#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Clone, Copy, Serialize, Deserialize, Default, ValueEnum, PartialEq, Eq, Debug)]
 pub enum TaskSort {
     #[default]
     None,
     Reverse,
     Priority,
+    PriorityReverse,
+    DueDate,
+    DueDateReverse,
     Alphanumeric,
     AlphanumericReverse,
+    FinishDate,
+    FinishDateReverse,
+    /// Groups tasks by their first project and sorts each group by priority.
+    ProjectThenPriority,
+    ProjectThenPriorityReverse,
+}
+
+impl TaskSort {
+    /// Sortings switched by `next`, each of them has a reversed variant.
+    const CYCLE: [TaskSort; 5] = [
+        TaskSort::None,
+        TaskSort::Priority,
+        TaskSort::DueDate,
+        TaskSort::Alphanumeric,
+        TaskSort::FinishDate,
+    ];
+
+    /// Gets the sorting in the opposite direction.
+    pub fn reversed(self) -> Self {
+        use TaskSort::*;
+        match self {
+            None => Reverse,
+            Reverse => None,
+            Priority => PriorityReverse,
+            PriorityReverse => Priority,
+            DueDate => DueDateReverse,
+            DueDateReverse => DueDate,
+            Alphanumeric => AlphanumericReverse,
+            AlphanumericReverse => Alphanumeric,
+            FinishDate => FinishDateReverse,
+            FinishDateReverse => FinishDate,
+            ProjectThenPriority => ProjectThenPriorityReverse,
+            ProjectThenPriorityReverse => ProjectThenPriority,
+        }
+    }
+
+    /// Gets the next sorting in the order `None`, `Priority`, `DueDate`,
+    /// `Alphanumeric`, `FinishDate` and again `None`. The direction
+    /// of the sorting is kept. Sortings out of the cycle switch to `None`.
+    pub fn next(self) -> Self {
+        let position = |sort: Self| Self::CYCLE.iter().position(|s| *s == sort);
+        let (base, reversed) = match (position(self), position(self.reversed())) {
+            (Some(position), _) => (position, false),
+            (Option::None, Some(position)) => (position, true),
+            (Option::None, Option::None) => return TaskSort::None,
+        };
+        let next = Self::CYCLE[(base + 1) % Self::CYCLE.len()];
+        if reversed {
+            next.reversed()
+        } else {
+            next
+        }
+    }
+
+    /// Compares two items by the sorting. `None` sorting considers all items equal.
+    /// Tasks without a project are sorted first by project sortings
+    /// if `no_project_first` is set, otherwise last.
+    fn compare(
+        &self,
+        (a_index, a_task): &Item,
+        (b_index, b_task): &Item,
+        no_project_first: bool,
+    ) -> Ordering {
+        use TaskSort::*;
+        let project = |task: &Task| task.projects().first().cloned();
+        let compare_projects = |reverse: bool| match (project(a_task), project(b_task)) {
+            (Some(a), Some(b)) if reverse => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), Option::None) if no_project_first => Ordering::Greater,
+            (Some(_), Option::None) => Ordering::Less,
+            (Option::None, Some(_)) if no_project_first => Ordering::Less,
+            (Option::None, Some(_)) => Ordering::Greater,
+            (Option::None, Option::None) => Ordering::Equal,
+        };
+        match self {
+            None => Ordering::Equal,
+            Reverse => b_index.cmp(a_index),
+            Priority => get_priority(b_task).cmp(&get_priority(a_task)),
+            PriorityReverse => get_priority(a_task).cmp(&get_priority(b_task)),
+            DueDate => Self::compare_dates(a_task.due_date, b_task.due_date, false),
+            DueDateReverse => Self::compare_dates(a_task.due_date, b_task.due_date, true),
+            Alphanumeric => a_task.subject.cmp(&b_task.subject),
+            AlphanumericReverse => b_task.subject.cmp(&a_task.subject),
+            FinishDate => Self::compare_dates(a_task.finish_date, b_task.finish_date, false),
+            FinishDateReverse => Self::compare_dates(a_task.finish_date, b_task.finish_date, true),
+            ProjectThenPriority => compare_projects(false)
+                .then_with(|| get_priority(b_task).cmp(&get_priority(a_task))),
+            ProjectThenPriorityReverse => {
+                compare_projects(true).then_with(|| get_priority(a_task).cmp(&get_priority(b_task)))
+            }
+        }
+    }
+
+    /// Compares optional dates, missing dates are sorted last in both directions.
+    fn compare_dates(a: Option<NaiveDate>, b: Option<NaiveDate>, reverse: bool) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if reverse => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
 }
 
 /// Represents a list of tasks, where each task is a tuple of `(usize, &'a Task)`.
@@ -32,6 +186,197 @@ pub struct TaskList<'a> {
 pub struct TaskSlice<'a> {
     pub vec: &'a [Item<'a>],
     pub styles: &'a Styles,
+    search: Option<&'a Search>,
+    current: Option<usize>,
+    wrap: Option<usize>,
+    active: Option<(usize, Style)>,
+    priority_gutter: bool,
+    selected_categories: Vec<String>,
+}
+
+/// Splits the characters to lines of at most `width` characters. Lines are broken
+/// at spaces if possible, spaces at the start of the wrapped lines are skipped.
+///
+/// # Returns
+///
+/// Ranges of the characters on each line, at least one line.
+fn wrap_ranges(chars: &[char], width: usize) -> Vec<Range<usize>> {
+    // Zero width means the characters are not wrapped.
+    let width = if width == 0 { chars.len() } else { width };
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        if !lines.is_empty() {
+            while chars.get(start) == Some(&' ') {
+                start += 1;
+            }
+            if start == chars.len() {
+                break;
+            }
+        }
+        let mut end = (start + width).min(chars.len());
+        if end < chars.len() && chars[end] != ' ' {
+            if let Some(space) = chars[start..end].iter().rposition(|c| *c == ' ') {
+                if space > 0 {
+                    end = start + space;
+                }
+            }
+        }
+        lines.push(start..end);
+        start = end;
+    }
+    if lines.is_empty() {
+        lines.push(0..0);
+    }
+    lines
+}
+
+/// Gets the number of rows the task takes when wrapped to the width.
+///
+/// # Arguments
+///
+/// * `task` - The task to wrap.
+/// * `width` - Maximal number of characters on a row.
+pub fn wrapped_height(task: &Task, width: usize) -> usize {
+    wrap_ranges(&task.subject.chars().collect::<Vec<_>>(), width).len()
+}
+
+/// Wraps the spans to lines of at most `width` characters, see `wrap_ranges`.
+fn wrap_spans<'a>(spans: &[Span<'a>], width: usize) -> Vec<Line<'a>> {
+    let chars: Vec<(char, Style)> = spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+    let text: Vec<char> = chars.iter().map(|(c, _)| *c).collect();
+    wrap_ranges(&text, width)
+        .into_iter()
+        .map(|range| {
+            let mut line: Vec<Span> = Vec::new();
+            let mut content = String::new();
+            let mut style = None;
+            for (c, char_style) in &chars[range] {
+                if style.is_some_and(|style| style != *char_style) {
+                    line.push(Span::styled(std::mem::take(&mut content), style.unwrap()));
+                }
+                style = Some(*char_style);
+                content.push(*c);
+            }
+            if let Some(style) = style {
+                line.push(Span::styled(content, style));
+            }
+            Line::from(line)
+        })
+        .collect()
+}
+
+impl<'a> TaskSlice<'a> {
+    /// Sets the search to highlight in the tasks.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The search to highlight.
+    /// * `current` - Index of the selected task in the slice, its first match
+    ///   is highlighted as the current one.
+    pub fn search(mut self, search: Option<&'a Search>, current: Option<usize>) -> Self {
+        self.search = search;
+        self.current = current;
+        self
+    }
+
+    /// Sets the width the tasks are wrapped to, `None` keeps every task on one line.
+    pub fn wrap(mut self, width: Option<usize>) -> Self {
+        self.wrap = width;
+        self
+    }
+
+    /// Sets the style patched into the text of the task on the `index` position
+    /// in the slice, `None` highlights no task.
+    pub fn active(mut self, index: Option<usize>, style: Style) -> Self {
+        self.active = index.map(|index| (index, style));
+        self
+    }
+
+    /// Sets if a bar colored by the priority is displayed before each task.
+    pub fn priority_gutter(mut self, enabled: bool) -> Self {
+        self.priority_gutter = enabled;
+        self
+    }
+
+    /// Sets categories with their prefixes, e.g. `+project`, that are styled
+    /// by the category select style in the tasks.
+    pub fn selected_categories(mut self, categories: Vec<String>) -> Self {
+        self.selected_categories = categories;
+        self
+    }
+
+    /// Gets the gutter with the background colored by the priority of the task,
+    /// the color of the priority style is used if it has no background.
+    fn gutter(&self, task: &Task) -> Vec<Span<'a>> {
+        let style = self
+            .styles
+            .priority_style
+            .get_style(u8::from(get_priority(task)));
+        let mut gutter = Style::default();
+        if let Some(color) = style.bg.or(style.fg) {
+            gutter = gutter.bg(color);
+        }
+        vec![
+            Span::styled(" ".repeat(PRIORITY_GUTTER_WIDTH - 1), gutter),
+            Span::raw(" "),
+        ]
+    }
+
+    /// Gets the lines of the task on the `i` position in the slice.
+    fn task_lines(&self, i: usize) -> Vec<Line<'a>> {
+        let task = self.vec[i].1;
+        let mut spans = TaskList::parse_task_string(task, self.styles);
+        if !self.selected_categories.is_empty() {
+            let select_style = self.styles.category_select_style.get_style();
+            spans
+                .iter_mut()
+                .filter(|span| {
+                    self.selected_categories
+                        .iter()
+                        .any(|category| *category == span.content)
+                })
+                .for_each(|span| span.style = span.style.patch(select_style));
+        }
+        if task.finished {
+            let done_style = self.styles.done_style.get_style();
+            spans
+                .iter_mut()
+                .for_each(|span| span.style = span.style.patch(done_style));
+        }
+        if let Some(search) = self.search {
+            let current = if self.current == Some(i) {
+                Some((0, self.styles.current_match_style.get_style()))
+            } else {
+                None
+            };
+            spans = search.find(&task.subject).highlight(
+                spans,
+                self.styles.highlight.get_style(),
+                current,
+            );
+        }
+        if let Some((_, active_style)) = self.active.filter(|(active, _)| *active == i) {
+            spans
+                .iter_mut()
+                .for_each(|span| span.style = span.style.patch(active_style));
+        }
+        let mut lines = match self.wrap {
+            Some(width) => wrap_spans(&spans, width),
+            None => vec![Line::from(spans)],
+        };
+        if self.priority_gutter {
+            for line in lines.iter_mut() {
+                let mut spans = self.gutter(task);
+                spans.append(&mut line.spans);
+                line.spans = spans;
+            }
+        }
+        lines
+    }
 }
 
 impl<'a> TaskList<'a> {
@@ -67,39 +412,67 @@ impl<'a> TaskList<'a> {
     /// # Returns
     ///
     /// A `TaskSlice` containing the sliced tasks.
-    pub fn slice(&self, first: usize, last: usize) -> TaskSlice {
+    pub fn slice(&self, first: usize, last: usize) -> TaskSlice<'_> {
         if last > self.vec.len() {
             return TaskSlice {
                 vec: &self.vec[first..],
                 styles: self.styles,
+                search: None,
+                current: None,
+                wrap: None,
+                active: None,
+                priority_gutter: false,
+                selected_categories: Vec::new(),
             };
         };
         TaskSlice {
             vec: &self.vec[first..last],
             styles: self.styles,
+            search: None,
+            current: None,
+            wrap: None,
+            active: None,
+            priority_gutter: false,
+            selected_categories: Vec::new(),
         }
     }
 
     /// Sorts the task list based on the specified sorting criteria.
+    /// Tasks that are equal are sorted by `tiebreak` and then by their original
+    /// index, so the order is always deterministic.
+    /// Task marked by the `now` tag is always pinned to the top. It is followed
+    /// by tasks with the `pin` tag in their original order.
     ///
     /// # Arguments
     ///
     /// * `sort` - The sorting criteria to apply.
-    pub fn sort(&mut self, sort: TaskSort) {
-        use TaskSort::*;
-        match sort {
-            None => {}
-            Reverse => self.vec.reverse(),
-            Priority => self
-                .vec
-                .sort_by(|(_, a_task), (_, b_task)| b_task.priority.cmp(&a_task.priority)),
-            Alphanumeric => self
-                .vec
-                .sort_by(|(_, a_task), (_, b_task)| a_task.subject.cmp(&b_task.subject)),
-            AlphanumericReverse => self
-                .vec
-                .sort_by(|(_, a_task), (_, b_task)| b_task.subject.cmp(&a_task.subject)),
+    /// * `tiebreak` - The sorting criteria for tasks equal by `sort`.
+    pub fn sort(&mut self, sort: TaskSort, tiebreak: TaskSort) {
+        self.sort_with(sort, tiebreak, false);
+    }
+
+    /// Same as `sort`, but tasks without a project are sorted first
+    /// by project sortings if `no_project_first` is set.
+    pub fn sort_with(&mut self, sort: TaskSort, tiebreak: TaskSort, no_project_first: bool) {
+        // `None` keeps the original order
+        if !matches!(sort, TaskSort::None) {
+            self.vec.sort_by(|a, b| {
+                sort.compare(a, b, no_project_first)
+                    .then_with(|| tiebreak.compare(a, b, no_project_first))
+                    .then_with(|| a.0.cmp(&b.0))
+            });
         }
+        self.vec.sort_by(|a, b| {
+            let key = |(index, task): &Item| {
+                let pinned = task.tags.contains_key(PIN_TAG);
+                (
+                    !task.tags.contains_key(NOW_TAG),
+                    !pinned,
+                    if pinned { *index } else { 0 },
+                )
+            };
+            key(a).cmp(&key(b))
+        });
     }
 
     /// Parses a task's string representation into a vector of `Span` elements for rendering.
@@ -137,7 +510,7 @@ impl<'a> TaskList<'a> {
             return vec![Span::styled(&task.subject, style)];
         }
 
-        indexes.sort_by(|a, b| a.0.cmp(&b.0));
+        indexes.sort_by_key(|a| a.0);
 
         let mut parsed = vec![Span::styled(&task.subject[0..indexes[0].0], style)];
         indexes.iter().zip(indexes.iter().skip(1)).for_each(
@@ -151,6 +524,10 @@ impl<'a> TaskList<'a> {
         let (last_index, last_len) = indexes.last().unwrap();
         let s = &task.subject[*last_index..last_index + last_len];
         parsed.push(Span::styled(s, styles.get_category_style(s).get_style()));
+        let rest = &task.subject[last_index + last_len..];
+        if !rest.is_empty() {
+            parsed.push(Span::styled(rest, style));
+        }
 
         parsed
     }
@@ -167,8 +544,13 @@ impl<'a> From<TaskSlice<'a>> for Vec<ListItem<'a>> {
     fn from(val: TaskSlice<'a>) -> Self {
         val.vec
             .iter()
-            .map(|(_, task)| {
-                ListItem::new(Line::from(TaskList::parse_task_string(task, val.styles)))
+            .enumerate()
+            .map(|(i, (_, task))| {
+                let mut style = Style::default();
+                if task.tags.contains_key(NOW_TAG) {
+                    style = style.patch(val.styles.now_style.get_style());
+                }
+                ListItem::new(val.task_lines(i)).style(style)
             })
             .collect::<Vec<ListItem<'a>>>()
     }
@@ -190,6 +572,45 @@ mod tests {
         assert_eq!(parsed[3].content, "@context1");
         assert_eq!(parsed[4].content, " ");
         assert_eq!(parsed[5].content, "#hashtag1");
+        assert_eq!(parsed.len(), 6);
+
+        let task = Task::from_str("buy +milk today").unwrap();
+        let parsed = TaskList::parse_task_string(&task, &styles);
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[1].content, "+milk");
+        assert_eq!(parsed[2].content, " today");
+    }
+
+    #[test]
+    fn pinned_on_top() -> Result<(), todo_txt::Error> {
+        let tasks = [
+            "b task",
+            "d pinned pin:1",
+            "a task",
+            "c pinned pin:1",
+            "e task now:2023-11-01T10:00:00",
+        ]
+        .map(Task::from_str)
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+        let styles = Styles::default();
+        let mut list = TaskList {
+            vec: tasks.iter().enumerate().collect(),
+            styles: &styles,
+        };
+        list.sort(TaskSort::Alphanumeric, TaskSort::None);
+        assert_eq!(
+            list.vec.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            [4, 1, 3, 2, 0]
+        );
+
+        list.sort(TaskSort::AlphanumericReverse, TaskSort::None);
+        assert_eq!(
+            list.vec.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            [4, 1, 3, 0, 2]
+        );
+
+        Ok(())
     }
 
     #[test]
@@ -216,6 +637,156 @@ mod tests {
         assert_eq!(slice.vec[2], (3, &task4));
     }
 
+    #[test]
+    fn wrap() {
+        let styles = Styles::default();
+        let task = Task::from_str("a long task subject +project that does not fit").unwrap();
+        let tasklist = TaskList {
+            vec: vec![(0, &task)],
+            styles: &styles,
+        };
+        assert_eq!(wrapped_height(&task, 100), 1);
+        assert_eq!(wrapped_height(&task, 20), 3);
+
+        let items: Vec<ListItem> = tasklist.slice(0, 1).into();
+        assert_eq!(items[0].height(), 1);
+        let items: Vec<ListItem> = tasklist.slice(0, 1).wrap(Some(20)).into();
+        assert_eq!(items[0].height(), 3);
+        assert_eq!(items[0].width(), 19);
+
+        let lines = wrap_spans(&TaskList::parse_task_string(&task, &styles), 20);
+        let content = |line: &Line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        };
+        assert_eq!(content(&lines[0]), "a long task subject");
+        assert_eq!(content(&lines[1]), "+project that does");
+        assert_eq!(content(&lines[2]), "not fit");
+
+        // Styles of the spans are kept, also if a span is split to more lines.
+        let red = Style::default().fg(tui::style::Color::Red);
+        let lines = wrap_spans(
+            &[
+                Span::raw("first "),
+                Span::styled("red part", red),
+                Span::raw(" end"),
+            ],
+            10,
+        );
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0].spans,
+            vec![Span::raw("first "), Span::styled("red", red)]
+        );
+        assert_eq!(
+            lines[1].spans,
+            vec![Span::styled("part", red), Span::raw(" end")]
+        );
+    }
+
+    #[test]
+    fn priority_gutter() {
+        use tui::style::Color;
+        let styles = Styles::default();
+        let tasks = [
+            Task::from_str("(A) important task with long subject").unwrap(),
+            Task::from_str("task without priority").unwrap(),
+        ];
+        let list = TaskList {
+            vec: tasks.iter().enumerate().collect(),
+            styles: &styles,
+        };
+
+        let slice = list.slice(0, 2).priority_gutter(true);
+        let lines = slice.task_lines(0);
+        assert_eq!(lines[0].spans[0].content, " ");
+        assert_eq!(lines[0].spans[0].style.bg, Some(Color::Red));
+        assert_eq!(lines[0].spans[1], Span::raw(" "));
+        assert_eq!(
+            lines[0].spans[2].content,
+            "important task with long subject"
+        );
+        let lines = slice.task_lines(1);
+        assert_eq!(lines[0].spans[0].style.bg, None);
+
+        // The gutter is on every row of a wrapped task.
+        let lines = list
+            .slice(0, 2)
+            .priority_gutter(true)
+            .wrap(Some(15))
+            .task_lines(0);
+        assert!(lines.len() > 1);
+        assert!(lines
+            .iter()
+            .all(|line| line.spans[0].style.bg == Some(Color::Red)));
+
+        let lines = list.slice(0, 2).task_lines(0);
+        assert_eq!(
+            lines[0].spans[0].content,
+            "important task with long subject"
+        );
+    }
+
+    #[test]
+    fn selected_categories() -> Result<(), todo_txt::Error> {
+        use crate::todo::{FilterState, ToDo, ToDoCategory, ToDoData};
+        let mut todo = ToDo::default();
+        todo.new_task("task +project1 +project2 @context1")?;
+        todo.toggle_filter(ToDoCategory::Projects, "project1", FilterState::Select);
+        todo.toggle_filter(ToDoCategory::Contexts, "context2", FilterState::Remove);
+        let selected = todo.get_selected_categories();
+        assert_eq!(selected, ["+project1"]);
+
+        let list = todo.get_filtered_and_sorted(ToDoData::Pending);
+        let styles = list.styles;
+        let lines = list.slice(0, 1).selected_categories(selected).task_lines(0);
+        let span = |content: &str| {
+            lines[0]
+                .spans
+                .iter()
+                .find(|span| span.content == content)
+                .unwrap()
+                .style
+        };
+        let select_style = styles.category_select_style.get_style();
+        assert_eq!(
+            span("+project1"),
+            styles
+                .get_category_style("+project1")
+                .get_style()
+                .patch(select_style)
+        );
+        assert_eq!(
+            span("+project2"),
+            styles.get_category_style("+project2").get_style()
+        );
+        assert_eq!(
+            span("@context1"),
+            styles.get_category_style("@context1").get_style()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn wrap_ranges() {
+        let wrap = |s: &str, width| {
+            let chars: Vec<char> = s.chars().collect();
+            super::wrap_ranges(&chars, width)
+                .into_iter()
+                .map(|range| chars[range].iter().collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(wrap("", 5), vec![""]);
+        assert_eq!(wrap("short", 5), vec!["short"]);
+        assert_eq!(wrap("two words", 5), vec!["two", "words"]);
+        assert_eq!(wrap("abc  def", 4), vec!["abc ", "def"]);
+        assert_eq!(wrap("loooooong word", 4), vec!["looo", "ooon", "g", "word"]);
+        assert_eq!(wrap("no wrap", 0), vec!["no wrap"]);
+    }
+
     #[test]
     fn sort_tasklist() {
         let compare = |expected: &TaskList, real: TaskList| {
@@ -238,14 +809,14 @@ mod tests {
             vec: vec![(0, &task1), (1, &task2), (2, &task3), (3, &task4)],
             styles: &styles,
         };
-        none.sort(TaskSort::None);
+        none.sort(TaskSort::None, TaskSort::None);
         compare(&tasklist, none);
 
         let mut reverse = TaskList {
             vec: vec![(0, &task1), (1, &task2), (2, &task3), (3, &task4)],
             styles: &styles,
         };
-        reverse.sort(TaskSort::Reverse);
+        reverse.sort(TaskSort::Reverse, TaskSort::None);
         compare(
             &TaskList {
                 vec: vec![(3, &task4), (2, &task3), (1, &task2), (0, &task1)],
@@ -258,7 +829,7 @@ mod tests {
             vec: vec![(0, &task1), (1, &task2), (2, &task3), (3, &task4)],
             styles: &styles,
         };
-        priority.sort(TaskSort::Priority);
+        priority.sort(TaskSort::Priority, TaskSort::None);
         compare(
             &TaskList {
                 vec: vec![(3, &task4), (0, &task1), (1, &task2), (2, &task3)],
@@ -271,7 +842,7 @@ mod tests {
             vec: vec![(0, &task1), (1, &task2), (2, &task3), (3, &task4)],
             styles: &styles,
         };
-        alpha.sort(TaskSort::Alphanumeric);
+        alpha.sort(TaskSort::Alphanumeric, TaskSort::None);
         compare(
             &TaskList {
                 vec: vec![(2, &task3), (0, &task1), (1, &task2), (3, &task4)],
@@ -284,7 +855,7 @@ mod tests {
             vec: vec![(0, &task1), (1, &task2), (2, &task3), (3, &task4)],
             styles: &styles,
         };
-        alpha_reverse.sort(TaskSort::AlphanumericReverse);
+        alpha_reverse.sort(TaskSort::AlphanumericReverse, TaskSort::None);
         compare(
             &TaskList {
                 vec: vec![(3, &task4), (1, &task2), (0, &task1), (2, &task3)],
@@ -293,4 +864,233 @@ mod tests {
             alpha_reverse,
         );
     }
+
+    #[test]
+    fn search_highlight() {
+        let mut styles = Styles::default();
+        styles.highlight = styles.highlight.bg(tui::style::Color::Yellow);
+        styles.current_match_style = styles.current_match_style.bg(tui::style::Color::Red);
+        let task1 = Task::from_str("first task task").unwrap();
+        let task2 = Task::from_str("second task").unwrap();
+        let tasklist = TaskList {
+            vec: vec![(0, &task1), (1, &task2)],
+            styles: &styles,
+        };
+        let search = Search::new("task").unwrap();
+        let items: Vec<ListItem> = tasklist.slice(0, 2).search(Some(&search), Some(0)).into();
+        let expected: Vec<ListItem> = vec![
+            ListItem::new(Line::from(vec![
+                Span::raw("first "),
+                Span::styled("task", styles.current_match_style.get_style()),
+                Span::raw(" "),
+                Span::styled("task", styles.highlight.get_style()),
+            ])),
+            ListItem::new(Line::from(vec![
+                Span::raw("second "),
+                Span::styled("task", styles.highlight.get_style()),
+            ])),
+        ];
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn done_style() {
+        use crate::config::{TextModifier, TextStyle};
+        use tui::style::Modifier;
+
+        let mut styles = Styles::default();
+        styles.done_style = TextStyle::default().modifier(TextModifier::CrossedOut);
+        styles.highlight = styles.highlight.bg(tui::style::Color::Yellow);
+        let task1 = Task::from_str("x done task +project").unwrap();
+        let task2 = Task::from_str("pending task +project").unwrap();
+        let tasklist = TaskList {
+            vec: vec![(0, &task1), (1, &task2)],
+            styles: &styles,
+        };
+        let search = Search::new("task").unwrap();
+        let items: Vec<ListItem> = tasklist.slice(0, 2).search(Some(&search), None).into();
+        let crossed = Style::default().add_modifier(Modifier::CROSSED_OUT);
+        let expected: Vec<ListItem> = vec![
+            ListItem::new(Line::from(vec![
+                Span::styled("done ", crossed),
+                Span::styled("task", crossed.patch(styles.highlight.get_style())),
+                Span::styled(" ", crossed),
+                Span::styled("+project", crossed),
+            ])),
+            ListItem::new(Line::from(vec![
+                Span::raw("pending "),
+                Span::styled("task", styles.highlight.get_style()),
+                Span::raw(" "),
+                Span::raw("+project"),
+            ])),
+        ];
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn progress() {
+        let progress = |s: &str| get_progress(&Task::from_str(s).unwrap());
+        assert_eq!(progress("task pct:40"), Some(40));
+        assert_eq!(progress("task progress:75%"), Some(75));
+        assert_eq!(progress("task pct:150"), Some(100));
+        assert_eq!(progress("task pct:0"), Some(0));
+        assert_eq!(progress("task pct:half"), None);
+        assert_eq!(progress("task pct:-5"), None);
+        assert_eq!(progress("task"), None);
+    }
+
+    #[test]
+    fn sort_finish_date() {
+        let styles = Styles::default();
+        let task1 = Task::from_str("x 2023-05-02 2023-04-01 second").unwrap();
+        let task2 = Task::from_str("x no finish date").unwrap();
+        let task3 = Task::from_str("x 2023-06-10 2023-04-01 newest").unwrap();
+        let task4 = Task::from_str("x 2023-05-01 2023-04-01 oldest").unwrap();
+        let task5 = Task::from_str("x another without date").unwrap();
+        let new_list = || TaskList {
+            vec: vec![
+                (0, &task1),
+                (1, &task2),
+                (2, &task3),
+                (3, &task4),
+                (4, &task5),
+            ],
+            styles: &styles,
+        };
+        let indexes = |list: &TaskList| list.vec.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+
+        let mut list = new_list();
+        list.sort(TaskSort::FinishDateReverse, TaskSort::None);
+        assert_eq!(indexes(&list), vec![2, 0, 3, 1, 4]);
+        assert_eq!(list[0].subject, "newest");
+
+        let mut list = new_list();
+        list.sort(TaskSort::FinishDate, TaskSort::None);
+        assert_eq!(indexes(&list), vec![3, 0, 2, 1, 4]);
+
+        let mut list = new_list();
+        list.sort(TaskSort::FinishDateReverse, TaskSort::Alphanumeric);
+        assert_eq!(indexes(&list), vec![2, 0, 3, 4, 1]);
+    }
+
+    #[test]
+    fn sort_done_by_priority_tag() {
+        let styles = Styles::default();
+        let task1 = Task::from_str("x low pri:C").unwrap();
+        let task2 = Task::from_str("x without priority").unwrap();
+        let task3 = Task::from_str("x high pri:a").unwrap();
+        let task4 = Task::from_str("x (B) own priority pri:A").unwrap();
+        let task5 = Task::from_str("x invalid pri:AB").unwrap();
+        let tasks = [&task1, &task2, &task3, &task4, &task5];
+        let mut list = TaskList {
+            vec: tasks.into_iter().enumerate().collect(),
+            styles: &styles,
+        };
+        let indexes = |list: &TaskList| list.vec.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+
+        list.sort(TaskSort::Priority, TaskSort::None);
+        assert_eq!(indexes(&list), vec![2, 3, 0, 1, 4]);
+        assert_eq!(get_priority(&task1), Priority::from(2));
+        assert!(get_priority(&task5).is_lowest());
+
+        // Pending tasks do not use the tag.
+        let pending = Task::from_str("pending pri:A").unwrap();
+        assert!(get_priority(&pending).is_lowest());
+    }
+
+    #[test]
+    fn sort_by_due_date() {
+        let styles = Styles::default();
+        let task1 = Task::from_str("second due:2023-05-02").unwrap();
+        let task2 = Task::from_str("no due date").unwrap();
+        let task3 = Task::from_str("first due:2023-05-01").unwrap();
+        let tasks = [&task1, &task2, &task3];
+        let mut list = TaskList {
+            vec: tasks.into_iter().enumerate().collect(),
+            styles: &styles,
+        };
+        let indexes = |list: &TaskList| list.vec.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+
+        list.sort(TaskSort::DueDate, TaskSort::None);
+        assert_eq!(indexes(&list), vec![2, 0, 1]);
+        list.sort(TaskSort::DueDateReverse, TaskSort::None);
+        assert_eq!(indexes(&list), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn sort_by_project_then_priority() {
+        let styles = Styles::default();
+        let tasks = [
+            Task::from_str("(C) work low +work").unwrap(),
+            Task::from_str("(A) home high +home").unwrap(),
+            Task::from_str("no project").unwrap(),
+            Task::from_str("(A) work high +work").unwrap(),
+            Task::from_str("home none +home").unwrap(),
+            Task::from_str("(B) work middle +work").unwrap(),
+        ];
+        let mut list = TaskList {
+            vec: tasks.iter().enumerate().collect(),
+            styles: &styles,
+        };
+        let indexes = |list: &TaskList| list.vec.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+
+        list.sort(TaskSort::ProjectThenPriority, TaskSort::None);
+        assert_eq!(indexes(&list), vec![1, 4, 3, 5, 0, 2]);
+        list.sort_with(TaskSort::ProjectThenPriority, TaskSort::None, true);
+        assert_eq!(indexes(&list), vec![2, 1, 4, 3, 5, 0]);
+        list.sort(TaskSort::ProjectThenPriorityReverse, TaskSort::None);
+        assert_eq!(indexes(&list), vec![0, 5, 3, 4, 1, 2]);
+        assert_eq!(TaskSort::ProjectThenPriority.next(), TaskSort::None);
+    }
+
+    #[test]
+    fn next_and_reversed() {
+        use TaskSort::*;
+        let mut sort = None;
+        let mut cycle = Vec::new();
+        for _ in 0..5 {
+            sort = sort.next();
+            cycle.push(sort);
+        }
+        assert_eq!(cycle, [Priority, DueDate, Alphanumeric, FinishDate, None]);
+        assert_eq!(AlphanumericReverse.next(), FinishDateReverse);
+        assert_eq!(FinishDateReverse.next(), Reverse);
+        for sort in TaskSort::value_variants() {
+            assert_eq!(sort.reversed().reversed(), *sort);
+            assert_ne!(sort.reversed(), *sort);
+        }
+    }
+
+    #[test]
+    fn sort_tiebreak() {
+        let styles = Styles::default();
+        let task1 = Task::from_str("(B) b task").unwrap();
+        let task2 = Task::from_str("(A) c task").unwrap();
+        let task3 = Task::from_str("(B) a task").unwrap();
+        let task4 = Task::from_str("(A) a task").unwrap();
+        let task5 = Task::from_str("(B) a task").unwrap();
+        let new_list = || TaskList {
+            vec: vec![
+                (0, &task1),
+                (1, &task2),
+                (2, &task3),
+                (3, &task4),
+                (4, &task5),
+            ],
+            styles: &styles,
+        };
+        let indexes = |list: &TaskList| list.vec.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+
+        let mut list = new_list();
+        list.sort(TaskSort::Priority, TaskSort::None);
+        assert_eq!(indexes(&list), vec![1, 3, 0, 2, 4]);
+
+        let mut list = new_list();
+        list.sort(TaskSort::Priority, TaskSort::Alphanumeric);
+        assert_eq!(indexes(&list), vec![3, 1, 2, 4, 0]);
+
+        let mut list = new_list();
+        list.sort(TaskSort::Priority, TaskSort::Reverse);
+        assert_eq!(indexes(&list), vec![3, 1, 4, 2, 0]);
+    }
 }
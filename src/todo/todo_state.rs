@@ -1,13 +1,20 @@
+use chrono::{NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, str::FromStr};
 use todo_txt::Task;
 
-use crate::config::ToDoConfig;
+use crate::{config::ToDoConfig, ToDoError};
 
-use super::{task_list::TaskSort, ToDo};
+use super::{
+    counts::{is_due_this_week, is_overdue},
+    get_actual_date,
+    task_list::TaskSort,
+    ToDo,
+};
 
 /// Enum to represent the state of ToDo data (pending or done).
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug))]
 pub enum ToDoData {
     Pending,
     Done,
@@ -39,23 +46,39 @@ impl ToDoData {
         }
     }
 
-    pub fn get_sorting(&self, config: &ToDoConfig) -> TaskSort {
+    /// Gets the sorting of the data. Sorting set at runtime takes
+    /// precedence over the config.
+    pub fn get_sorting(&self, config: &ToDoConfig, state: &ToDoState) -> TaskSort {
         use ToDoData::*;
         match self {
-            Pending => config.pending_sort,
-            Done => config.done_sort,
+            Pending => state.pending_sort.unwrap_or(config.pending_sort),
+            Done => state.done_sort.unwrap_or(config.done_sort),
         }
     }
 }
 
 /// Enum to represent different categories.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum ToDoCategory {
     Projects,
     Contexts,
     Hashtags,
 }
 
+impl FromStr for ToDoCategory {
+    type Err = ToDoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ToDoCategory::*;
+        Ok(match s.to_lowercase().as_str() {
+            "projects" => Projects,
+            "contexts" => Contexts,
+            "hashtags" => Hashtags,
+            _ => return Err(ToDoError::ParseCategory(s.to_string())),
+        })
+    }
+}
+
 impl ToDoCategory {
     pub fn get_data<'a>(&self, task: &'a Task) -> &'a [String] {
         use ToDoCategory::*;
@@ -66,6 +89,16 @@ impl ToDoCategory {
         }
     }
 
+    /// Gets the character that starts the category in a task.
+    pub fn prefix(&self) -> char {
+        use ToDoCategory::*;
+        match self {
+            Projects => '+',
+            Contexts => '@',
+            Hashtags => '#',
+        }
+    }
+
     pub fn get_all() -> &'static [ToDoCategory] {
         use ToDoCategory::*;
         static ALL_CATEGORIES: [ToDoCategory; 3] = [Projects, Contexts, Hashtags];
@@ -79,12 +112,83 @@ pub enum FilterState {
     Remove,
 }
 
+impl FilterState {
+    /// Gets the opposite state, a task passing the filter fails the inverted one.
+    pub fn invert(self) -> Self {
+        match self {
+            FilterState::Select => FilterState::Remove,
+            FilterState::Remove => FilterState::Select,
+        }
+    }
+}
+
+/// Filter of tasks by their due date relative to today.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum DateFilter {
+    /// Tasks due today.
+    DueToday,
+    /// Pending tasks after their due date.
+    Overdue,
+    /// Pending tasks due within the current week.
+    DueThisWeek,
+}
+
+impl DateFilter {
+    /// Checks if the task passes the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The checked task.
+    /// * `today` - The date the due date is compared to.
+    /// * `week_start` - The first day of the week.
+    pub fn matches(&self, task: &Task, today: NaiveDate, week_start: Weekday) -> bool {
+        match self {
+            DateFilter::DueToday => task.due_date == Some(today),
+            DateFilter::Overdue => is_overdue(task, today),
+            DateFilter::DueThisWeek => is_due_this_week(task, today, week_start),
+        }
+    }
+
+    /// Gets the name of the filter shown in filter chips.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DateFilter::DueToday => "due today",
+            DateFilter::Overdue => "overdue",
+            DateFilter::DueThisWeek => "due this week",
+        }
+    }
+}
+
+/// Named set of filters that can be applied later.
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct FilterPreset {
+    pub project_filters: BTreeMap<String, FilterState>,
+    pub context_filters: BTreeMap<String, FilterState>,
+    pub hashtag_filters: BTreeMap<String, FilterState>,
+    pub due_filter: Option<FilterState>,
+    #[serde(default)]
+    pub date_filter: Option<DateFilter>,
+}
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct ToDoState {
     pub active: Option<(ToDoData, usize)>,
     pub project_filters: BTreeMap<String, FilterState>,
     pub context_filters: BTreeMap<String, FilterState>,
     pub hashtag_filters: BTreeMap<String, FilterState>,
+    /// Select keeps only tasks with a due date, remove only tasks without it.
+    pub due_filter: Option<FilterState>,
+    #[serde(default)]
+    pub date_filter: Option<DateFilter>,
+    /// Sorting of pending tasks changed at runtime, overrides the config.
+    #[serde(default)]
+    pub pending_sort: Option<TaskSort>,
+    /// Sorting of done tasks changed at runtime, overrides the config.
+    #[serde(default)]
+    pub done_sort: Option<TaskSort>,
+    #[serde(default)]
+    pub filter_presets: BTreeMap<String, FilterPreset>,
 }
 
 impl ToDoState {
@@ -109,7 +213,7 @@ impl ToDoState {
         }
     }
 
-    pub fn filter_out(&self, task: &Task) -> bool {
+    pub fn filter_out(&self, task: &Task, week_start: Weekday) -> bool {
         fn filter(category: &BTreeMap<String, FilterState>, task_categories: &[String]) -> bool {
             category.iter().all(|(category, state)| {
                 let contains = task_categories.contains(category);
@@ -119,11 +223,85 @@ impl ToDoState {
                 }
             })
         }
-        filter(&self.project_filters, task.projects())
+        let due = match self.due_filter {
+            Some(FilterState::Select) => task.due_date.is_some(),
+            Some(FilterState::Remove) => task.due_date.is_none(),
+            None => true,
+        };
+        let date = self
+            .date_filter
+            .is_none_or(|date_filter| date_filter.matches(task, get_actual_date(), week_start));
+        due && date
+            && filter(&self.project_filters, task.projects())
             && filter(&self.context_filters, task.contexts())
             && filter(&self.hashtag_filters, &task.hashtags)
     }
 
+    /// Removes all category and due date filters.
+    pub fn clear_filters(&mut self) {
+        self.project_filters.clear();
+        self.context_filters.clear();
+        self.hashtag_filters.clear();
+        self.due_filter = None;
+        self.date_filter = None;
+    }
+
+    /// Stores actual filters as a preset, preset with the same name is replaced.
+    pub fn save_preset(&mut self, name: &str) {
+        let preset = FilterPreset {
+            project_filters: self.project_filters.clone(),
+            context_filters: self.context_filters.clone(),
+            hashtag_filters: self.hashtag_filters.clone(),
+            due_filter: self.due_filter,
+            date_filter: self.date_filter,
+        };
+        self.filter_presets.insert(name.to_string(), preset);
+    }
+
+    /// Replaces actual filters by filters of the preset.
+    ///
+    /// # Returns
+    ///
+    /// `false` if there is no preset with the name.
+    pub fn apply_preset(&mut self, name: &str) -> bool {
+        let Some(preset) = self.filter_presets.get(name) else {
+            return false;
+        };
+        self.project_filters = preset.project_filters.clone();
+        self.context_filters = preset.context_filters.clone();
+        self.hashtag_filters = preset.hashtag_filters.clone();
+        self.due_filter = preset.due_filter;
+        self.date_filter = preset.date_filter;
+        true
+    }
+
+    /// Inverts all filters of the category. Single filter in the category then
+    /// shows the complement of the tasks it showed before. Multiple filters are
+    /// inverted one by one, so tasks with all selected categories become tasks
+    /// without any of them.
+    pub fn invert_filters(&mut self, category: ToDoCategory) {
+        self.get_mut_category(category)
+            .values_mut()
+            .for_each(|state| *state = state.invert());
+    }
+
+    /// Toggles the due date filter. Setting the active state turns the filter off.
+    pub fn set_due_filter(&mut self, filter_state: FilterState) {
+        self.due_filter = match self.due_filter {
+            Some(state) if state == filter_state => None,
+            _ => Some(filter_state),
+        };
+    }
+
+    /// Toggles the date filter. Setting the active filter turns it off,
+    /// another filter replaces the active one.
+    pub fn set_date_filter(&mut self, date_filter: DateFilter) {
+        self.date_filter = match self.date_filter {
+            Some(active) if active == date_filter => None,
+            _ => Some(date_filter),
+        };
+    }
+
     pub fn set_filter(&mut self, category: ToDoCategory, filter: &str, filter_state: FilterState) {
         let category = self.get_mut_category(category);
         match category.get_mut(filter) {
@@ -1,32 +1,46 @@
+mod history;
+mod popup;
 mod ui_event;
 mod ui_state;
 
+use history::History;
+use popup::{Popup, PopupResult};
+
 pub use ui_event::*;
 pub use ui_state::*;
 
 use crate::{
-    config::Config,
-    file_worker::{FileWorker, FileWorkerCommands},
+    config::{Config, FileWorkerConfig, Styles},
+    error::ToDoRes,
+    file_worker::{FileWorker, FileWorkerCommands, SavePolicy},
+    layout::widget::widget_type::WidgetType,
     layout::Layout,
     layout::Render,
     todo::autocomplete,
-    todo::ToDo,
+    todo::links::open_link,
+    todo::{DateFilter, FilterState, Parser, ToDo, ToDoData},
 };
+use clap::ValueEnum;
 use crossterm::{
     self,
-    event::{self, read, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, read, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste,
+        EnableMouseCapture, Event, KeyCode, MouseButton, MouseEventKind,
+    },
     execute,
     terminal::{
         disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
     },
     ExecutableCommand,
 };
+use serde::{Deserialize, Serialize};
 use std::{
     error::Error,
     io,
     path::PathBuf,
     sync::mpsc::Sender,
     sync::{Arc, Mutex},
+    thread::JoinHandle,
     time::Duration,
 };
 use tui::{
@@ -37,30 +51,81 @@ use tui::{
     widgets::{Block, BorderType, Borders},
     Terminal,
 };
-use tui_input::{backend::crossterm::EventHandler, Input};
+use tui_input::{backend::crossterm::EventHandler, Input, InputRequest};
+
+/// Position of the input field in the window.
+#[derive(Clone, Copy, Serialize, Deserialize, Default, ValueEnum, Debug, PartialEq, Eq)]
+pub enum InputPosition {
+    #[default]
+    Top,
+    Bottom,
+}
 
 /// Enum representing the different modes of the UI.
 #[derive(Debug, PartialEq, Eq)]
 enum Mode {
     Input,
     Edit,
+    EditSubject,
+    Append,
+    Search,
+    SavePreset,
+    ApplyPreset,
     Normal,
 }
 
+impl Mode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Mode::Input => "INSERT",
+            Mode::Edit | Mode::EditSubject => "EDIT",
+            Mode::Append => "APPEND",
+            Mode::Search => "SEARCH",
+            Mode::SavePreset | Mode::ApplyPreset => "PRESET",
+            Mode::Normal => "NORMAL",
+        }
+    }
+
+    /// Gets the title of the input block.
+    fn title(&self) -> &'static str {
+        match self {
+            Mode::Search => "Search",
+            Mode::SavePreset => "Save filter preset",
+            Mode::ApplyPreset => "Apply filter preset",
+            _ => "Input",
+        }
+    }
+}
+
 /// The struct representing the UI for the application.
 pub struct UI {
+    header_chunk: Option<Rect>,
     input_chunk: Rect,
+    input_position: InputPosition,
+    input_max_height: u16,
+    max_width: Option<u16>,
+    main_chunk: Rect,
     tinput: Input,
     layout: Layout,
     mode: Mode,
     data: Arc<Mutex<ToDo>>,
     tx: Sender<FileWorkerCommands>,
+    /// Thread of the file worker, waited for on exit so pending saves are finished.
+    file_worker: Option<JoinHandle<()>>,
+    save_policy: SavePolicy,
+    confirm_quit_unsaved: bool,
+    stay_in_insert: bool,
     event_handler: EventHandlerUI,
     quit: bool,
     window_title: String,
     list_refresh_rate: Duration,
     active_color: Color,
     save_state_path: Option<PathBuf>,
+    todo_files: Vec<PathBuf>,
+    header: bool,
+    search_history: History,
+    popup: Option<Popup>,
+    detail_parser: Parser,
 }
 
 impl UI {
@@ -74,51 +139,94 @@ impl UI {
     ///
     /// # Returns
     ///
-    /// A new `UI` instance.
+    /// A new `UI` instance or an error if the preview format is invalid.
     pub fn new(
         layout: Layout,
         data: Arc<Mutex<ToDo>>,
         tx: Sender<FileWorkerCommands>,
         config: &Config,
-    ) -> UI {
-        UI {
+    ) -> ToDoRes<UI> {
+        Ok(UI {
+            header_chunk: None,
             input_chunk: Rect::default(),
+            input_position: config.get_input_position(),
+            input_max_height: config.get_input_max_height(),
+            max_width: config.get_max_width(),
+            main_chunk: Rect::default(),
             tinput: Input::default(),
             layout,
             mode: Mode::Normal,
             data,
             tx,
+            file_worker: None,
+            save_policy: config.get_save_policy(),
+            confirm_quit_unsaved: config.get_confirm_quit_unsaved(),
+            stay_in_insert: config.get_stay_in_insert(),
             event_handler: config.get_window_keybind(),
             quit: false,
             window_title: config.get_window_title(),
             list_refresh_rate: config.get_list_refresh_rate(),
             active_color: config.get_active_color(),
             save_state_path: config.get_save_state_path(),
-        }
+            todo_files: Self::get_todo_files(config),
+            header: config.get_header(),
+            search_history: History::default(),
+            popup: None,
+            detail_parser: Parser::new(&config.get_preview_format(), Styles::new(config))?,
+        })
     }
 
-    pub fn build(config: &Config) -> Result<UI, Box<dyn Error>> {
-        let mut todo = ToDo::new(config);
+    /// Gets the todo files that can be switched between. The configured
+    /// todo file is always the first one.
+    fn get_todo_files(config: &Config) -> Vec<PathBuf> {
+        let todo_path = PathBuf::from(config.get_todo_path());
+        let mut files = vec![todo_path.clone()];
+        files.extend(
+            config
+                .get_todo_files()
+                .into_iter()
+                .map(|(_, path)| path)
+                .filter(|path| *path != todo_path),
+        );
+        files
+    }
 
-        if let Some(path) = &config.get_save_state_path() {
-            let state = UIState::load(path)?;
-            let (_active, todo_state) = (state.active, state.todo_state);
-            todo.update_state(todo_state);
-        }
+    /// Gets the index of the todo file the file worker switched to,
+    /// the configured todo file until it switches.
+    fn file_index(&self) -> usize {
+        let todo = self.data.lock().unwrap();
+        todo.get_todo_file()
+            .and_then(|file| self.todo_files.iter().position(|path| path == file))
+            .unwrap_or(0)
+    }
 
-        let todo = Arc::new(Mutex::new(todo));
-        let file_worker = FileWorker::new(
-            config.get_todo_path(),
-            config.get_archive_path(),
-            todo.clone(),
-        );
+    pub fn build(config: &Config) -> Result<UI, Box<dyn Error>> {
+        let todo = Arc::new(Mutex::new(ToDo::new(config)));
+        let file_worker = FileWorker::new(FileWorkerConfig::new(config), todo.clone());
 
         file_worker.load()?;
-        let tx = file_worker.run(config.get_autosave_duration(), config.get_file_watcher());
 
-        let layout = Layout::from_str(&config.get_layout(), todo.clone(), config)?;
+        // The state file does not exist before the first quit.
+        let state = match &config.get_save_state_path() {
+            Some(path) if path.exists() => Some(UIState::load(path)?),
+            _ => None,
+        };
+        if let Some(state) = &state {
+            state.restore_todo(&mut todo.lock().unwrap());
+        }
+        let (tx, file_worker) = file_worker.run();
 
-        Ok(UI::new(layout, todo, tx.clone(), config))
+        let mut layout = Layout::from_str(&config.get_layout(), todo.clone(), config)?;
+        if let Some(state) = &state {
+            state.restore_layout(&mut layout);
+        }
+
+        let mut ui = UI::new(layout, todo, tx.clone(), config)?;
+        ui.file_worker = Some(file_worker);
+        if let Some(state) = state {
+            ui.search_history = History::new(state.search_history);
+        }
+        Ok(ui)
     }
 
     /// Updates the input chunk of the UI based on the main chunk's dimensions.
@@ -130,12 +238,112 @@ impl UI {
     ///
     /// * `main_chunk` - The main chunk's dimensions, typically representing the entire terminal window.
     fn update_chunk(&mut self, main_chunk: Rect) {
+        self.main_chunk = main_chunk;
+        let main_chunk = self.content_chunk();
+        let input_height = self.input_height() + 2;
+        let main_chunk = if self.header {
+            let layout = tuiLayout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(1)])
+                .split(main_chunk);
+            self.header_chunk = Some(layout[0]);
+            layout[1]
+        } else {
+            self.header_chunk = None;
+            main_chunk
+        };
+        let (input_index, layout_index, constraints) = match self.input_position {
+            InputPosition::Top => (0, 1, [Constraint::Length(input_height), Constraint::Min(1)]),
+            InputPosition::Bottom => (1, 0, [Constraint::Min(1), Constraint::Length(input_height)]),
+        };
         let layout = tuiLayout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .constraints(constraints)
             .split(main_chunk);
-        self.input_chunk = layout[0];
-        self.layout.update_chunk(layout[1]);
+        self.input_chunk = layout[input_index];
+        self.layout.update_chunk(layout[layout_index]);
+    }
+
+    /// Gets the chunk of the content, the main chunk narrowed to `max_width`
+    /// and horizontally centered.
+    fn content_chunk(&self) -> Rect {
+        match self.max_width {
+            Some(max_width) if self.main_chunk.width > max_width => Rect {
+                x: self.main_chunk.x + (self.main_chunk.width - max_width) / 2,
+                width: max_width,
+                ..self.main_chunk
+            },
+            _ => self.main_chunk,
+        }
+    }
+
+    /// Gets the width of the text in the input field.
+    fn input_width(&self) -> usize {
+        self.content_chunk().width.saturating_sub(2).max(1) as usize
+    }
+
+    /// Gets the number of rows needed to show the input text, at most `input_max_height`.
+    fn input_height(&self) -> u16 {
+        if self.input_max_height <= 1 {
+            return 1;
+        }
+        let rows = self.tinput.value().chars().count() / self.input_width() + 1;
+        (rows as u16).min(self.input_max_height)
+    }
+
+    /// Updates chunks if the input field needs a different height for the current text.
+    fn update_input_height(&mut self) {
+        if self.input_max_height > 1 && self.input_chunk.height != self.input_height() + 2 {
+            self.update_chunk(self.main_chunk);
+        }
+    }
+
+    /// Gets the title of the input block. Search shows the position
+    /// of the selected task among matching tasks and their number.
+    fn input_title(&self) -> String {
+        let data = match self.layout.get_active_widget() {
+            WidgetType::List if self.mode == Mode::Search => ToDoData::Pending,
+            WidgetType::Done if self.mode == Mode::Search => ToDoData::Done,
+            _ => return self.mode.title().to_string(),
+        };
+        let index = self
+            .layout
+            .get_active_position()
+            .map(|position| position.selected)
+            .unwrap_or(0);
+        match self
+            .data
+            .lock()
+            .unwrap()
+            .count_matches(data, self.tinput.value(), index)
+        {
+            Some((current, total)) => format!("{} {current}/{total}", self.mode.title()),
+            None => self.mode.title().to_string(),
+        }
+    }
+
+    /// Gets the content of the header bar.
+    ///
+    /// # Returns
+    ///
+    /// Name of the current todo file, task counts and the active mode.
+    fn header_text(&self) -> String {
+        let path = &self.todo_files[self.file_index()];
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_else(|| path.to_string_lossy());
+        let todo = self.data.lock().unwrap();
+        let pending = todo.len(ToDoData::Pending);
+        let done = todo.len(ToDoData::Done);
+        format!(
+            " {} | total: {} pending: {} done: {} | {}",
+            name,
+            pending + done,
+            pending,
+            done,
+            self.mode.as_str()
+        )
     }
 
     /// Runs the user interface, handling setup and cleanup of terminal interactions.
@@ -150,7 +358,12 @@ impl UI {
             // setup terminal
             enable_raw_mode()?;
             let mut stdout = io::stdout();
-            execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+            execute!(
+                stdout,
+                EnterAlternateScreen,
+                EnableMouseCapture,
+                EnableBracketedPaste
+            )?;
 
             let mut backend = CrosstermBackend::new(stdout);
             backend.execute(SetTitle(this.window_title.clone()))?;
@@ -167,19 +380,23 @@ impl UI {
             execute!(
                 terminal.backend_mut(),
                 LeaveAlternateScreen,
-                DisableMouseCapture
+                DisableMouseCapture,
+                DisableBracketedPaste
             )?;
             terminal.show_cursor()?;
 
             Ok(())
         }
 
-        if let Err(e) = run_ui(self) {
-            self.tx.send(FileWorkerCommands::Exit).unwrap();
-            Err(e)
-        } else {
-            Ok(())
+        let result = run_ui(self);
+        if let Err(e) = self.tx.send(FileWorkerCommands::Exit) {
+            log::error!("Error while send signal to exit file worker: {}", e);
+        } else if let Some(file_worker) = self.file_worker.take() {
+            if file_worker.join().is_err() {
+                log::error!("File worker thread panicked");
+            }
         }
+        result
     }
 
     /// Handles the main event loop of the UI.
@@ -199,11 +416,13 @@ impl UI {
                 if self.process_event()? {
                     break;
                 }
+                self.check_file_conflict();
                 version = self.data.lock().unwrap().get_version();
                 self.draw(terminal)?;
             } else {
                 new_version = self.data.lock().unwrap().get_version();
                 if new_version != version {
+                    self.check_file_conflict();
                     version = self.data.lock().unwrap().get_version();
                     self.draw(terminal)?;
                 }
@@ -224,19 +443,47 @@ impl UI {
     fn draw<B: Backend>(&self, terminal: &mut Terminal<B>) -> io::Result<()> {
         let mut block = Block::default()
             .borders(Borders::ALL)
-            .title("Input")
+            .title(self.input_title())
             .border_type(BorderType::Rounded);
-        if self.mode == Mode::Input || self.mode == Mode::Edit {
+        if self.mode != Mode::Normal {
             block = block.border_style(Style::default().fg(self.active_color));
         }
+        let header = self.header_chunk.map(|chunk| (self.header_text(), chunk));
         terminal.draw(|f| {
-            f.render_widget(
-                Paragraph::new(self.tinput.value()).block(block),
-                self.input_chunk,
-            );
+            if let Some((text, chunk)) = header {
+                f.render_widget(
+                    Paragraph::new(text).style(Style::default().fg(self.active_color)),
+                    chunk,
+                );
+            }
+            let input = if self.input_max_height > 1 {
+                let chars: Vec<char> = self.tinput.value().chars().collect();
+                let text = chars
+                    .chunks(self.input_width())
+                    .map(|row| row.iter().collect::<String>())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let rows = self.input_chunk.height.saturating_sub(2).max(1);
+                let cursor_row = (self.tinput.visual_cursor() / self.input_width()) as u16;
+                Paragraph::new(text).scroll((cursor_row.saturating_sub(rows - 1), 0))
+            } else {
+                Paragraph::new(self.tinput.value())
+            };
+            f.render_widget(input.block(block), self.input_chunk);
             self.layout.render(f);
+            if let Some(popup) = &self.popup {
+                popup.render(f, f.size(), self.active_color);
+            }
 
-            if self.mode == Mode::Input || self.mode == Mode::Edit {
+            if self.mode != Mode::Normal && self.input_max_height > 1 {
+                let width = self.input_width();
+                let rows = self.input_chunk.height.saturating_sub(2).max(1);
+                let cursor = self.tinput.visual_cursor();
+                f.set_cursor(
+                    self.input_chunk.x + (cursor % width) as u16 + 1,
+                    self.input_chunk.y + ((cursor / width) as u16).min(rows - 1) + 1,
+                );
+            } else if self.mode != Mode::Normal {
                 let width = self.input_chunk.width.max(3) - 3;
                 let scroll = self.tinput.visual_scroll(width as usize);
                 f.set_cursor(
@@ -260,6 +507,166 @@ impl UI {
         Ok(self.quit)
     }
 
+    /// Saves changes of tasks immediately if the save policy requires it.
+    fn save_changes(&self) {
+        if self.save_policy == SavePolicy::AutoSaveImmediate {
+            if let Err(e) = self.tx.send(FileWorkerCommands::Save) {
+                log::error!("Error while send signal to save todo list: {}", e);
+            }
+        }
+    }
+
+    /// Creates the popup with actions for the selected task.
+    fn task_popup(column: u16, row: u16) -> Popup {
+        Popup::new(
+            "Task",
+            vec![
+                (String::from("Toggle done"), UIEvent::MoveItem),
+                (String::from("Delete"), UIEvent::RemoveItem),
+                (String::from("Edit"), UIEvent::EditMode),
+                (String::from("Priority up"), UIEvent::PriorityUp),
+                (String::from("Priority down"), UIEvent::PriorityDown),
+            ],
+            column,
+            row,
+        )
+    }
+
+    /// Opens the popup asking which changes to keep if the todo file was
+    /// changed by another program while there were unsaved changes.
+    /// A failed load of the file and errors after the save are reported
+    /// by a popup too.
+    fn check_file_conflict(&mut self) {
+        if self.mode != Mode::Normal || self.popup.is_some() {
+            return;
+        }
+        let load_error = self.data.lock().unwrap().take_load_error();
+        if let Some(error) = load_error {
+            self.popup = Some(Popup::new(
+                &error,
+                vec![
+                    (String::from("Reload file"), UIEvent::Load),
+                    (String::from("Close"), UIEvent::None),
+                ],
+                self.main_chunk.width / 2,
+                self.main_chunk.height / 2,
+            ));
+        } else if self.data.lock().unwrap().take_file_conflict() {
+            self.popup = Some(Popup::new(
+                "File changed",
+                vec![
+                    (String::from("Keep changes"), UIEvent::Save),
+                    (String::from("Reload file"), UIEvent::Load),
+                    (String::from("Merge"), UIEvent::MergeFile),
+                ],
+                self.main_chunk.width / 2,
+                self.main_chunk.height / 2,
+            ));
+        } else {
+            let save_error = self.data.lock().unwrap().take_save_error();
+            if let Some(error) = save_error {
+                self.popup = Some(self.message_popup(&error));
+            }
+        }
+    }
+
+    /// Saves the state of the UI if it is configured and ends the main loop.
+    fn quit(&mut self) {
+        if let Some(path) = &self.save_state_path {
+            if let Err(e) = UIState::new(
+                &self.layout,
+                &self.data.lock().unwrap(),
+                &self.search_history,
+            )
+            .save(path)
+            {
+                log::error!("Error while saveing UI state: {}", e);
+            }
+        }
+        self.quit = true;
+    }
+
+    /// Creates the popup asking what to do with unsaved changes on quit.
+    fn quit_popup(&self) -> Popup {
+        Popup::new(
+            "Unsaved changes",
+            vec![
+                (String::from("[s]ave and quit"), UIEvent::SaveAndQuit),
+                (String::from("[q]uit without saving"), UIEvent::ForceQuit),
+                (String::from("[c]ancel"), UIEvent::None),
+            ],
+            self.main_chunk.width / 2,
+            self.main_chunk.height / 2,
+        )
+        .with_hotkeys(&['s', 'q', 'c'])
+    }
+
+    /// Creates the popup asking to confirm completion of all filtered tasks.
+    fn complete_filtered_popup(&self) -> Popup {
+        let count = self.data.lock().unwrap().filtered_unfinished_count();
+        Popup::new(
+            &format!("Complete {count} tasks?"),
+            vec![
+                (
+                    String::from("Complete"),
+                    UIEvent::CompleteAllFilteredConfirmed,
+                ),
+                (String::from("Cancel"), UIEvent::None),
+            ],
+            self.main_chunk.width / 2,
+            self.main_chunk.height / 2,
+        )
+    }
+
+    /// Creates the popup with details of the active task filled by the preview format.
+    fn detail_popup(&self) -> Popup {
+        let text = self.detail_parser.fill(&self.data.lock().unwrap());
+        Popup::text(
+            "Detail",
+            text,
+            self.main_chunk.width / 4,
+            self.main_chunk.height / 4,
+        )
+    }
+
+    /// Creates the popup showing the message.
+    fn message_popup(&self, message: &str) -> Popup {
+        Popup::new(
+            message,
+            vec![(String::from("Close"), UIEvent::None)],
+            self.main_chunk.width / 2,
+            self.main_chunk.height / 2,
+        )
+    }
+
+    /// Changes the todo list with the layout unfocused, so the lists keep
+    /// their selected tasks if they are still displayed after the change.
+    fn with_refocus<R>(&mut self, f: impl FnOnce(&mut ToDo) -> R) -> R {
+        self.layout.unfocus();
+        let result = f(&mut self.data.lock().unwrap());
+        self.layout.focus();
+        result
+    }
+
+    /// Moves the focus to the widget of the type if it is in the layout.
+    fn focus_widget(&mut self, widget_type: WidgetType) {
+        if let Err(e) = self.layout.select_widget(widget_type) {
+            log::info!("Cannot focus widget {}: {}", widget_type, e);
+        }
+    }
+
+    /// Handles the event selected in the popup.
+    fn handle_popup_event(&mut self, event: UIEvent) {
+        if event == UIEvent::EditMode {
+            // Only the active task can be edited.
+            self.layout.handle_event(UIEvent::Select);
+        }
+        let changes_tasks = event.changes_tasks();
+        if !self.handle_event(event.clone()) && self.layout.handle_event(event) && changes_tasks {
+            self.save_changes();
+        }
+    }
+
     fn handle_event_window(&mut self, e: Event) {
         match e {
             Event::Resize(width, height) => {
@@ -268,50 +675,122 @@ impl UI {
             }
             Event::Mouse(event) => {
                 log::debug!("Mouse event: {:?}", event);
+                if self.mode == Mode::Normal {
+                    match event.kind {
+                        MouseEventKind::ScrollDown => {
+                            self.layout.scroll(event.column, event.row, true);
+                        }
+                        MouseEventKind::ScrollUp => {
+                            self.layout.scroll(event.column, event.row, false);
+                        }
+                        MouseEventKind::Down(MouseButton::Left) => {
+                            self.popup = None;
+                            self.layout.click(event.column, event.row);
+                        }
+                        MouseEventKind::Down(MouseButton::Right) => {
+                            self.popup = None;
+                            if self.layout.click(event.column, event.row)
+                                && [WidgetType::List, WidgetType::Done]
+                                    .contains(&self.layout.get_active_widget())
+                            {
+                                self.popup = Some(Self::task_popup(event.column, event.row));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::Paste(text) if self.mode != Mode::Normal => {
+                // Tasks are single line, so new lines are pasted as spaces.
+                text.chars()
+                    .filter(|c| *c != '\r')
+                    .map(|c| if c == '\n' { ' ' } else { c })
+                    .for_each(|c| {
+                        self.tinput.handle(InputRequest::InsertChar(c));
+                    });
             }
             Event::Key(event) => match self.mode {
-                Mode::Input => match event.code {
-                    KeyCode::Enter => {
-                        self.data
-                            .lock()
-                            .unwrap()
-                            .new_task(self.tinput.value())
-                            .unwrap(); // TODO fix
+                Mode::Input
+                | Mode::Edit
+                | Mode::EditSubject
+                | Mode::Append
+                | Mode::Search
+                | Mode::SavePreset
+                | Mode::ApplyPreset => match event.code {
+                    KeyCode::Enter if self.mode == Mode::Search => {
+                        self.search_history.push(self.tinput.value());
+                        self.data.lock().unwrap().set_search(self.tinput.value());
                         self.tinput.reset();
                         self.mode = Mode::Normal;
                         self.layout.focus();
+                        self.layout.handle_event(UIEvent::NextSearch);
                     }
-                    KeyCode::Esc => {
-                        self.mode = Mode::Normal;
-                        self.layout.focus();
-                    }
-                    KeyCode::Tab => {
-                        if let Some(input) =
-                            autocomplete(&self.data.lock().unwrap(), self.tinput.value())
-                        {
-                            self.tinput = input.into();
+                    KeyCode::Up if self.mode == Mode::Search => {
+                        if let Some(term) = self.search_history.prev() {
+                            self.tinput = term.into();
                         }
                     }
-                    _ => {
-                        self.tinput.handle_event(&e);
+                    KeyCode::Down if self.mode == Mode::Search => {
+                        if let Some(term) = self.search_history.next() {
+                            self.tinput = term.into();
+                        }
                     }
-                },
-                Mode::Edit => match event.code {
                     KeyCode::Enter => {
-                        self.data
-                            .lock()
-                            .unwrap()
-                            .update_active(self.tinput.value())
-                            .unwrap();
+                        {
+                            let mut todo = self.data.lock().unwrap();
+                            let value = self.tinput.value();
+                            let result = match self.mode {
+                                Mode::Edit => todo.update_active(value),
+                                Mode::Append => todo.append_to_active(value),
+                                Mode::EditSubject => todo.update_active_subject(value),
+                                Mode::SavePreset => {
+                                    todo.save_filter_preset(value);
+                                    Ok(())
+                                }
+                                Mode::ApplyPreset => {
+                                    if !todo.apply_filter_preset(value) {
+                                        log::info!("There is no filter preset '{}'", value);
+                                    }
+                                    Ok(())
+                                }
+                                _ => todo.new_task(value),
+                            };
+                            match result {
+                                Ok(())
+                                    if self.mode != Mode::SavePreset
+                                        && self.mode != Mode::ApplyPreset =>
+                                {
+                                    self.save_changes()
+                                }
+                                Ok(()) => {}
+                                Err(e) => log::error!("Cannot process input '{}': {}", value, e),
+                            }
+                        }
                         self.tinput.reset();
-                        self.mode = Mode::Normal;
-                        self.layout.focus();
+                        if self.mode != Mode::Input || !self.stay_in_insert {
+                            self.mode = Mode::Normal;
+                            self.layout.focus();
+                        }
                     }
                     KeyCode::Esc => {
-                        self.tinput.reset();
+                        if self.mode != Mode::Input {
+                            self.tinput.reset();
+                        }
                         self.mode = Mode::Normal;
                         self.layout.focus();
                     }
+                    KeyCode::Tab if self.mode == Mode::ApplyPreset => {
+                        let name = self
+                            .data
+                            .lock()
+                            .unwrap()
+                            .get_filter_presets()
+                            .find(|name| name.starts_with(self.tinput.value()))
+                            .cloned();
+                        if let Some(name) = name {
+                            self.tinput = name.into();
+                        }
+                    }
                     KeyCode::Tab => {
                         if let Some(input) =
                             autocomplete(&self.data.lock().unwrap(), self.tinput.value())
@@ -323,12 +802,26 @@ impl UI {
                         self.tinput.handle_event(&e);
                     }
                 },
-                Mode::Normal => {
-                    let _ = self.handle_key(&event.code) || self.layout.handle_key(&event);
-                }
+                Mode::Normal => match self.popup.as_mut().map(|p| p.handle_key(&event.code)) {
+                    Some(PopupResult::Open) => {}
+                    Some(PopupResult::Close) => self.popup = None,
+                    Some(PopupResult::Select(event)) => {
+                        self.popup = None;
+                        self.handle_popup_event(event);
+                    }
+                    None => {
+                        if !self.handle_key(&event.code) {
+                            let changes_tasks = self.layout.get_event(&event).changes_tasks();
+                            if self.layout.handle_key(&event) && changes_tasks {
+                                self.save_changes();
+                            }
+                        }
+                    }
+                },
             },
             _ => {}
         }
+        self.update_input_height();
     }
 }
 
@@ -341,15 +834,20 @@ impl HandleEvent for UI {
         use UIEvent::*;
         match event {
             Quit => {
-                if let Some(path) = &self.save_state_path {
-                    if let Err(e) =
-                        UIState::new(&self.layout, &self.data.lock().unwrap()).save(path)
-                    {
-                        log::error!("Error while saveing UI state: {}", e);
-                    }
+                if self.confirm_quit_unsaved && self.data.lock().unwrap().has_unsaved_changes() {
+                    self.popup = Some(self.quit_popup());
+                } else {
+                    self.quit();
                 }
-                self.quit = true;
             }
+            SaveAndQuit => {
+                if let Err(e) = self.tx.send(FileWorkerCommands::ForceSave) {
+                    log::error!("Error while send signal to save todo list: {}", e);
+                    return true;
+                }
+                self.quit();
+            }
+            ForceQuit => self.quit(),
             InsertMode => {
                 self.mode = Mode::Input;
                 self.layout.unfocus();
@@ -378,14 +876,126 @@ impl HandleEvent for UI {
                     // TODO show something on screen
                 }
             }
+            FocusWidget(widget_type) => self.focus_widget(widget_type),
+            ToggleFilter { category, name } => {
+                self.with_refocus(|todo| todo.toggle_filter(category, &name, FilterState::Select));
+            }
+            FocusList => self.focus_widget(WidgetType::List),
+            FocusDone => self.focus_widget(WidgetType::Done),
+            FocusProjects => self.focus_widget(WidgetType::Project),
+            FocusContexts => self.focus_widget(WidgetType::Context),
+            FocusHashtags => self.focus_widget(WidgetType::Hashtag),
+            ShowDetail => {
+                self.layout.handle_event(UIEvent::Select);
+                self.popup = Some(self.detail_popup());
+            }
+            OpenLink => {
+                self.layout.handle_event(UIEvent::Select);
+                let link = self.data.lock().unwrap().get_active_link();
+                let result = match link {
+                    Some(url) => open_link(&url).map_err(|e| format!("Cannot open {url}: {e}")),
+                    Option::None => Err(String::from("Task has no link")),
+                };
+                if let Err(e) = result {
+                    log::warn!("{e}");
+                    self.popup = Some(self.message_popup(&e));
+                }
+            }
+            CompleteAllFiltered => {
+                self.popup = Some(self.complete_filtered_popup());
+            }
+            CompleteAllFilteredConfirmed => {
+                let count = self.with_refocus(ToDo::complete_filtered);
+                log::info!("Completed {count} filtered tasks");
+                self.save_changes();
+            }
+            MergeFile => {
+                if let Err(e) = self.tx.send(FileWorkerCommands::Merge) {
+                    log::error!("Error while send signal to merge todo list: {}", e);
+                }
+            }
+            SwitchFile => {
+                if self.todo_files.len() < 2 {
+                    log::info!("There is no other todo file to switch to");
+                    return true;
+                }
+                // The file index changes once the file worker switches the file.
+                let index = (self.file_index() + 1) % self.todo_files.len();
+                let path = self.todo_files[index].clone();
+                if let Err(e) = self.tx.send(FileWorkerCommands::SwitchTo(path)) {
+                    log::error!("Error while send signal to switch todo file: {}", e);
+                }
+            }
+            ToggleWrap => {
+                self.layout.broadcast_event(ToggleWrap);
+            }
+            FilterDue => {
+                self.with_refocus(|todo| todo.toggle_due_filter(FilterState::Select));
+            }
+            FilterNoDue => {
+                self.with_refocus(|todo| todo.toggle_due_filter(FilterState::Remove));
+            }
+            FilterDueToday => {
+                self.with_refocus(|todo| todo.toggle_date_filter(DateFilter::DueToday));
+            }
+            FilterOverdue => {
+                self.with_refocus(|todo| todo.toggle_date_filter(DateFilter::Overdue));
+            }
+            FilterDueThisWeek => {
+                self.with_refocus(|todo| todo.toggle_date_filter(DateFilter::DueThisWeek));
+            }
+            ClearFilters => {
+                self.with_refocus(ToDo::clear_all_filters);
+            }
+            SaveFilterPreset | ApplyFilterPreset => {
+                self.tinput.reset();
+                self.mode = if event == SaveFilterPreset {
+                    Mode::SavePreset
+                } else {
+                    Mode::ApplyPreset
+                };
+                self.layout.unfocus();
+            }
+            SearchMode => {
+                self.tinput.reset();
+                self.search_history.reset();
+                self.mode = Mode::Search;
+                self.layout.unfocus();
+            }
+            AppendMode => {
+                if self.data.lock().unwrap().get_active().is_some() {
+                    self.tinput.reset();
+                    self.mode = Mode::Append;
+                    self.layout.unfocus();
+                }
+            }
             EditMode => {
-                if let Some(active) = self.data.lock().unwrap().get_active() {
-                    self.tinput = active.to_string().into();
+                let active = self
+                    .data
+                    .lock()
+                    .unwrap()
+                    .get_active()
+                    .map(|active| active.to_string());
+                if let Some(active) = active {
+                    self.tinput = active.into();
                     self.mode = Mode::Edit;
                     self.layout.unfocus();
                     // self.in
                 }
             }
+            EditSubjectMode => {
+                let active = self
+                    .data
+                    .lock()
+                    .unwrap()
+                    .get_active()
+                    .map(|active| active.subject.clone());
+                if let Some(active) = active {
+                    self.tinput = active.into();
+                    self.mode = Mode::EditSubject;
+                    self.layout.unfocus();
+                }
+            }
             _ => {
                 return false;
             }
@@ -397,16 +1007,19 @@ impl HandleEvent for UI {
 #[cfg(test)]
 mod tests {
     use crossterm::event::{KeyEvent, KeyModifiers};
-    use std::env;
+    use std::{env, str::FromStr};
     use test_log::test;
+    use todo_txt::Task;
 
     use super::*;
+    use crate::{test_utils::TempDir, todo::ToDoCategory};
 
     fn default_ui() -> Result<UI, Box<dyn Error>> {
         let config = Config::load_from_buffer(
             format!(
                 r#"
-            todo_path = "{}todo.txt"
+            todo_path = "{0}todo.txt"
+            save_state_path = "{0}missing-state.toml"
 
             [[list_keybind.events]]
             event = "ListDown"
@@ -440,7 +1053,10 @@ mod tests {
             )
             .as_bytes(),
         );
-        UI::build(&config)
+        let mut ui = UI::build(&config)?;
+        // Tests do not write the state anywhere.
+        ui.save_state_path = None;
+        Ok(ui)
     }
 
     #[test]
@@ -488,4 +1104,896 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_save_policy() -> Result<(), Box<dyn Error>> {
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut ui = default_ui()?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        ui.tx = tx;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        ui.data.lock().unwrap().move_data(ToDo::default());
+        let dir = TempDir::new("save-policy");
+        let path = dir.join("todo.txt");
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = path.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let mut worker = FileWorker::new(config, ui.data.clone());
+        // Handles the queued save like the running file worker would
+        // and gets subjects of the saved tasks.
+        let mut saved = || -> Result<Vec<String>, Box<dyn Error>> {
+            match rx.try_recv() {
+                Ok(command @ FileWorkerCommands::Save) => worker.handle_command(command)?,
+                _ => return Err("Save is not queued".into()),
+            }
+            assert!(rx.try_recv().is_err());
+            Ok(std::fs::read_to_string(&path)?
+                .lines()
+                .map(|line| Task::from_str(line).map(|task| task.subject))
+                .collect::<Result<_, _>>()?)
+        };
+        let add_task = |ui: &mut UI, task: &str| {
+            ui.handle_event(UIEvent::InsertMode);
+            for c in task.chars() {
+                ui.handle_event_window(key(KeyCode::Char(c)));
+            }
+            ui.handle_event_window(key(KeyCode::Enter));
+        };
+
+        add_task(&mut ui, "first task");
+        assert!(saved().is_err());
+
+        ui.save_policy = SavePolicy::AutoSaveImmediate;
+        add_task(&mut ui, "second task");
+        assert_eq!(saved()?, ["first task", "second task"]);
+
+        // Moving in the list does not change tasks.
+        ui.handle_event_window(key(KeyCode::Char('j')));
+        assert!(saved().is_err());
+
+        ui.handle_event_window(key(KeyCode::Enter));
+        ui.handle_event(UIEvent::EditMode);
+        ui.tinput = "edited task".into();
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert_eq!(saved()?, ["edited task", "second task"]);
+
+        ui.handle_popup_event(UIEvent::SwapDownItem);
+        assert_eq!(saved()?, ["second task", "edited task"]);
+
+        ui.handle_popup_event(UIEvent::RemoveItem);
+        assert_eq!(ui.data.lock().unwrap().len(ToDoData::Pending), 1);
+        assert_eq!(saved()?, ["second task"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_switch_file() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        ui.tx = tx;
+        let dir = TempDir::new("switch-file");
+        let first = dir.join("first.txt");
+        let second = dir.join("second.txt");
+        std::fs::write(&first, "first task\n")?;
+        ui.todo_files = vec![first.clone(), second.clone()];
+        let mut config = FileWorkerConfig::new(&Config::default());
+        config.todo_path = first.to_string_lossy().into_owned();
+        config.archive_path = None;
+        let mut worker = FileWorker::new(config, ui.data.clone());
+        worker.load()?;
+        let mut switch = |ui: &mut UI| {
+            ui.handle_event(UIEvent::SwitchFile);
+            match rx.try_recv() {
+                Ok(command @ FileWorkerCommands::SwitchTo(_)) => worker.handle_command(command),
+                _ => panic!("Switch is not queued"),
+            }
+        };
+
+        // The second file does not exist, so the first one stays shown.
+        assert!(switch(&mut ui).is_err());
+        assert!(ui.header_text().starts_with(" first.txt |"));
+
+        std::fs::write(&second, "second task\n")?;
+        switch(&mut ui)?;
+        assert!(ui.header_text().starts_with(" second.txt |"));
+        switch(&mut ui)?;
+        assert!(ui.header_text().starts_with(" first.txt |"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_conflict_popup() -> Result<(), Box<dyn Error>> {
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut ui = default_ui()?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        ui.tx = tx;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        ui.check_file_conflict();
+        assert!(ui.popup.is_none());
+
+        ui.data.lock().unwrap().set_file_conflict();
+        ui.check_file_conflict();
+        assert!(ui.popup.is_some());
+        ui.handle_event_window(key(KeyCode::Down));
+        ui.handle_event_window(key(KeyCode::Down));
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.popup.is_none());
+        assert!(matches!(rx.try_recv(), Ok(FileWorkerCommands::Merge)));
+
+        ui.check_file_conflict();
+        assert!(ui.popup.is_none());
+
+        // Failed load of the file.
+        ui.data
+            .lock()
+            .unwrap()
+            .set_load_error(String::from("Line 2 cannot be parsed"));
+        ui.check_file_conflict();
+        assert!(ui.popup.is_some());
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.popup.is_none());
+        assert!(matches!(rx.try_recv(), Ok(FileWorkerCommands::Load)));
+
+        // Error after the file was saved.
+        ui.data
+            .lock()
+            .unwrap()
+            .set_save_error(String::from("Git autocommit failed"));
+        ui.check_file_conflict();
+        assert!(ui.popup.is_some());
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.popup.is_none());
+        assert!(rx.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirm_quit() -> Result<(), Box<dyn Error>> {
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut ui = default_ui()?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        ui.tx = tx;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        ui.data.lock().unwrap().mark_saved();
+        assert!(ui.handle_event(UIEvent::Quit));
+        assert!(ui.quit);
+
+        ui.quit = false;
+        ui.data.lock().unwrap().new_task("unsaved task")?;
+        assert!(ui.handle_event(UIEvent::Quit));
+        assert!(!ui.quit);
+        assert!(ui.popup.is_some());
+
+        // Cancel
+        ui.handle_event_window(key(KeyCode::Char('c')));
+        assert!(ui.popup.is_none());
+        assert!(!ui.quit);
+        ui.handle_event(UIEvent::Quit);
+        ui.handle_event_window(key(KeyCode::Esc));
+        assert!(ui.popup.is_none());
+        assert!(!ui.quit);
+
+        ui.handle_event(UIEvent::Quit);
+        ui.handle_event_window(key(KeyCode::Char('s')));
+        assert!(ui.quit);
+        assert!(matches!(rx.try_recv(), Ok(FileWorkerCommands::ForceSave)));
+
+        ui.quit = false;
+        ui.handle_event(UIEvent::Quit);
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.quit);
+        assert!(matches!(rx.try_recv(), Ok(FileWorkerCommands::ForceSave)));
+
+        ui.quit = false;
+        ui.handle_event(UIEvent::Quit);
+        ui.handle_event_window(key(KeyCode::Char('q')));
+        assert!(ui.quit);
+        assert!(rx.try_recv().is_err());
+
+        ui.quit = false;
+        ui.handle_event(UIEvent::Quit);
+        ui.handle_event_window(key(KeyCode::Down));
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.quit);
+        assert!(rx.try_recv().is_err());
+
+        ui.quit = false;
+        ui.confirm_quit_unsaved = false;
+        ui.handle_event(UIEvent::Quit);
+        assert!(ui.quit);
+        assert!(ui.popup.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_complete_all_filtered() -> Result<(), Box<dyn Error>> {
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("first +project")?;
+            todo.new_task("other task")?;
+            todo.new_task("second +project")?;
+            todo.toggle_filter(ToDoCategory::Projects, "project", FilterState::Select);
+        }
+        ui.layout.focus();
+
+        assert!(ui.handle_event(UIEvent::CompleteAllFiltered));
+        ui.handle_event_window(key(KeyCode::Down));
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.popup.is_none());
+        assert_eq!(ui.data.lock().unwrap().len(ToDoData::Pending), 2);
+
+        assert!(ui.handle_event(UIEvent::CompleteAllFiltered));
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.popup.is_none());
+        {
+            let todo = ui.data.lock().unwrap();
+            assert_eq!(todo.len(ToDoData::Pending), 0);
+            assert_eq!(todo.len(ToDoData::Done), 2);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_detail_popup() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.detail_parser = Parser::new(
+            "Subject: $subject\nDue: $due_date",
+            Styles::new(&Config::default()),
+        )?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("first task")?;
+            todo.new_task("second task +project due:2023-06-30")?;
+        }
+        ui.layout.focus();
+        ui.layout.handle_event(UIEvent::ListDown);
+        assert!(ui.handle_event(UIEvent::ShowDetail));
+        let popup = ui.popup.as_ref().unwrap();
+        assert_eq!(
+            popup.text_lines(),
+            ["Subject: second task +project", "Due: 2023-06-30"]
+        );
+
+        ui.handle_event_window(Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(ui.popup.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_link_without_link() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("task without link")?;
+        }
+        ui.layout.focus();
+        assert!(ui.handle_event(UIEvent::OpenLink));
+        assert!(ui.popup.is_some());
+        ui.handle_event_window(Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(ui.popup.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_toggle_filter_keybind() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        let config = Config::load_from_buffer(
+            r#"
+            [[window_keybind.events]]
+            event.ToggleFilter = { category = "Contexts", name = "urgent" }
+            key.Char = "!"
+            "#
+            .as_bytes(),
+        );
+        ui.event_handler = config.get_window_keybind();
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("urgent task @urgent")?;
+            todo.new_task("calm task @home")?;
+        }
+        ui.layout.focus();
+        let key = Event::Key(KeyEvent::new(KeyCode::Char('!'), KeyModifiers::NONE));
+        let subjects = |ui: &UI| -> Vec<String> {
+            ui.data
+                .lock()
+                .unwrap()
+                .get_filtered_and_sorted(ToDoData::Pending)
+                .vec
+                .iter()
+                .map(|(_, task)| task.subject.clone())
+                .collect()
+        };
+
+        ui.handle_event_window(key.clone());
+        assert_eq!(subjects(&ui), ["urgent task @urgent"]);
+
+        ui.handle_event_window(key);
+        assert_eq!(subjects(&ui), ["urgent task @urgent", "calm task @home"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_focus_widget() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 80, 40));
+        ui.layout.focus();
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::List);
+
+        assert!(ui.handle_event(UIEvent::FocusWidget(WidgetType::Done)));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::Done);
+        assert!(ui.handle_event(UIEvent::FocusProjects));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::Project);
+        ui.handle_event_window(Event::Key(KeyEvent::new(
+            KeyCode::Char('1'),
+            KeyModifiers::NONE,
+        )));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::List);
+
+        // Widgets that are not in the layout are ignored.
+        assert!(ui.handle_event(UIEvent::FocusHashtags));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::List);
+        assert!(ui.handle_event(UIEvent::FocusWidget(WidgetType::Preview)));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::Preview);
+
+        assert_eq!(
+            UIEvent::from_str("FocusWidget(done)")?,
+            UIEvent::FocusWidget(WidgetType::Done)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_width() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 200, 50));
+        assert_eq!(ui.content_chunk(), Rect::new(0, 0, 200, 50));
+
+        ui.max_width = Some(120);
+        ui.update_chunk(Rect::new(0, 0, 200, 50));
+        assert_eq!(ui.content_chunk(), Rect::new(40, 0, 120, 50));
+        assert_eq!(ui.input_chunk.x, 40);
+        assert_eq!(ui.input_chunk.width, 120);
+        // Margins contain no widgets.
+        for row in [10, 40] {
+            assert_eq!(ui.layout.find_widget(39, row), None);
+            assert!(ui.layout.find_widget(40, row).is_some());
+            assert!(ui.layout.find_widget(159, row).is_some());
+            assert_eq!(ui.layout.find_widget(160, row), None);
+        }
+
+        // Narrower window is not changed.
+        ui.update_chunk(Rect::new(0, 0, 100, 50));
+        assert_eq!(ui.content_chunk(), Rect::new(0, 0, 100, 50));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stay_in_insert() -> Result<(), Box<dyn Error>> {
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut ui = default_ui()?;
+        ui.stay_in_insert = true;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        ui.data.lock().unwrap().move_data(ToDo::default());
+        ui.layout.focus();
+
+        assert!(ui.handle_event(UIEvent::InsertMode));
+        for task in ["first task", "second task"] {
+            task.chars()
+                .for_each(|c| ui.handle_event_window(key(KeyCode::Char(c))));
+            ui.handle_event_window(key(KeyCode::Enter));
+            assert_eq!(ui.mode, Mode::Input);
+            assert_eq!(ui.tinput.value(), "");
+        }
+        {
+            let todo = ui.data.lock().unwrap();
+            assert_eq!(todo.len(ToDoData::Pending), 2);
+            assert_eq!(todo.pending[0].subject, "first task");
+            assert_eq!(todo.pending[1].subject, "second task");
+        }
+
+        ui.handle_event_window(key(KeyCode::Esc));
+        assert_eq!(ui.mode, Mode::Normal);
+
+        // Without the option Enter returns to the normal mode.
+        ui.stay_in_insert = false;
+        assert!(ui.handle_event(UIEvent::InsertMode));
+        "third task"
+            .chars()
+            .for_each(|c| ui.handle_event_window(key(KeyCode::Char(c))));
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert_eq!(ui.mode, Mode::Normal);
+        assert_eq!(ui.data.lock().unwrap().len(ToDoData::Pending), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_height() -> Result<(), Box<dyn Error>> {
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+        ui.mode = Mode::Input;
+        for _ in 0..40 {
+            ui.handle_event_window(key('a'));
+        }
+        assert_eq!(ui.input_chunk, Rect::new(0, 0, 20, 3));
+
+        ui.input_max_height = 3;
+        ui.tinput.reset();
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+        assert_eq!(ui.input_chunk.height, 3);
+        // Text is 18 characters wide.
+        for _ in 0..18 {
+            ui.handle_event_window(key('a'));
+        }
+        assert_eq!(ui.input_chunk, Rect::new(0, 0, 20, 4));
+        for _ in 0..18 {
+            ui.handle_event_window(key('a'));
+        }
+        assert_eq!(ui.input_chunk, Rect::new(0, 0, 20, 5));
+        for _ in 0..40 {
+            ui.handle_event_window(key('a'));
+        }
+        assert_eq!(ui.input_chunk, Rect::new(0, 0, 20, 5));
+
+        ui.handle_event_window(Event::Key(KeyEvent::new(
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+        )));
+        assert_eq!(ui.input_chunk, Rect::new(0, 0, 20, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_header() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+        assert_eq!(ui.header_chunk, None);
+
+        ui.header = true;
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+        assert_eq!(ui.header_chunk, Some(Rect::new(0, 0, 20, 1)));
+        assert_eq!(ui.input_chunk, Rect::new(0, 1, 20, 3));
+
+        ui.input_position = InputPosition::Bottom;
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+        assert_eq!(ui.header_chunk, Some(Rect::new(0, 0, 20, 1)));
+        assert_eq!(ui.input_chunk, Rect::new(0, 17, 20, 3));
+        ui.header = false;
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+        assert_eq!(ui.input_chunk, Rect::new(0, 17, 20, 3));
+        ui.input_position = InputPosition::Top;
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+        assert_eq!(ui.input_chunk, Rect::new(0, 0, 20, 3));
+        ui.header = true;
+        ui.update_chunk(Rect::new(0, 0, 20, 20));
+
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("pending task")?;
+            todo.new_task("x done task")?;
+            todo.new_task("another pending task")?;
+        }
+        assert_eq!(
+            ui.header_text(),
+            " todo.txt | total: 3 pending: 2 done: 1 | NORMAL"
+        );
+
+        ui.mode = Mode::Edit;
+        assert!(ui.header_text().ends_with("| EDIT"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_presets() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("first task +work")?;
+            todo.new_task("second task +home")?;
+            todo.toggle_filter(ToDoCategory::Projects, "work", FilterState::Select);
+        }
+        let type_text = |ui: &mut UI, text: &str| {
+            for c in text.chars() {
+                ui.handle_event_window(Event::Key(KeyEvent::new(
+                    KeyCode::Char(c),
+                    KeyModifiers::NONE,
+                )));
+            }
+        };
+        let press = |ui: &mut UI, code: KeyCode| {
+            ui.handle_event_window(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        };
+
+        ui.handle_event(UIEvent::SaveFilterPreset);
+        assert_eq!(ui.mode, Mode::SavePreset);
+        type_text(&mut ui, "work");
+        press(&mut ui, KeyCode::Enter);
+        assert_eq!(ui.mode, Mode::Normal);
+
+        ui.handle_event(UIEvent::ClearFilters);
+        assert_eq!(
+            ui.data
+                .lock()
+                .unwrap()
+                .get_filtered_and_sorted(ToDoData::Pending)
+                .len(),
+            2
+        );
+
+        ui.handle_event(UIEvent::ApplyFilterPreset);
+        assert_eq!(ui.mode, Mode::ApplyPreset);
+        type_text(&mut ui, "w");
+        press(&mut ui, KeyCode::Tab);
+        assert_eq!(ui.tinput.value(), "work");
+        press(&mut ui, KeyCode::Enter);
+        assert_eq!(ui.mode, Mode::Normal);
+        let todo = ui.data.lock().unwrap();
+        let filtered = todo.get_filtered_and_sorted(ToDoData::Pending);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].subject, "first task +work");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_paste_at_cursor() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.mode = Mode::Input;
+        ui.tinput = "first last".into();
+        for _ in 0.."last".len() {
+            ui.tinput.handle(InputRequest::GoToPrevChar);
+        }
+        ui.handle_event_window(Event::Paste(String::from("second\n")));
+        assert_eq!(ui.tinput.value(), "first second last");
+        assert_eq!(ui.tinput.cursor(), "first second ".len());
+
+        ui.mode = Mode::Normal;
+        ui.handle_event_window(Event::Paste(String::from("ignored")));
+        assert_eq!(ui.tinput.value(), "first second last");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_edit_subject() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("(B) 2023-04-30 old subject due:2023-06-30")?;
+            todo.set_active(ToDoData::Pending, 0);
+        }
+        ui.handle_event(UIEvent::EditSubjectMode);
+        assert_eq!(ui.mode, Mode::EditSubject);
+        assert_eq!(ui.tinput.value(), "old subject");
+        ui.tinput = "new subject".into();
+        ui.handle_event_window(Event::Key(KeyEvent::new(
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+        )));
+        assert_eq!(ui.mode, Mode::Normal);
+        assert_eq!(
+            ui.data.lock().unwrap().pending[0].to_string(),
+            "(B) 2023-04-30 new subject due:2023-06-30"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_append() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        ui.data.lock().unwrap().move_data(ToDo::default());
+        ui.handle_event(UIEvent::AppendMode);
+        assert_eq!(ui.mode, Mode::Normal);
+
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.new_task("(B) 2023-04-30 task due:2023-06-30")?;
+            todo.set_active(ToDoData::Pending, 0);
+        }
+        ui.handle_event(UIEvent::AppendMode);
+        assert_eq!(ui.mode, Mode::Append);
+        assert_eq!(ui.tinput.value(), "");
+        ui.handle_event_window(Event::Paste(String::from("+project")));
+        ui.handle_event_window(Event::Key(KeyEvent::new(
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+        )));
+        assert_eq!(ui.mode, Mode::Normal);
+        assert_eq!(
+            ui.data.lock().unwrap().pending[0].to_string(),
+            "(B) 2023-04-30 task +project due:2023-06-30"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("first task")?;
+            todo.new_task("second task")?;
+            todo.new_task("third task")?;
+        }
+        ui.handle_event(UIEvent::SearchMode);
+        assert_eq!(ui.mode, Mode::Search);
+        for c in "THIRD".chars() {
+            ui.handle_event_window(Event::Key(KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+            )));
+        }
+        ui.handle_event_window(Event::Key(KeyEvent::new(
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+        )));
+        assert_eq!(ui.mode, Mode::Normal);
+        assert_eq!(
+            ui.data
+                .lock()
+                .unwrap()
+                .get_search()
+                .map(|s| s.term().to_string()),
+            Some(String::from("THIRD"))
+        );
+
+        // Selected task is the found one.
+        ui.layout.handle_event(UIEvent::Select);
+        assert_eq!(
+            ui.data.lock().unwrap().get_active().unwrap().subject,
+            "third task"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_counter() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 50, 50));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            todo.new_task("first task")?;
+            todo.new_task("second")?;
+            todo.new_task("third task")?;
+        }
+        ui.layout.focus();
+        assert_eq!(ui.input_title(), "Input");
+
+        ui.handle_event(UIEvent::SearchMode);
+        assert_eq!(ui.input_title(), "Search");
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        for c in "task".chars() {
+            ui.handle_event_window(key(KeyCode::Char(c)));
+        }
+        assert_eq!(ui.input_title(), "Search 1/2");
+        ui.handle_event_window(key(KeyCode::Char('x')));
+        assert_eq!(ui.input_title(), "Search 0/0");
+
+        ui.handle_event_window(key(KeyCode::Backspace));
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert_eq!(ui.input_title(), "Input");
+        assert_eq!(ui.layout.get_active_position().map(|p| p.selected), Some(2));
+        ui.handle_event(UIEvent::SearchMode);
+        ui.handle_event_window(key(KeyCode::Char('s')));
+        assert_eq!(ui.input_title(), "Search 3/3");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_history() -> Result<(), Box<dyn Error>> {
+        let mut ui = default_ui()?;
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        for term in ["first", "second", "second", "third"] {
+            ui.handle_event(UIEvent::SearchMode);
+            for c in term.chars() {
+                ui.handle_event_window(key(KeyCode::Char(c)));
+            }
+            ui.handle_event_window(key(KeyCode::Enter));
+        }
+        assert_eq!(ui.search_history.entries(), ["first", "second", "third"]);
+
+        ui.handle_event(UIEvent::SearchMode);
+        ui.handle_event_window(key(KeyCode::Down));
+        assert_eq!(ui.tinput.value(), "");
+        for expected in ["third", "second", "first", "first"] {
+            ui.handle_event_window(key(KeyCode::Up));
+            assert_eq!(ui.tinput.value(), expected);
+        }
+        for expected in ["second", "third", ""] {
+            ui.handle_event_window(key(KeyCode::Down));
+            assert_eq!(ui.tinput.value(), expected);
+        }
+
+        // Recalled term can be searched again.
+        ui.handle_event_window(key(KeyCode::Up));
+        ui.handle_event_window(key(KeyCode::Up));
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert_eq!(
+            ui.data
+                .lock()
+                .unwrap()
+                .get_search()
+                .map(|s| s.term().to_string()),
+            Some(String::from("second"))
+        );
+        assert_eq!(
+            ui.search_history.entries(),
+            ["first", "second", "third", "second"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mouse_scroll() -> Result<(), Box<dyn Error>> {
+        use crossterm::event::MouseEvent;
+
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 80, 40));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            for i in 0..30 {
+                todo.new_task(&format!("task {}", i))?;
+            }
+            todo.new_task("x done task")?;
+            todo.new_task("x another done task")?;
+        }
+        let scroll = |kind, column, row| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let position = |ui: &UI, widget| {
+            ui.layout
+                .get_positions()
+                .into_iter()
+                .find(|(t, _)| *t == widget)
+                .map(|(_, p)| p.selected)
+        };
+
+        for _ in 0..3 {
+            ui.handle_event_window(scroll(MouseEventKind::ScrollDown, 5, 5));
+        }
+        ui.handle_event_window(scroll(MouseEventKind::ScrollUp, 5, 5));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::List);
+        assert_eq!(position(&ui, WidgetType::List), Some(2));
+
+        // Scrolled list gets the focus.
+        ui.handle_event_window(scroll(MouseEventKind::ScrollDown, 60, 5));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::Done);
+        assert_eq!(position(&ui, WidgetType::Done), Some(1));
+        assert_eq!(position(&ui, WidgetType::List), Some(2));
+
+        // Scrolling the preview does not move the lists, other events are ignored.
+        let positions = ui.layout.get_positions();
+        ui.handle_event_window(scroll(MouseEventKind::ScrollDown, 5, 30));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::Preview);
+        ui.handle_event_window(scroll(MouseEventKind::Moved, 5, 5));
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::Preview);
+        assert_eq!(ui.layout.get_positions(), positions);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_right_click_popup() -> Result<(), Box<dyn Error>> {
+        use crossterm::event::MouseEvent;
+        use std::str::FromStr;
+        use todo_txt::Task;
+
+        let mut ui = default_ui()?;
+        ui.update_chunk(Rect::new(0, 0, 80, 40));
+        {
+            let mut todo = ui.data.lock().unwrap();
+            todo.move_data(ToDo::default());
+            for i in 0..5 {
+                todo.add_task(Task::from_str(&format!("task {}", i))?);
+            }
+        }
+        let click = |button, column, row| {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(button),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let selected = |ui: &UI| {
+            ui.layout
+                .get_positions()
+                .into_iter()
+                .find(|(t, _)| *t == WidgetType::List)
+                .map(|(_, p)| p.selected)
+                .unwrap()
+        };
+        let top = (0..40)
+            .find(|row| ui.layout.find_widget(5, *row) == Some(WidgetType::List))
+            .unwrap();
+
+        // Right click on the border does not open the popup.
+        ui.handle_event_window(click(MouseButton::Right, 5, top));
+        assert!(ui.popup.is_none());
+
+        // Third task is selected and the popup is opened.
+        ui.handle_event_window(click(MouseButton::Right, 5, top + 3));
+        assert!(ui.popup.is_some());
+        assert_eq!(ui.layout.get_active_widget(), WidgetType::List);
+        assert_eq!(selected(&ui), 2);
+
+        // Keys are handled by the popup.
+        ui.handle_event_window(key(KeyCode::Char('j')));
+        assert_eq!(selected(&ui), 2);
+        assert_eq!(
+            ui.popup.as_ref().unwrap().selected(),
+            Some(UIEvent::RemoveItem)
+        );
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert!(ui.popup.is_none());
+        {
+            let todo = ui.data.lock().unwrap();
+            assert_eq!(todo.pending.len(), 4);
+            assert!(todo.pending.iter().all(|t| t.subject != "task 2"));
+        }
+
+        // Edit action sets the task active and starts editing.
+        ui.handle_event_window(click(MouseButton::Right, 5, top + 1));
+        for _ in 0..2 {
+            ui.handle_event_window(key(KeyCode::Down));
+        }
+        ui.handle_event_window(key(KeyCode::Enter));
+        assert_eq!(ui.mode, Mode::Edit);
+        assert_eq!(ui.tinput.value(), "task 0");
+        ui.handle_event_window(key(KeyCode::Esc));
+
+        // Escape closes the popup, left click only selects.
+        ui.handle_event_window(click(MouseButton::Right, 5, top + 1));
+        ui.handle_event_window(key(KeyCode::Esc));
+        assert!(ui.popup.is_none());
+        ui.handle_event_window(click(MouseButton::Left, 5, top + 2));
+        assert!(ui.popup.is_none());
+        assert_eq!(selected(&ui), 1);
+
+        Ok(())
+    }
 }
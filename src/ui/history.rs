@@ -0,0 +1,122 @@
+/// Maximal number of entries kept in the history.
+const HISTORY_SIZE: usize = 100;
+
+/// Bounded history of entered texts that can be browsed from the newest entry.
+#[derive(Default)]
+pub struct History {
+    entries: Vec<String>,
+    index: Option<usize>,
+}
+
+impl History {
+    /// Creates a history with the entries, the newest entry is the last one.
+    pub fn new(mut entries: Vec<String>) -> Self {
+        if entries.len() > HISTORY_SIZE {
+            entries.drain(..entries.len() - HISTORY_SIZE);
+        }
+        Self {
+            entries,
+            index: None,
+        }
+    }
+
+    /// Gets the entries, the newest entry is the last one.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Adds a new entry and stops browsing. Empty entries and entries equal
+    /// to the newest entry are not added.
+    pub fn push(&mut self, entry: &str) {
+        self.index = None;
+        if entry.is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        if self.entries.len() > HISTORY_SIZE {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Moves to the older entry.
+    ///
+    /// # Returns
+    ///
+    /// The older entry, the oldest one is returned repeatedly.
+    /// `None` if the history is empty.
+    pub fn prev(&mut self) -> Option<&str> {
+        let index = match self.index {
+            Some(index) => index.saturating_sub(1),
+            None => self.entries.len().checked_sub(1)?,
+        };
+        self.index = Some(index);
+        Some(&self.entries[index])
+    }
+
+    /// Moves to the newer entry.
+    ///
+    /// # Returns
+    ///
+    /// The newer entry, empty string if browsing moved past the newest entry.
+    /// `None` if the history is not browsed.
+    pub fn next(&mut self) -> Option<&str> {
+        let index = self.index?;
+        if index + 1 < self.entries.len() {
+            self.index = Some(index + 1);
+            Some(&self.entries[index + 1])
+        } else {
+            self.index = None;
+            Some("")
+        }
+    }
+
+    /// Stops browsing, next `prev` starts from the newest entry.
+    pub fn reset(&mut self) {
+        self.index = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browse() {
+        let mut history = History::default();
+        assert_eq!(history.prev(), None);
+        assert_eq!(history.next(), None);
+
+        history.push("first");
+        history.push("second");
+        history.push("second");
+        history.push("");
+        history.push("third");
+        assert_eq!(history.entries(), ["first", "second", "third"]);
+
+        assert_eq!(history.next(), None);
+        assert_eq!(history.prev(), Some("third"));
+        assert_eq!(history.prev(), Some("second"));
+        assert_eq!(history.prev(), Some("first"));
+        assert_eq!(history.prev(), Some("first"));
+        assert_eq!(history.next(), Some("second"));
+        assert_eq!(history.next(), Some("third"));
+        assert_eq!(history.next(), Some(""));
+        assert_eq!(history.next(), None);
+
+        history.prev();
+        history.push("first");
+        assert_eq!(history.prev(), Some("first"));
+    }
+
+    #[test]
+    fn bounded() {
+        let mut history = History::new((0..HISTORY_SIZE + 5).map(|i| i.to_string()).collect());
+        assert_eq!(history.entries().len(), HISTORY_SIZE);
+        assert_eq!(history.entries()[0], "5");
+
+        history.push("new");
+        assert_eq!(history.entries().len(), HISTORY_SIZE);
+        assert_eq!(history.entries()[0], "6");
+        assert_eq!(history.prev(), Some("new"));
+    }
+}
@@ -0,0 +1,275 @@
+use super::UIEvent;
+use crossterm::event::KeyCode;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Result of a key pressed in the popup.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PopupResult {
+    /// The popup stays open.
+    Open,
+    /// The popup is closed without any action.
+    Close,
+    /// The popup is closed and the event of the selected entry should be handled.
+    Select(UIEvent),
+}
+
+/// Small menu of actions or a text displayed over the layout at a position.
+pub struct Popup {
+    title: String,
+    entries: Vec<(String, UIEvent)>,
+    /// Keys selecting the entry on the same position.
+    hotkeys: Vec<char>,
+    text: Vec<Vec<(String, Style)>>,
+    selected: usize,
+    column: u16,
+    row: u16,
+}
+
+impl Popup {
+    /// Creates a new popup.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - Title of the popup.
+    /// * `entries` - Names of the entries and events they trigger.
+    /// * `column` - Column of the top left corner.
+    /// * `row` - Row of the top left corner.
+    pub fn new(title: &str, entries: Vec<(String, UIEvent)>, column: u16, row: u16) -> Self {
+        Self {
+            title: title.to_string(),
+            entries,
+            hotkeys: Vec::new(),
+            text: Vec::new(),
+            selected: 0,
+            column,
+            row,
+        }
+    }
+
+    /// Creates a new popup showing a text without any entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - Title of the popup.
+    /// * `text` - Lines of styled parts of the text.
+    /// * `column` - Column of the top left corner.
+    /// * `row` - Row of the top left corner.
+    pub fn text(title: &str, text: Vec<Vec<(String, Style)>>, column: u16, row: u16) -> Self {
+        Self {
+            text,
+            ..Self::new(title, Vec::new(), column, row)
+        }
+    }
+
+    /// Sets keys that select entries directly, the key on a position selects
+    /// the entry on the same position. Hotkeys take precedence over the keys
+    /// used to move in the popup.
+    ///
+    /// # Arguments
+    ///
+    /// * `hotkeys` - Keys of the entries in the order of the entries.
+    pub fn with_hotkeys(mut self, hotkeys: &[char]) -> Self {
+        self.hotkeys = hotkeys.to_vec();
+        self
+    }
+
+    /// Gets the event of the selected entry.
+    pub fn selected(&self) -> Option<UIEvent> {
+        self.entries
+            .get(self.selected)
+            .map(|(_, event)| event.clone())
+    }
+
+    /// Gets lines of the text without styles.
+    #[cfg(test)]
+    pub fn text_lines(&self) -> Vec<String> {
+        self.text
+            .iter()
+            .map(|line| line.iter().map(|(text, _)| text.as_str()).collect())
+            .collect()
+    }
+
+    /// Handles a key pressed while the popup is open.
+    pub fn handle_key(&mut self, key: &KeyCode) -> PopupResult {
+        if let KeyCode::Char(c) = key {
+            let entry = self.hotkeys.iter().position(|hotkey| hotkey == c);
+            if let Some((_, event)) = entry.and_then(|index| self.entries.get(index)) {
+                return PopupResult::Select(event.clone());
+            }
+        }
+        match key {
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                return match self.selected() {
+                    Some(event) => PopupResult::Select(event),
+                    None => PopupResult::Close,
+                };
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return PopupResult::Close,
+            _ => {}
+        }
+        PopupResult::Open
+    }
+
+    /// Gets the area of the popup moved and shrunk to fit in the area.
+    fn chunk(&self, area: Rect) -> Rect {
+        let width = (self
+            .entries
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .chain(
+                self.text
+                    .iter()
+                    .map(|line| line.iter().map(|(text, _)| text.chars().count()).sum()),
+            )
+            .chain([self.title.chars().count()])
+            .max()
+            .unwrap_or(0) as u16
+            + 2)
+        .min(area.width);
+        let height = ((self.entries.len() + self.text.len()) as u16 + 2).min(area.height);
+        Rect::new(
+            self.column.min(area.right() - width).max(area.x),
+            self.row.min(area.bottom() - height).max(area.y),
+            width,
+            height,
+        )
+    }
+
+    /// Renders the popup over everything in the area.
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect, active_color: Color) {
+        let chunk = self.chunk(area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title.as_str())
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(active_color));
+        f.render_widget(Clear, chunk);
+        if !self.text.is_empty() {
+            let lines: Vec<Line> = self
+                .text
+                .iter()
+                .map(|line| {
+                    Line::from(
+                        line.iter()
+                            .map(|(text, style)| Span::styled(text.as_str(), *style))
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect();
+            f.render_widget(Paragraph::new(lines).block(block), chunk);
+            return;
+        }
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|(name, _)| ListItem::new(name.as_str()))
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(active_color));
+        let mut state = ListState::default();
+        state.select(Some(self.selected));
+        f.render_stateful_widget(list, chunk, &mut state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn testing_popup() -> Popup {
+        Popup::new(
+            "Task",
+            vec![
+                (String::from("Complete"), UIEvent::MoveItem),
+                (String::from("Delete"), UIEvent::RemoveItem),
+            ],
+            70,
+            5,
+        )
+    }
+
+    #[test]
+    fn handle_key() {
+        let mut popup = testing_popup();
+        assert_eq!(popup.handle_key(&KeyCode::Up), PopupResult::Open);
+        assert_eq!(popup.selected(), Some(UIEvent::MoveItem));
+        assert_eq!(popup.handle_key(&KeyCode::Char('j')), PopupResult::Open);
+        assert_eq!(popup.handle_key(&KeyCode::Down), PopupResult::Open);
+        assert_eq!(popup.selected(), Some(UIEvent::RemoveItem));
+        assert_eq!(popup.handle_key(&KeyCode::Char('x')), PopupResult::Open);
+        assert_eq!(
+            popup.handle_key(&KeyCode::Enter),
+            PopupResult::Select(UIEvent::RemoveItem)
+        );
+        assert_eq!(popup.handle_key(&KeyCode::Esc), PopupResult::Close);
+
+        let mut popup = Popup::new("Empty", Vec::new(), 0, 0);
+        assert_eq!(popup.handle_key(&KeyCode::Enter), PopupResult::Close);
+    }
+
+    #[test]
+    fn hotkeys() {
+        let mut popup = testing_popup().with_hotkeys(&['c', 'q']);
+        assert_eq!(
+            popup.handle_key(&KeyCode::Char('q')),
+            PopupResult::Select(UIEvent::RemoveItem)
+        );
+        assert_eq!(
+            popup.handle_key(&KeyCode::Char('c')),
+            PopupResult::Select(UIEvent::MoveItem)
+        );
+        assert_eq!(popup.handle_key(&KeyCode::Char('j')), PopupResult::Open);
+        assert_eq!(popup.selected(), Some(UIEvent::RemoveItem));
+    }
+
+    #[test]
+    fn chunk() {
+        let popup = testing_popup();
+        assert_eq!(
+            popup.chunk(Rect::new(0, 0, 80, 40)),
+            Rect::new(70, 5, 10, 4)
+        );
+        assert_eq!(popup.chunk(Rect::new(0, 0, 75, 6)), Rect::new(65, 2, 10, 4));
+        assert_eq!(popup.chunk(Rect::new(0, 0, 5, 3)), Rect::new(0, 0, 5, 3));
+
+        let popup = Popup::text(
+            "Detail",
+            vec![
+                vec![
+                    (String::from("Subject: "), Style::default()),
+                    (String::from("task"), Style::default()),
+                ],
+                vec![(String::from("Due: 2023-06-30"), Style::default())],
+            ],
+            10,
+            10,
+        );
+        assert_eq!(
+            popup.chunk(Rect::new(0, 0, 80, 40)),
+            Rect::new(10, 10, 17, 4)
+        );
+    }
+
+    #[test]
+    fn text_popup_closes() {
+        let mut popup = Popup::text("Detail", vec![vec![]], 0, 0);
+        assert_eq!(popup.selected(), None);
+        assert_eq!(popup.handle_key(&KeyCode::Down), PopupResult::Open);
+        assert_eq!(popup.handle_key(&KeyCode::Enter), PopupResult::Close);
+        assert_eq!(popup.handle_key(&KeyCode::Char('q')), PopupResult::Close);
+    }
+}
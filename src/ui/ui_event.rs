@@ -5,12 +5,16 @@ use event_entry::EventEntry;
 use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, str::FromStr};
 
-use crate::ToDoError;
+use crate::{layout::widget::widget_type::WidgetType, todo::ToDoCategory, ToDoError};
 
 /// Enum representing various UI events that can be triggered.
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Copy, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
 pub enum UIEvent {
     Quit, // Window
+    /// Saves the todo list and quits, used by the quit confirmation popup.
+    SaveAndQuit,
+    /// Quits without saving, used by the quit confirmation popup.
+    ForceQuit,
     Save,
     Load,
     MoveLeft,
@@ -19,6 +23,37 @@ pub enum UIEvent {
     MoveDown,
     InsertMode,
     EditMode,
+    EditSubjectMode,
+    AppendMode,
+    SwitchFile,
+    SearchMode,
+    ToggleWrap,
+    FilterDue,
+    FilterNoDue,
+    FilterDueToday,
+    FilterOverdue,
+    FilterDueThisWeek,
+    ClearFilters,
+    SaveFilterPreset,
+    ApplyFilterPreset,
+    MergeFile,
+    ShowDetail,
+    OpenLink,
+    CompleteAllFiltered,
+    /// Completes all filtered tasks without asking, used by the confirmation popup.
+    CompleteAllFilteredConfirmed,
+    /// Moves the focus directly to the widget of the type.
+    FocusWidget(WidgetType),
+    /// Toggles the selection filter of the category with the name.
+    ToggleFilter {
+        category: ToDoCategory,
+        name: String,
+    },
+    FocusList,
+    FocusDone,
+    FocusProjects,
+    FocusContexts,
+    FocusHashtags,
 
     ListDown, // Widget list
     ListUp,
@@ -26,14 +61,48 @@ pub enum UIEvent {
     ListLast,
     SwapUpItem, // State list
     SwapDownItem,
+    MoveToTop,
+    MoveToBottom,
     RemoveItem,
     MoveItem,
+    ToggleNow,
+    TogglePin,
+    CycleSort,
+    ToggleSortOrder,
+    ToggleDone,
+    PriorityUp,
+    PriorityDown,
+    NextSearch,
+    PrevSearch,
     Select, // State categories + State list
     Remove, // State categories
+    ToggleUseDone,
+    InvertFilters,
     // State preview
     None, // without bind
 }
 
+impl UIEvent {
+    /// Checks if the event changes tasks, not only the view of them.
+    pub fn changes_tasks(&self) -> bool {
+        use UIEvent::*;
+        matches!(
+            self,
+            SwapUpItem
+                | SwapDownItem
+                | MoveToTop
+                | MoveToBottom
+                | RemoveItem
+                | MoveItem
+                | ToggleNow
+                | TogglePin
+                | ToggleDone
+                | PriorityUp
+                | PriorityDown
+        )
+    }
+}
+
 impl FromStr for UIEvent {
     type Err = ToDoError;
 
@@ -49,6 +118,28 @@ impl FromStr for UIEvent {
             "MoveDown" => MoveDown,
             "InsertMode" => InsertMode,
             "EditMode" => EditMode,
+            "EditSubjectMode" => EditSubjectMode,
+            "AppendMode" => AppendMode,
+            "SwitchFile" => SwitchFile,
+            "SearchMode" => SearchMode,
+            "ToggleWrap" => ToggleWrap,
+            "FilterDue" => FilterDue,
+            "FilterNoDue" => FilterNoDue,
+            "FilterDueToday" => FilterDueToday,
+            "FilterOverdue" => FilterOverdue,
+            "FilterDueThisWeek" => FilterDueThisWeek,
+            "ClearFilters" => ClearFilters,
+            "SaveFilterPreset" => SaveFilterPreset,
+            "ApplyFilterPreset" => ApplyFilterPreset,
+            "MergeFile" => MergeFile,
+            "ShowDetail" => ShowDetail,
+            "OpenLink" => OpenLink,
+            "CompleteAllFiltered" => CompleteAllFiltered,
+            "FocusList" => FocusList,
+            "FocusDone" => FocusDone,
+            "FocusProjects" => FocusProjects,
+            "FocusContexts" => FocusContexts,
+            "FocusHashtags" => FocusHashtags,
 
             "ListDown" => ListDown,
             "ListUp" => ListUp,
@@ -56,10 +147,42 @@ impl FromStr for UIEvent {
             "ListLast" => ListLast,
             "SwapUpItem" => SwapUpItem,
             "SwapDownItem" => SwapDownItem,
+            "MoveToTop" => MoveToTop,
+            "MoveToBottom" => MoveToBottom,
             "RemoveItem" => RemoveItem,
             "MoveItem" => MoveItem,
+            "ToggleNow" => ToggleNow,
+            "TogglePin" => TogglePin,
+            "CycleSort" => CycleSort,
+            "ToggleSortOrder" => ToggleSortOrder,
+            "ToggleDone" => ToggleDone,
+            "PriorityUp" => PriorityUp,
+            "PriorityDown" => PriorityDown,
+            "NextSearch" => NextSearch,
+            "PrevSearch" => PrevSearch,
             "Select" => Select,
+            "Remove" => Remove,
+            "ToggleUseDone" => ToggleUseDone,
+            "InvertFilters" => InvertFilters,
             "None" => None,
+            _ if s.starts_with("FocusWidget(") && s.ends_with(')') => {
+                FocusWidget(s["FocusWidget(".len()..s.len() - 1].parse()?)
+            }
+            _ if s.starts_with("ToggleFilter(") && s.ends_with(')') => {
+                let (category, name) = s["ToggleFilter(".len()..s.len() - 1]
+                    .split_once(',')
+                    .ok_or_else(|| ToDoError::ParseToggleFilter(s.to_string()))?;
+                let category: ToDoCategory = category.trim().parse()?;
+                let name = name.trim();
+                let name = name.strip_prefix(category.prefix()).unwrap_or(name);
+                if name.is_empty() {
+                    return Err(ToDoError::ParseToggleFilter(s.to_string()));
+                }
+                ToggleFilter {
+                    category,
+                    name: name.to_string(),
+                }
+            }
 
             _ => todo!(), // Error TODO
         })
@@ -140,7 +263,7 @@ impl EventHandlerUI {
     /// The UI event corresponding to the key code.
     pub fn get_event(&self, key: &KeyCode) -> UIEvent {
         match self.events.binary_search_by(|a| Self::compare(&a.key, key)) {
-            Ok(index) => self.events[index].event,
+            Ok(index) => self.events[index].event.clone(),
             Err(_) => UIEvent::None,
         }
     }
@@ -165,3 +288,34 @@ impl EventHandlerUI {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_toggle_filter() -> Result<(), ToDoError> {
+        assert_eq!(
+            UIEvent::from_str("ToggleFilter(Contexts, urgent)")?,
+            UIEvent::ToggleFilter {
+                category: ToDoCategory::Contexts,
+                name: String::from("urgent")
+            }
+        );
+        assert_eq!(
+            UIEvent::from_str("ToggleFilter(projects,+work)")?,
+            UIEvent::ToggleFilter {
+                category: ToDoCategory::Projects,
+                name: String::from("work")
+            }
+        );
+        assert_eq!(
+            UIEvent::from_str("ToggleFilter(Tags, urgent)"),
+            Err(ToDoError::ParseCategory(String::from("Tags")))
+        );
+        assert!(UIEvent::from_str("ToggleFilter(Contexts)").is_err());
+        assert!(UIEvent::from_str("ToggleFilter(Contexts, )").is_err());
+
+        Ok(())
+    }
+}
@@ -37,7 +37,7 @@ impl From<&(KeyCode, UIEvent)> for EventEntry {
     fn from(value: &(KeyCode, UIEvent)) -> Self {
         Self {
             key: value.0,
-            event: value.1,
+            event: value.1.clone(),
         }
     }
 }
@@ -1,29 +1,82 @@
-use std::fs::File;
+use std::fs::{create_dir_all, File};
 use std::io::{Read, Result as ioResult, Write};
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use super::History;
 use crate::error::{ToDoIoError, ToDoRes};
-use crate::layout::widget::widget_type::WidgetType;
+use crate::layout::widget::{widget_type::WidgetType, ListPosition};
 use crate::layout::Layout;
 use crate::todo::{ToDo, ToDoState};
 
+/// Scroll position of a list widget.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct WidgetPosition {
+    pub widget: WidgetType,
+    #[serde(flatten)]
+    pub position: ListPosition,
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct UIState {
     pub active: WidgetType,
     pub todo_state: ToDoState,
+    /// Text of the active task, used to find the task again after the tasks are loaded.
+    #[serde(default)]
+    pub active_task: Option<String>,
+    #[serde(default)]
+    pub positions: Vec<WidgetPosition>,
+    /// Previous search terms, the newest is the last one.
+    #[serde(default)]
+    pub search_history: Vec<String>,
 }
 
 impl UIState {
-    pub fn new(layout: &Layout, todo: &ToDo) -> Self {
+    pub fn new(layout: &Layout, todo: &ToDo, search_history: &History) -> Self {
         Self {
             active: layout.get_active_widget(),
             todo_state: todo.get_state().clone(),
+            active_task: todo.get_active().map(|task| task.to_string()),
+            positions: layout
+                .get_positions()
+                .into_iter()
+                .map(|(widget, position)| WidgetPosition { widget, position })
+                .collect(),
+            search_history: search_history.entries().to_vec(),
+        }
+    }
+
+    /// Restores the state of the tasks. Must be called after the tasks are loaded,
+    /// the active task is kept only if it still exists.
+    pub fn restore_todo(&self, todo: &mut ToDo) {
+        let mut state = self.todo_state.clone();
+        state.active = None;
+        todo.update_state(state);
+        if let (Some((data, index)), Some(task)) = (self.todo_state.active, &self.active_task) {
+            todo.set_active_task(data, index, task);
+        }
+    }
+
+    /// Restores the focused widget and scroll positions of lists.
+    /// Positions are clamped to the current length of lists.
+    pub fn restore_layout(&self, layout: &mut Layout) {
+        if let Err(e) = layout.select_widget(self.active) {
+            log::warn!("Cannot restore active widget {}: {}", self.active, e);
         }
+        let positions: Vec<_> = self
+            .positions
+            .iter()
+            .map(|p| (p.widget, p.position))
+            .collect();
+        layout.set_positions(&positions);
     }
 
     pub fn save(&self, path: &Path) -> ioResult<()> {
+        if let Some(folder) = path.parent().filter(|f| !f.as_os_str().is_empty()) {
+            create_dir_all(folder)?;
+        }
         self.serialize(&mut File::create(path)?)
     }
 
@@ -54,3 +107,132 @@ impl UIState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::layout::Render;
+    use crate::test_utils::TempDir;
+    use crate::todo::ToDoData;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use todo_txt::Task;
+    use tui::layout::Rect;
+
+    fn testing_layout(todo: &Arc<Mutex<ToDo>>) -> ToDoRes<Layout> {
+        let mut layout = Layout::from_str(
+            "[Direction: Horizontal, List, Done,]",
+            todo.clone(),
+            &Config::default(),
+        )?;
+        layout.update_chunk(Rect::new(0, 0, 40, 12));
+        Ok(layout)
+    }
+
+    #[test]
+    fn round_trip() -> ToDoRes<()> {
+        let mut todo = ToDo::default();
+        for i in 0..30 {
+            todo.add_task(Task::from_str(&format!("Task {}", i)).unwrap());
+        }
+        todo.add_task(Task::from_str("x Done task").unwrap());
+        todo.set_active(ToDoData::Pending, 15);
+        let todo = Arc::new(Mutex::new(todo));
+
+        let mut layout = testing_layout(&todo)?;
+        let position = ListPosition {
+            first: 12,
+            selected: 15,
+        };
+        layout.set_positions(&[(WidgetType::List, position)]);
+        layout.select_widget(WidgetType::Done)?;
+
+        let mut buf = Vec::new();
+        let history = History::new(vec![String::from("task"), String::from("project")]);
+        UIState::new(&layout, &todo.lock().unwrap(), &history)
+            .serialize(&mut buf)
+            .unwrap();
+        let state = UIState::deserialize(buf.as_slice());
+        assert_eq!(state.active, WidgetType::Done);
+        assert_eq!(state.active_task.as_deref(), Some("Task 15"));
+        assert_eq!(state.search_history, ["task", "project"]);
+        assert_eq!(
+            state.positions[0],
+            WidgetPosition {
+                widget: WidgetType::List,
+                position
+            }
+        );
+
+        // Active task moved and the list got shorter.
+        let mut new_todo = ToDo::default();
+        for i in 10..30 {
+            new_todo.add_task(Task::from_str(&format!("Task {}", i)).unwrap());
+        }
+        state.restore_todo(&mut new_todo);
+        assert_eq!(new_todo.get_active().unwrap().subject, "Task 15");
+        assert_eq!(new_todo.get_state().active, Some((ToDoData::Pending, 5)));
+        let new_todo = Arc::new(Mutex::new(new_todo));
+
+        let mut layout = testing_layout(&new_todo)?;
+        state.restore_layout(&mut layout);
+        assert_eq!(layout.get_active_widget(), WidgetType::Done);
+        assert_eq!(layout.get_positions()[0], (WidgetType::List, position));
+
+        for index in (14..20).rev() {
+            new_todo
+                .lock()
+                .unwrap()
+                .remove_task(ToDoData::Pending, index);
+        }
+        let mut layout = testing_layout(&new_todo)?;
+        state.restore_layout(&mut layout);
+        assert_eq!(
+            layout.get_positions()[0],
+            (
+                WidgetType::List,
+                ListPosition {
+                    first: 12,
+                    selected: 13
+                }
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_creates_folder() -> ToDoRes<()> {
+        let dir = TempDir::new("ui-state");
+        let path = dir.join("todotxt-tui").join("state.toml");
+        let todo = Arc::new(Mutex::new(ToDo::default()));
+        let layout = testing_layout(&todo)?;
+        UIState::new(&layout, &todo.lock().unwrap(), &History::new(Vec::new()))
+            .save(&path)
+            .unwrap();
+        assert_eq!(UIState::load(&path)?.active, layout.get_active_widget());
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_active_task() {
+        let state = UIState::deserialize(
+            r#"
+            active = "List"
+            [todo_state]
+            active = ["Pending", 3]
+            [todo_state.project_filters]
+            [todo_state.context_filters]
+            [todo_state.hashtag_filters]
+            "#
+            .as_bytes(),
+        );
+        let mut todo = ToDo::default();
+        todo.new_task("Task").unwrap();
+        state.restore_todo(&mut todo);
+        assert!(todo.get_active().is_none());
+        assert!(state.positions.is_empty());
+    }
+}
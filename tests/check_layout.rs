@@ -0,0 +1,49 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+/// Runs the application with `--check-layout` and the arguments,
+/// the configuration folders point to an empty temporary folder.
+fn check_layout(name: &str, args: &[&str]) -> (bool, String) {
+    let home: PathBuf = env::temp_dir().join(format!(
+        "todotxt-tui-check-layout-{}-{}",
+        name,
+        std::process::id()
+    ));
+    fs::create_dir_all(&home).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_todotxt-tui"))
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", &home)
+        .env("XDG_STATE_HOME", &home)
+        .arg("--check-layout")
+        .args(args)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&home).unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    (output.status.success(), stderr)
+}
+
+#[test]
+fn valid_layout() {
+    let (success, stderr) = check_layout("valid", &[]);
+    assert!(success, "{stderr}");
+
+    let (success, stderr) = check_layout(
+        "valid_custom",
+        &["--layout", "[Direction: Vertical, List: 50%, Done: 50%]"],
+    );
+    assert!(success, "{stderr}");
+}
+
+#[test]
+fn invalid_layout() {
+    let (success, stderr) = check_layout("not_closed", &["--layout", "[List: 50%, Done: 50%"]);
+    assert!(!success);
+    assert!(
+        stderr.contains("All containers must be closed."),
+        "{stderr}"
+    );
+
+    let (success, stderr) = check_layout("unknown", &["--layout", "[Lsit: 50%]"]);
+    assert!(!success);
+    assert!(stderr.starts_with("Invalid configuration"), "{stderr}");
+}
@@ -0,0 +1,34 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+#[test]
+fn add_saves_task() {
+    let home: PathBuf =
+        env::temp_dir().join(format!("todotxt-tui-headless-add-{}", std::process::id()));
+    fs::create_dir_all(&home).unwrap();
+    let todo_path = home.join("todo.txt");
+    fs::copy(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_files/count_todo.txt"),
+        &todo_path,
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_todotxt-tui"))
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", &home)
+        .env("XDG_STATE_HOME", &home)
+        .arg("--todo-path")
+        .arg(&todo_path)
+        // The task is saved even if the user interface would save it later.
+        .args(["--save-policy", "timer"])
+        .args(["add", "new task +headless"])
+        .output()
+        .unwrap();
+    let content = fs::read_to_string(&todo_path).unwrap();
+    fs::remove_dir_all(&home).unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+
+    let lines: Vec<_> = content.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[3].ends_with(" new task +headless"), "{content}");
+    assert_eq!(lines[4], "x 2023-02-01 2023-01-01 done task");
+}